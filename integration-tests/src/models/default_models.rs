@@ -48,6 +48,7 @@ pub struct TableMetadata {
     pub key_type: String,
     pub value_type: String,
     pub inserted_at: chrono::NaiveDateTime,
+    pub owner_address: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize, Queryable)]
@@ -36,6 +36,7 @@ pub fn setup_events_processor_config(
                 override_starting_version: transaction_stream_config.starting_version.unwrap(),
                 ending_version: transaction_stream_config.request_ending_version,
             }),
+            additional_processor_configs: vec![],
         },
         processor_name,
     )
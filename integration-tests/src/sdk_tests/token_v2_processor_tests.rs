@@ -14,6 +14,14 @@ use std::collections::HashSet;
 pub fn setup_token_v2_processor_config(
     test_context: &SdkTestContext,
     db_url: &str,
+) -> (IndexerProcessorConfig, &'static str) {
+    setup_token_v2_processor_config_with_tables(test_context, db_url, HashSet::new())
+}
+
+pub fn setup_token_v2_processor_config_with_tables(
+    test_context: &SdkTestContext,
+    db_url: &str,
+    tables_to_write: HashSet<String>,
 ) -> (IndexerProcessorConfig, &'static str) {
     let transaction_stream_config = test_context.create_transaction_stream_config();
     let postgres_config = PostgresConfig {
@@ -25,7 +33,7 @@ pub fn setup_token_v2_processor_config(
     let default_processor_config = DefaultProcessorConfig {
         per_table_chunk_sizes: AHashMap::new(),
         channel_size: 100,
-        tables_to_write: HashSet::new(),
+        tables_to_write,
     };
     let token_v2_processor_config = TokenV2ProcessorConfig {
         default_config: default_processor_config,
@@ -46,6 +54,7 @@ pub fn setup_token_v2_processor_config(
                 override_starting_version: transaction_stream_config.starting_version.unwrap(),
                 ending_version: transaction_stream_config.request_ending_version,
             }),
+            additional_processor_configs: vec![],
         },
         processor_name,
     )
@@ -54,13 +63,14 @@ pub fn setup_token_v2_processor_config(
 #[allow(clippy::needless_return)]
 #[cfg(test)]
 mod sdk_token_v2_processor_tests {
-    use super::setup_token_v2_processor_config;
+    use super::{setup_token_v2_processor_config, setup_token_v2_processor_config_with_tables};
     use crate::{
         diff_test_helper::token_v2_processor::load_data,
         sdk_tests::test_helpers::{
             run_processor_test, setup_test_environment, validate_json, DEFAULT_OUTPUT_FOLDER,
         },
     };
+    use diesel::{pg::PgConnection, QueryDsl, RunQueryDsl};
     use libra2_indexer_processor_sdk::testing_framework::{
         cli_parser::get_test_config, database::TestDatabase,
     };
@@ -84,7 +94,15 @@ mod sdk_token_v2_processor_tests {
         IMPORTED_MAINNET_TXNS_97963136_TOKEN_V1_CANCEL_OFFER,
         IMPORTED_MAINNET_TXNS_999930475_TOKEN_V2_CONCURRENT_MINT,
     };
-    use processor::processors::token_v2::token_v2_processor::TokenV2Processor;
+    use processor::{
+        processors::token_v2::token_v2_processor::TokenV2Processor,
+        schema::{
+            collections_v2::dsl as cv2_dsl, current_token_datas_v2::dsl as ctdv2_dsl,
+            token_datas_v2::dsl as tdv2_dsl, token_ownerships_v2::dsl as tov2_dsl,
+        },
+    };
+    use serde_json::Value;
+    use std::collections::HashMap;
 
     /**
     * This test includes processing for the following:
@@ -396,6 +414,96 @@ mod sdk_token_v2_processor_tests {
         .await;
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_token_v2_history_tables_are_opt_in() {
+        let row_counts = process_with_tables(
+            IMPORTED_MAINNET_TXNS_537250181_TOKEN_V2_FIXED_SUPPLY_MINT,
+            &[],
+        )
+        .await;
+        assert_eq!(row_counts["collections_v2"], 0);
+        assert_eq!(row_counts["token_datas_v2"], 0);
+        assert_eq!(row_counts["token_ownerships_v2"], 0);
+        assert!(row_counts["current_token_datas_v2"].as_i64().unwrap() > 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_token_v2_writes_opted_in_history_tables() {
+        let row_counts = process_with_tables(
+            IMPORTED_MAINNET_TXNS_537250181_TOKEN_V2_FIXED_SUPPLY_MINT,
+            &["COLLECTIONS_V2", "TOKEN_DATAS_V2", "TOKEN_OWNERSHIPS_V2"],
+        )
+        .await;
+        assert!(row_counts["collections_v2"].as_i64().unwrap() > 0);
+        assert!(row_counts["token_datas_v2"].as_i64().unwrap() > 0);
+        assert!(row_counts["token_ownerships_v2"].as_i64().unwrap() > 0);
+        // The current tables aren't listed, so they're not written
+        assert_eq!(row_counts["current_token_datas_v2"], 0);
+    }
+
+    /// Counts the rows of the history tables, and of a current table to compare with.
+    fn load_row_counts(conn: &mut PgConnection) -> anyhow::Result<HashMap<String, Value>> {
+        Ok(HashMap::from([
+            (
+                "collections_v2".to_string(),
+                cv2_dsl::collections_v2
+                    .count()
+                    .get_result::<i64>(conn)?
+                    .into(),
+            ),
+            (
+                "token_datas_v2".to_string(),
+                tdv2_dsl::token_datas_v2
+                    .count()
+                    .get_result::<i64>(conn)?
+                    .into(),
+            ),
+            (
+                "token_ownerships_v2".to_string(),
+                tov2_dsl::token_ownerships_v2
+                    .count()
+                    .get_result::<i64>(conn)?
+                    .into(),
+            ),
+            (
+                "current_token_datas_v2".to_string(),
+                ctdv2_dsl::current_token_datas_v2
+                    .count()
+                    .get_result::<i64>(conn)?
+                    .into(),
+            ),
+        ]))
+    }
+
+    /// Runs the processor with `tables_to_write` and returns the row counts of `load_row_counts`.
+    async fn process_with_tables(txn: &[u8], tables_to_write: &[&str]) -> HashMap<String, Value> {
+        let (db, mut test_context) = setup_test_environment(&[txn]).await;
+        let db_url = db.get_db_url();
+        let (indexer_processor_config, _) = setup_token_v2_processor_config_with_tables(
+            &test_context,
+            &db_url,
+            tables_to_write
+                .iter()
+                .map(|table| table.to_string())
+                .collect(),
+        );
+        let token_v2_processor = TokenV2Processor::new(indexer_processor_config)
+            .await
+            .expect("Failed to create TokenV2Processor");
+
+        run_processor_test(
+            &mut test_context,
+            token_v2_processor,
+            load_row_counts,
+            db_url,
+            false,
+            DEFAULT_OUTPUT_FOLDER.to_string(),
+            None,
+        )
+        .await
+        .expect("Failed to run TokenV2Processor")
+    }
+
     // Helper function to abstract out the transaction processing
     async fn process_single_transaction(txn: &[u8], test_case_name: Option<String>) {
         let (generate_flag, custom_output_path) = get_test_config();
@@ -0,0 +1,465 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    config::{
+        db_config::DbConfig, indexer_processor_config::IndexerProcessorConfig,
+        processor_config::ProcessorName,
+    },
+    utils::{
+        counters::{AUDIT_LAST_RUN_TIMESTAMP_SECS, AUDIT_MISMATCH_COUNT},
+        table_flags::TableFlags,
+    },
+};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::postgres::utils::database::{new_db_pool, DbPoolConnection};
+use clap::Args;
+use diesel::{
+    sql_types::{BigInt, Text},
+    QueryableByName,
+};
+use diesel_async::RunQueryDsl;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Response, Server,
+};
+use prometheus::{Encoder, TextEncoder};
+use serde::Serialize;
+use std::{convert::Infallible, path::PathBuf, time::Duration};
+use tracing::{error, info, warn};
+
+#[derive(Clone, Debug, Args)]
+pub struct AuditArgs {
+    /// Only audit current rows last updated at or after this version.
+    #[clap(long, default_value_t = 0)]
+    pub start_version: u64,
+    /// Maximum number of mismatched rows to include in the report for each check.
+    #[clap(long, default_value_t = 10)]
+    pub sample_size: u64,
+    /// Write the JSON report to this file instead of stdout.
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+    /// Run the audit again every `interval_secs` instead of exiting after the first run.
+    #[clap(long)]
+    pub interval_secs: Option<u64>,
+    /// Serve the audit gauges on `/metrics` on this port while the command runs.
+    #[clap(long)]
+    pub metrics_port: Option<u16>,
+}
+
+/// An invariant between the tables of a processor.
+///
+/// `query` selects one `mismatch` JSON text column per row that violates the invariant. `$1` is
+/// bound to the first version to audit.
+#[derive(Clone, Debug)]
+pub struct AuditCheck {
+    pub name: &'static str,
+    pub description: &'static str,
+    query: &'static str,
+    /// Opt-in table read by the query. The check is skipped unless it's in tables_to_write, since
+    /// every row would be reported as a mismatch otherwise.
+    opt_in_table: Option<TableFlags>,
+}
+
+impl AuditCheck {
+    /// Get the checks for the tables written by a processor.
+    pub fn for_processor(processor: ProcessorName) -> Result<Vec<Self>> {
+        let checks = match processor {
+            ProcessorName::FungibleAssetProcessor => vec![
+                AuditCheck {
+                    name: "current_fungible_asset_balances_version_in_history",
+                    description: "The last v1 and v2 versions of every current balance have a \
+                                  fungible_asset_balances row",
+                    query: "SELECT json_build_object( \
+                                'storage_id', c.storage_id, \
+                                'last_transaction_version_v1', c.last_transaction_version_v1, \
+                                'last_transaction_version_v2', c.last_transaction_version_v2 \
+                            )::text AS mismatch \
+                            FROM current_fungible_asset_balances c \
+                            WHERE c.last_transaction_version >= $1 AND ( \
+                                (c.last_transaction_version_v1 IS NOT NULL AND NOT EXISTS ( \
+                                    SELECT 1 FROM fungible_asset_balances h \
+                                    WHERE h.transaction_version = c.last_transaction_version_v1 \
+                                    AND h.storage_id = c.storage_id AND h.token_standard = 'v1' \
+                                )) OR (c.last_transaction_version_v2 IS NOT NULL AND NOT EXISTS ( \
+                                    SELECT 1 FROM fungible_asset_balances h \
+                                    WHERE h.transaction_version = c.last_transaction_version_v2 \
+                                    AND h.storage_id = c.storage_id AND h.token_standard = 'v2' \
+                                )) \
+                            )",
+                    opt_in_table: Some(TableFlags::FUNGIBLE_ASSET_BALANCES),
+                },
+                AuditCheck {
+                    name: "current_fungible_asset_balances_match_latest_history",
+                    description: "The v1 and v2 amounts of every current balance match the \
+                                  latest fungible_asset_balances row of the same standard",
+                    query: "SELECT json_build_object( \
+                                'storage_id', c.storage_id, \
+                                'amount_v1', c.amount_v1::text, \
+                                'history_amount_v1', v1.amount::text, \
+                                'amount_v2', c.amount_v2::text, \
+                                'history_amount_v2', v2.amount::text \
+                            )::text AS mismatch \
+                            FROM current_fungible_asset_balances c \
+                            LEFT JOIN LATERAL ( \
+                                SELECT h.amount FROM fungible_asset_balances h \
+                                WHERE h.storage_id = c.storage_id AND h.token_standard = 'v1' \
+                                ORDER BY h.transaction_version DESC, h.write_set_change_index DESC \
+                                LIMIT 1 \
+                            ) v1 ON TRUE \
+                            LEFT JOIN LATERAL ( \
+                                SELECT h.amount FROM fungible_asset_balances h \
+                                WHERE h.storage_id = c.storage_id AND h.token_standard = 'v2' \
+                                ORDER BY h.transaction_version DESC, h.write_set_change_index DESC \
+                                LIMIT 1 \
+                            ) v2 ON TRUE \
+                            WHERE c.last_transaction_version >= $1 \
+                            AND (c.amount_v1 IS DISTINCT FROM v1.amount \
+                                OR c.amount_v2 IS DISTINCT FROM v2.amount)",
+                    opt_in_table: Some(TableFlags::FUNGIBLE_ASSET_BALANCES),
+                },
+            ],
+            ProcessorName::ObjectsProcessor => vec![
+                AuditCheck {
+                    name: "current_objects_version_in_history",
+                    description: "The last version of every current object has an objects row",
+                    query: "SELECT json_build_object( \
+                                'object_address', c.object_address, \
+                                'last_transaction_version', c.last_transaction_version \
+                            )::text AS mismatch \
+                            FROM current_objects c \
+                            WHERE c.last_transaction_version >= $1 AND NOT EXISTS ( \
+                                SELECT 1 FROM objects h \
+                                WHERE h.transaction_version = c.last_transaction_version \
+                                AND h.object_address = c.object_address \
+                            )",
+                    opt_in_table: None,
+                },
+                AuditCheck {
+                    name: "current_objects_match_latest_history",
+                    description: "The owner and deletion state of every current object match \
+                                  its latest objects row",
+                    query: "SELECT json_build_object( \
+                                'object_address', c.object_address, \
+                                'owner_address', c.owner_address, \
+                                'history_owner_address', h.owner_address, \
+                                'is_deleted', c.is_deleted, \
+                                'history_is_deleted', h.is_deleted \
+                            )::text AS mismatch \
+                            FROM current_objects c \
+                            JOIN LATERAL ( \
+                                SELECT h.owner_address, h.is_deleted FROM objects h \
+                                WHERE h.object_address = c.object_address \
+                                ORDER BY h.transaction_version DESC, h.write_set_change_index DESC \
+                                LIMIT 1 \
+                            ) h ON TRUE \
+                            WHERE c.last_transaction_version >= $1 \
+                            AND (c.owner_address <> h.owner_address \
+                                OR c.is_deleted <> h.is_deleted)",
+                    opt_in_table: None,
+                },
+            ],
+            ProcessorName::StakeProcessor => vec![
+                AuditCheck {
+                    name: "current_delegator_balances_version_in_history",
+                    description: "The last version of every current delegator balance has a \
+                                  delegator_balances row",
+                    query: "SELECT json_build_object( \
+                                'delegator_address', c.delegator_address, \
+                                'pool_address', c.pool_address, \
+                                'pool_type', c.pool_type, \
+                                'last_transaction_version', c.last_transaction_version \
+                            )::text AS mismatch \
+                            FROM current_delegator_balances c \
+                            WHERE c.last_transaction_version >= $1 AND NOT EXISTS ( \
+                                SELECT 1 FROM delegator_balances h \
+                                WHERE h.transaction_version = c.last_transaction_version \
+                                AND h.delegator_address = c.delegator_address \
+                                AND h.pool_address = c.pool_address \
+                                AND h.pool_type = c.pool_type \
+                                AND h.table_handle = c.table_handle \
+                            )",
+                    opt_in_table: None,
+                },
+                AuditCheck {
+                    name: "delegated_staking_pool_shares_match_delegator_balances",
+                    description: "The total active shares of every delegation pool equal the sum \
+                                  of the active shares of its delegators",
+                    query: "SELECT json_build_object( \
+                                'staking_pool_address', p.staking_pool_address, \
+                                'total_shares', p.total_shares::text, \
+                                'delegator_shares', COALESCE(d.shares, 0)::text \
+                            )::text AS mismatch \
+                            FROM current_delegated_staking_pool_balances p \
+                            LEFT JOIN LATERAL ( \
+                                SELECT SUM(b.shares) AS shares FROM current_delegator_balances b \
+                                WHERE b.table_handle = p.active_table_handle \
+                                AND b.pool_type = 'active_shares' \
+                            ) d ON TRUE \
+                            WHERE p.last_transaction_version >= $1 \
+                            AND p.total_shares <> COALESCE(d.shares, 0)",
+                    opt_in_table: None,
+                },
+            ],
+            _ => anyhow::bail!("{processor} has no audit checks"),
+        };
+        Ok(checks)
+    }
+
+    /// Whether the tables read by the check are written with these tables_to_write.
+    pub fn is_enabled(&self, tables_to_write: &TableFlags) -> bool {
+        self.opt_in_table
+            .map_or(true, |flag| tables_to_write.contains(flag))
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct MismatchRow {
+    #[diesel(sql_type = BigInt)]
+    total: i64,
+    #[diesel(sql_type = Text)]
+    mismatch: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditCheckResult {
+    pub check: &'static str,
+    pub description: &'static str,
+    pub mismatch_count: i64,
+    /// Up to `sample_size` of the mismatched rows.
+    pub mismatches: Vec<serde_json::Value>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditReport {
+    pub processor: &'static str,
+    pub start_version: u64,
+    pub checks: Vec<AuditCheckResult>,
+    /// Checks that weren't run because they read an opt-in table that isn't written.
+    pub skipped_checks: Vec<&'static str>,
+}
+
+impl AuditReport {
+    /// Sets the audit gauges from the report.
+    pub fn record_metrics(&self) {
+        for check in &self.checks {
+            AUDIT_MISMATCH_COUNT
+                .with_label_values(&[self.processor, check.check])
+                .set(check.mismatch_count);
+        }
+        AUDIT_LAST_RUN_TIMESTAMP_SECS
+            .with_label_values(&[self.processor])
+            .set(chrono::Utc::now().timestamp());
+    }
+}
+
+async fn run_check(
+    conn: &mut DbPoolConnection<'_>,
+    check: &AuditCheck,
+    start_version: i64,
+    sample_size: u64,
+) -> Result<AuditCheckResult> {
+    // Count every mismatch, but only return a sample of them
+    let rows: Vec<MismatchRow> = diesel::sql_query(format!(
+        "SELECT COUNT(*) OVER () AS total, c.mismatch FROM ({}) c LIMIT $2",
+        check.query
+    ))
+    .bind::<BigInt, _>(start_version)
+    .bind::<BigInt, _>(sample_size.max(1) as i64)
+    .load(conn)
+    .await
+    .with_context(|| format!("Failed to run audit check {}", check.name))?;
+
+    Ok(AuditCheckResult {
+        check: check.name,
+        description: check.description,
+        mismatch_count: rows.first().map_or(0, |row| row.total),
+        mismatches: rows
+            .into_iter()
+            .take(sample_size as usize)
+            .map(|row| serde_json::from_str(&row.mismatch))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Runs all checks of the processor and returns the report. Checks that read an opt-in table
+/// missing from `tables_to_write` are skipped.
+pub async fn run_audit_checks(
+    conn: &mut DbPoolConnection<'_>,
+    processor: &'static str,
+    checks: &[AuditCheck],
+    tables_to_write: &TableFlags,
+    args: &AuditArgs,
+) -> Result<AuditReport> {
+    let start_version =
+        i64::try_from(args.start_version).context("Audit start version is too large")?;
+    let mut results = vec![];
+    let mut skipped_checks = vec![];
+    for check in checks {
+        if !check.is_enabled(tables_to_write) {
+            warn!(
+                processor,
+                check = check.name,
+                "Skipping audit check, it reads a table that isn't in tables_to_write"
+            );
+            skipped_checks.push(check.name);
+            continue;
+        }
+        let result = run_check(conn, check, start_version, args.sample_size).await?;
+        info!(
+            processor,
+            check = check.name,
+            mismatch_count = result.mismatch_count,
+            "Finished audit check"
+        );
+        results.push(result);
+    }
+
+    Ok(AuditReport {
+        processor,
+        start_version: args.start_version,
+        checks: results,
+        skipped_checks,
+    })
+}
+
+/// Audits the tables of the processor, reporting mismatches as JSON and as the
+/// `indexer_audit_mismatch_count` gauge.
+pub async fn run_audit(config: &IndexerProcessorConfig, args: &AuditArgs) -> Result<()> {
+    let processor_name = config.processor_config.name();
+    let checks = AuditCheck::for_processor(ProcessorName::from(&config.processor_config))?;
+    let tables_to_write = config.processor_config.tables_to_write();
+    let postgres_config = match &config.db_config {
+        DbConfig::PostgresConfig(postgres_config) => postgres_config,
+        _ => anyhow::bail!(
+            "Audit requires a PostgresConfig, got {:?}",
+            config.db_config
+        ),
+    };
+    let db_pool = new_db_pool(&postgres_config.connection_string, Some(1))
+        .await
+        .context("Failed to create connection pool for PostgresConfig")?;
+
+    if let Some(port) = args.metrics_port {
+        tokio::spawn(async move {
+            if let Err(e) = serve_metrics(port).await {
+                error!(port, "Failed to serve audit metrics: {e:?}");
+            }
+        });
+    }
+
+    loop {
+        let mut conn = db_pool
+            .get()
+            .await
+            .context("Failed to get database connection")?;
+        let report =
+            run_audit_checks(&mut conn, processor_name, &checks, &tables_to_write, args).await?;
+        report.record_metrics();
+
+        let json = serde_json::to_string_pretty(&report)?;
+        match &args.output {
+            Some(path) => std::fs::write(path, json)
+                .with_context(|| format!("Failed to write audit report to {path:?}"))?,
+            None => println!("{json}"),
+        }
+
+        match args.interval_secs {
+            Some(interval_secs) => tokio::time::sleep(Duration::from_secs(interval_secs)).await,
+            None => return Ok(()),
+        }
+    }
+}
+
+async fn serve_metrics(port: u16) -> Result<()> {
+    let make_service = make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(|_| async {
+            let mut buffer = vec![];
+            let response = match TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+                Ok(()) => Response::new(Body::from(buffer)),
+                Err(e) => Response::builder()
+                    .status(500)
+                    .body(Body::from(e.to_string()))
+                    .unwrap(),
+            };
+            Ok::<_, Infallible>(response)
+        }))
+    });
+    Server::bind(&([0, 0, 0, 0], port).into())
+        .serve(make_service)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::tests::table_definition;
+    use regex::Regex;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_audit_checks_reference_existing_tables() {
+        let table_regex = Regex::new(r"(?:FROM|JOIN) (\w+) \w+").unwrap();
+        for processor in ProcessorName::iter() {
+            let Ok(checks) = AuditCheck::for_processor(processor) else {
+                continue;
+            };
+            for check in checks {
+                assert!(check.query.contains("$1"), "{} is not bounded", check.name);
+                assert!(!check.query.contains("$2"), "{} uses $2", check.name);
+                for table in table_regex.captures_iter(check.query) {
+                    let table = &table[1];
+                    assert!(
+                        table_definition(table).is_some(),
+                        "{}: table {table} is not in schema.rs",
+                        check.name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fungible_asset_balance_checks_are_opt_in() {
+        let checks = AuditCheck::for_processor(ProcessorName::FungibleAssetProcessor).unwrap();
+        for check in &checks {
+            assert!(!check.is_enabled(&TableFlags::empty()), "{}", check.name);
+            assert!(check.is_enabled(&TableFlags::FUNGIBLE_ASSET_BALANCES));
+        }
+
+        let checks = AuditCheck::for_processor(ProcessorName::ObjectsProcessor).unwrap();
+        assert!(checks
+            .iter()
+            .all(|check| check.is_enabled(&TableFlags::empty())));
+    }
+
+    #[test]
+    fn test_record_metrics() {
+        let report = AuditReport {
+            processor: "objects_processor",
+            start_version: 0,
+            checks: vec![AuditCheckResult {
+                check: "current_objects_version_in_history",
+                description: "",
+                mismatch_count: 3,
+                mismatches: vec![],
+            }],
+            skipped_checks: vec![],
+        };
+        report.record_metrics();
+
+        assert_eq!(
+            AUDIT_MISMATCH_COUNT
+                .with_label_values(&["objects_processor", "current_objects_version_in_history"])
+                .get(),
+            3
+        );
+        assert!(
+            AUDIT_LAST_RUN_TIMESTAMP_SECS
+                .with_label_values(&["objects_processor"])
+                .get()
+                > 0
+        );
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    config::{db_config::DbConfig, indexer_processor_config::IndexerProcessorConfig},
+    db::balances::{
+        get_delegator_balance, get_fungible_asset_balances, Page, PointInTime, DEFAULT_PAGE_SIZE,
+        MAX_PAGE_SIZE,
+    },
+};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::postgres::utils::database::{new_db_pool, ArcDbPool};
+use clap::Args;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde_json::json;
+use std::{collections::HashMap, convert::Infallible};
+use tracing::{error, info};
+
+#[derive(Clone, Debug, Args)]
+pub struct BalanceApiArgs {
+    /// Port to serve the balance API on.
+    #[clap(long, default_value_t = 8080)]
+    pub port: u16,
+}
+
+/// A balance query parsed from the path and query string of a request.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BalanceRequest {
+    /// `GET /v1/accounts/{owner_address}/fungible_asset_balances`
+    FungibleAssetBalances {
+        owner_address: String,
+        asset_type: Option<String>,
+        at: PointInTime,
+        page: Page,
+    },
+    /// `GET /v1/accounts/{delegator_address}/delegations/{pool_address}`
+    DelegatorBalance {
+        delegator_address: String,
+        pool_address: String,
+        at: PointInTime,
+    },
+}
+
+fn parse_point_in_time(params: &mut HashMap<String, String>) -> Result<PointInTime> {
+    match (params.remove("version"), params.remove("timestamp")) {
+        (None, None) => Ok(PointInTime::Latest),
+        (Some(version), None) => Ok(PointInTime::Version(
+            version
+                .parse()
+                .with_context(|| format!("Invalid version {version}"))?,
+        )),
+        (None, Some(timestamp)) => Ok(PointInTime::Timestamp(
+            chrono::DateTime::parse_from_rfc3339(&timestamp)
+                .with_context(|| format!("Invalid RFC 3339 timestamp {timestamp}"))?
+                .naive_utc(),
+        )),
+        (Some(_), Some(_)) => anyhow::bail!("Only one of version and timestamp can be set"),
+    }
+}
+
+/// Parses a request. Returns None if the path isn't a route of the API, and an error if the
+/// query string is invalid.
+pub fn parse_request(path: &str, query: Option<&str>) -> Result<Option<BalanceRequest>> {
+    let mut params: HashMap<String, String> =
+        url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+            .into_owned()
+            .collect();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let request = match segments.as_slice() {
+        ["v1", "accounts", owner_address, "fungible_asset_balances"] => {
+            let limit = match params.remove("limit") {
+                Some(limit) => limit
+                    .parse()
+                    .ok()
+                    .filter(|limit| (1..=MAX_PAGE_SIZE).contains(limit))
+                    .with_context(|| {
+                        format!("Invalid limit {limit}, must be between 1 and {MAX_PAGE_SIZE}")
+                    })?,
+                None => DEFAULT_PAGE_SIZE,
+            };
+            BalanceRequest::FungibleAssetBalances {
+                owner_address: owner_address.to_string(),
+                asset_type: params.remove("asset_type"),
+                at: parse_point_in_time(&mut params)?,
+                page: Page {
+                    cursor: params.remove("cursor"),
+                    limit,
+                },
+            }
+        },
+        ["v1", "accounts", delegator_address, "delegations", pool_address] => {
+            let at = parse_point_in_time(&mut params)?;
+            if matches!(at, PointInTime::Timestamp(_)) {
+                anyhow::bail!("Delegator balances can only be queried by version");
+            }
+            BalanceRequest::DelegatorBalance {
+                delegator_address: delegator_address.to_string(),
+                pool_address: pool_address.to_string(),
+                at,
+            }
+        },
+        _ => return Ok(None),
+    };
+    if let Some(param) = params.keys().next() {
+        anyhow::bail!("Unknown query parameter {param}");
+    }
+    Ok(Some(request))
+}
+
+async fn query_balances(db_pool: &ArcDbPool, request: BalanceRequest) -> Result<serde_json::Value> {
+    let mut conn = db_pool
+        .get()
+        .await
+        .context("Failed to get database connection")?;
+    Ok(match request {
+        BalanceRequest::FungibleAssetBalances {
+            owner_address,
+            asset_type,
+            at,
+            page,
+        } => serde_json::to_value(
+            get_fungible_asset_balances(
+                &mut conn,
+                &owner_address,
+                asset_type.as_deref(),
+                at,
+                &page,
+            )
+            .await?,
+        )?,
+        BalanceRequest::DelegatorBalance {
+            delegator_address,
+            pool_address,
+            at,
+        } => serde_json::to_value(
+            get_delegator_balance(&mut conn, &delegator_address, &pool_address, at).await?,
+        )?,
+    })
+}
+
+fn json_response(status: StatusCode, body: serde_json::Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+async fn handle_request(db_pool: ArcDbPool, request: Request<Body>) -> Response<Body> {
+    if request.method() != Method::GET {
+        return json_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            json!({ "error": "Only GET is supported" }),
+        );
+    }
+    let balance_request = match parse_request(request.uri().path(), request.uri().query()) {
+        Ok(Some(balance_request)) => balance_request,
+        Ok(None) => return json_response(StatusCode::NOT_FOUND, json!({ "error": "Not found" })),
+        Err(e) => return json_response(StatusCode::BAD_REQUEST, json!({ "error": e.to_string() })),
+    };
+    match query_balances(&db_pool, balance_request).await {
+        Ok(body) => json_response(StatusCode::OK, body),
+        Err(e) => {
+            error!("Failed to query balances: {e:?}");
+            json_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                json!({ "error": "Failed to query balances" }),
+            )
+        },
+    }
+}
+
+/// Serves the point-in-time balance queries of `db::balances` over HTTP, until the process is
+/// stopped.
+pub async fn run_balance_api(config: &IndexerProcessorConfig, args: &BalanceApiArgs) -> Result<()> {
+    let postgres_config = match &config.db_config {
+        DbConfig::PostgresConfig(postgres_config) => postgres_config,
+        _ => anyhow::bail!(
+            "The balance API requires a PostgresConfig, got {:?}",
+            config.db_config
+        ),
+    };
+    let db_pool = new_db_pool(
+        &postgres_config.connection_string,
+        Some(postgres_config.db_pool_size),
+    )
+    .await
+    .context("Failed to create connection pool for PostgresConfig")?;
+
+    let make_service = make_service_fn(move |_| {
+        let db_pool = db_pool.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let db_pool = db_pool.clone();
+                async move { Ok::<_, Infallible>(handle_request(db_pool, request).await) }
+            }))
+        }
+    });
+    info!(port = args.port, "Serving the balance API");
+    Server::bind(&([0, 0, 0, 0], args.port).into())
+        .serve(make_service)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fungible_asset_balances_request() {
+        let request = parse_request(
+            "/v1/accounts/0xa11ce/fungible_asset_balances",
+            Some("asset_type=0x1%3A%3Alibra2_coin%3A%3ALibra2Coin&version=100&limit=10&cursor=0xb"),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(request, BalanceRequest::FungibleAssetBalances {
+            owner_address: "0xa11ce".to_string(),
+            asset_type: Some("0x1::libra2_coin::Libra2Coin".to_string()),
+            at: PointInTime::Version(100),
+            page: Page {
+                cursor: Some("0xb".to_string()),
+                limit: 10,
+            },
+        });
+
+        let request = parse_request(
+            "/v1/accounts/0xa11ce/fungible_asset_balances",
+            Some("timestamp=2024-05-01T12:30:00Z"),
+        )
+        .unwrap()
+        .unwrap();
+        let BalanceRequest::FungibleAssetBalances { at, page, .. } = request else {
+            panic!("Expected a fungible asset balances request");
+        };
+        assert_eq!(
+            at,
+            PointInTime::Timestamp(
+                chrono::NaiveDate::from_ymd_opt(2024, 5, 1)
+                    .unwrap()
+                    .and_hms_opt(12, 30, 0)
+                    .unwrap()
+            )
+        );
+        assert_eq!(page, Page::default());
+    }
+
+    #[test]
+    fn test_parse_delegator_balance_request() {
+        let request = parse_request("/v1/accounts/0xa11ce/delegations/0xb0b", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(request, BalanceRequest::DelegatorBalance {
+            delegator_address: "0xa11ce".to_string(),
+            pool_address: "0xb0b".to_string(),
+            at: PointInTime::Latest,
+        });
+
+        // delegator_balances has no timestamp column
+        assert!(parse_request(
+            "/v1/accounts/0xa11ce/delegations/0xb0b",
+            Some("timestamp=2024-05-01T12:30:00Z")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_requests() {
+        let path = "/v1/accounts/0xa11ce/fungible_asset_balances";
+        assert!(parse_request("/v1/accounts/0xa11ce", None)
+            .unwrap()
+            .is_none());
+        assert!(parse_request(path, Some("version=1&timestamp=2024-05-01T12:30:00Z")).is_err());
+        assert!(parse_request(path, Some("version=latest")).is_err());
+        assert!(parse_request(path, Some("limit=0")).is_err());
+        assert!(parse_request(path, Some("limit=1001")).is_err());
+        assert!(parse_request(path, Some("owner=0xa11ce")).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::IndexerProcessorConfig,
+        processor_config::{ProcessorConfig, ProcessorName},
+    },
+    parquet_processors::{
+        initialize_uploader,
+        parquet_utils::{
+            parquet_compactor::ParquetCompactor, parquet_file_ledger::ParquetFileLedger,
+        },
+        ParquetTypeEnum,
+    },
+};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::postgres::utils::database::new_db_pool;
+use clap::Args;
+use strum::IntoEnumIterator;
+use tracing::warn;
+
+const DEFAULT_TARGET_FILE_SIZE_BYTES: u64 = 128 * 1024 * 1024;
+
+#[derive(Clone, Debug, Args)]
+pub struct CompactArgs {
+    /// Tables to compact. Defaults to every table of the processor.
+    #[clap(long, value_delimiter = ',')]
+    pub tables: Vec<String>,
+    /// Size that merged files are filled up to. Files at least this large are left as they are.
+    #[clap(long, default_value_t = DEFAULT_TARGET_FILE_SIZE_BYTES)]
+    pub target_file_size_bytes: u64,
+    /// Print which files would be merged without touching the bucket.
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// Merges the small parquet files of the processor's tables into files of about
+/// `args.target_file_size_bytes`, and deletes the files they were merged from.
+///
+/// Safe to run while the processor is running, files that aren't committed yet are skipped.
+pub async fn run_compact(config: &IndexerProcessorConfig, args: &CompactArgs) -> Result<()> {
+    let processor_name = config.processor_config.name();
+    let parquet_config = match &config.db_config {
+        DbConfig::ParquetConfig(parquet_config) => parquet_config,
+        _ => anyhow::bail!(
+            "Compaction requires a ParquetConfig, got {:?}",
+            config.db_config
+        ),
+    };
+
+    let table_names = if args.tables.is_empty() {
+        let mut table_names: Vec<String> =
+            ProcessorConfig::table_names(&ProcessorName::from(&config.processor_config))
+                .into_iter()
+                .collect();
+        if table_names.is_empty() {
+            anyhow::bail!("{processor_name} doesn't write parquet files");
+        }
+        table_names.sort();
+        table_names
+    } else {
+        args.tables.clone()
+    };
+    let mut parquet_types = vec![];
+    for table_name in table_names {
+        match ParquetTypeEnum::iter().find(|parquet_type| parquet_type.to_string() == table_name) {
+            Some(parquet_type) => parquet_types.push(parquet_type),
+            None if args.tables.is_empty() => {
+                warn!(
+                    table_name,
+                    "Skipping table that isn't written as parquet files"
+                )
+            },
+            None => anyhow::bail!("Unknown parquet table {table_name}"),
+        }
+    }
+
+    let db_pool = new_db_pool(&parquet_config.connection_string, Some(1))
+        .await
+        .context("Failed to create connection pool for ParquetConfig")?;
+    let compactor = ParquetCompactor::new(
+        initialize_uploader(parquet_config).await?,
+        &parquet_config.bucket_root,
+        Some(ParquetFileLedger::new(db_pool, processor_name.to_string())),
+        args.target_file_size_bytes,
+    );
+
+    for parquet_type in parquet_types {
+        let plan = compactor.plan(parquet_type).await?;
+        for (compacted_file, leftover_sources) in &plan.unfinished {
+            println!(
+                "{parquet_type}: deleting {} files already merged into {}",
+                leftover_sources.len(),
+                compacted_file.file_path
+            );
+        }
+        for group in &plan.groups {
+            println!(
+                "{parquet_type}: merging {} files with versions [{}, {}]",
+                group.len(),
+                group[0].start_version,
+                group[group.len() - 1].end_version
+            );
+        }
+
+        if !args.dry_run {
+            let compacted_files = compactor.compact(&plan).await?;
+            println!(
+                "{parquet_type}: wrote {} compacted files",
+                compacted_files.len()
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Maintenance commands that run against the processor's database or local files instead of the
+//! stream.
+
+use crate::config::indexer_processor_config::IndexerProcessorConfig;
+use anyhow::{Context, Result};
+use clap::Subcommand;
+
+pub mod audit;
+pub mod balance_api;
+pub mod compact;
+pub mod recompute;
+pub mod replay;
+pub mod rollback;
+pub mod schemas;
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum Command {
+    /// Delete everything the processor wrote above a version and rewind its checkpoint
+    Rollback(rollback::RollbackArgs),
+    /// Rebuild the current tables of the processor from their history tables
+    ///
+    /// Each current table is cleared and refilled from its history table, so the history table
+    /// must hold every version of it. The command refuses to run unless the processor writes the
+    /// history table, or the history table spans the versions of the current table. Opt-in
+    /// history tables such as fungible_asset_balances, ans_lookup_v2 and token_ownerships_v2 are
+    /// only written when they're in tables_to_write.
+    Recompute(recompute::RecomputeArgs),
+    /// Check the tables of the processor for data that is inconsistent between them
+    Audit(audit::AuditArgs),
+    /// Run the processor against transactions read from local files instead of the stream
+    Replay(replay::ReplayArgs),
+    /// Serve balances at a version or timestamp from the history tables over HTTP
+    BalanceApi(balance_api::BalanceApiArgs),
+    /// Merge the small parquet files of the processor's tables into larger ones
+    Compact(compact::CompactArgs),
+    /// Print the schema and schema version of every parquet table as JSON
+    Schemas(schemas::SchemasArgs),
+}
+
+impl Command {
+    /// Runs the command. Every command but `schemas` needs the processor config.
+    pub async fn run(&self, config: Option<&IndexerProcessorConfig>) -> Result<()> {
+        let config = || config.context("This command needs a config, pass it with --config-path");
+        match self {
+            Command::Rollback(args) => rollback::run_rollback(config()?, args).await,
+            Command::Recompute(args) => recompute::run_recompute(config()?, args).await,
+            Command::Audit(args) => audit::run_audit(config()?, args).await,
+            Command::Replay(args) => replay::run_replay(config()?, args).await,
+            Command::BalanceApi(args) => balance_api::run_balance_api(config()?, args).await,
+            Command::Compact(args) => compact::run_compact(config()?, args).await,
+            Command::Schemas(args) => schemas::run_schemas(args),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    const SCHEMA: &str = include_str!("../db/schema.rs");
+
+    /// Returns the column definitions of a table in schema.rs.
+    pub fn table_definition(table_name: &str) -> Option<&'static str> {
+        let start = SCHEMA.find(&format!("    {table_name} ("))?;
+        let end = SCHEMA[start..].find("\n    }")?;
+        Some(&SCHEMA[start..start + end])
+    }
+}
@@ -0,0 +1,664 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    config::{
+        db_config::DbConfig, indexer_processor_config::IndexerProcessorConfig,
+        processor_config::ProcessorName,
+    },
+    processors::fungible_asset::fungible_asset_models::v2_fungible_asset_to_coin_mappings::FA_TO_COIN_MAPPING,
+    utils::table_flags::TableFlags,
+};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::postgres::{
+    models::processor_status::ProcessorStatusQuery,
+    utils::database::{new_db_pool, DbPoolConnection},
+};
+use clap::Args;
+use diesel::{
+    sql_types::{BigInt, Nullable},
+    QueryableByName,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+use itertools::Itertools;
+use tracing::info;
+
+pub const DEFAULT_CHUNK_SIZE: u64 = 1_000_000;
+
+#[derive(Clone, Debug, Args)]
+pub struct RecomputeArgs {
+    /// Only rebuild these current tables. By default every current table of the processor that
+    /// has a history table is rebuilt.
+    #[clap(long, value_delimiter = ',')]
+    pub tables: Vec<String>,
+    /// Last version to rebuild up to. Defaults to the processor's checkpoint in processor_status.
+    #[clap(long)]
+    pub end_version: Option<u64>,
+    /// Number of versions of the history table to read per statement.
+    #[clap(long, default_value_t = DEFAULT_CHUNK_SIZE)]
+    pub chunk_size: u64,
+}
+
+/// What happens to a column of the current table when the key already exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OnConflict {
+    /// Overwrite with the value from the history table.
+    Update,
+    /// Overwrite unless the value from the history table is null.
+    Coalesce,
+    /// Only set when the row is inserted.
+    Keep,
+}
+
+#[derive(Clone, Debug)]
+struct Column {
+    name: &'static str,
+    /// Expression over the history row, aliased as `h`.
+    expression: String,
+    on_conflict: OnConflict,
+}
+
+fn column(name: &'static str, expression: &str) -> Column {
+    Column {
+        name,
+        expression: expression.to_string(),
+        on_conflict: OnConflict::Update,
+    }
+}
+
+/// One `INSERT ... SELECT` that upserts the latest history row per key into a current table.
+#[derive(Clone, Debug)]
+struct UpsertFromHistory {
+    /// Primary key of the current table. The history table uses the same column names.
+    key_columns: &'static [&'static str],
+    columns: Vec<Column>,
+    /// Version column of the current table. A row is only overwritten by a newer version.
+    version_column: &'static str,
+    joins: &'static str,
+    filter: Option<&'static str>,
+}
+
+/// How a current table is derived from its history table.
+#[derive(Clone, Debug)]
+pub struct CurrentTableRecompute {
+    pub current_table: &'static str,
+    pub history_table: &'static str,
+    /// Flag of the history table in tables_to_write.
+    history_flag: TableFlags,
+    /// Whether the history table is only written when its flag is in tables_to_write.
+    history_opt_in: bool,
+    upserts: Vec<UpsertFromHistory>,
+}
+
+/// First and last version of a table, both null when the table has no rows.
+#[derive(Debug, QueryableByName)]
+struct VersionRange {
+    #[diesel(sql_type = Nullable<BigInt>)]
+    first_version: Option<i64>,
+    #[diesel(sql_type = Nullable<BigInt>)]
+    last_version: Option<i64>,
+}
+
+impl CurrentTableRecompute {
+    /// Get the current tables of a processor that can be rebuilt from a history table.
+    pub fn for_processor(processor: ProcessorName) -> Result<Vec<Self>> {
+        let tables = match processor {
+            ProcessorName::AnsProcessor => vec![current_ans_lookup_v2()],
+            ProcessorName::FungibleAssetProcessor => vec![current_fungible_asset_balances()],
+            ProcessorName::ObjectsProcessor => vec![current_objects()],
+            ProcessorName::StakeProcessor => vec![current_delegator_balances()],
+            ProcessorName::TokenV2Processor => vec![current_token_ownerships_v2()],
+            _ => anyhow::bail!("{processor} has no current tables that can be recomputed"),
+        };
+        Ok(tables)
+    }
+
+    /// Whether the processor writes the history table with these tables_to_write.
+    pub fn history_is_written(&self, tables_to_write: &TableFlags) -> bool {
+        tables_to_write.contains(self.history_flag)
+            || (!self.history_opt_in && tables_to_write.is_empty())
+    }
+
+    /// Returns the statement that selects the version range of the current table up to `$1`.
+    fn current_version_range_statement(&self) -> String {
+        let versions = self
+            .upserts
+            .iter()
+            .map(|upsert| {
+                format!(
+                    "SELECT {} AS version FROM {}",
+                    upsert.version_column, self.current_table
+                )
+            })
+            .join(" UNION ALL ");
+        format!(
+            "SELECT MIN(c.version) AS first_version, MAX(c.version) AS last_version \
+             FROM ({versions}) c WHERE c.version <= $1"
+        )
+    }
+
+    /// Returns the statement that selects the version range of the history table up to `$1`.
+    fn history_version_range_statement(&self) -> String {
+        format!(
+            "SELECT MIN(transaction_version) AS first_version, \
+             MAX(transaction_version) AS last_version \
+             FROM {} WHERE transaction_version <= $1",
+            self.history_table
+        )
+    }
+
+    /// Checks that the history table has the rows to rebuild the current table up to
+    /// `end_version`, so that clearing the current table doesn't lose state.
+    ///
+    /// That's the case when the processor writes the history table, or when the history table
+    /// spans every version of the current table.
+    async fn ensure_history_covers(
+        &self,
+        conn: &mut DbPoolConnection<'_>,
+        tables_to_write: &TableFlags,
+        end_version: u64,
+    ) -> Result<()> {
+        if self.history_is_written(tables_to_write) {
+            return Ok(());
+        }
+        let end_version = end_version.min(i64::MAX as u64) as i64;
+        let current: VersionRange = diesel::sql_query(self.current_version_range_statement())
+            .bind::<BigInt, _>(end_version)
+            .get_result(conn)
+            .await
+            .with_context(|| format!("Failed to query the versions of {}", self.current_table))?;
+        let history: VersionRange = diesel::sql_query(self.history_version_range_statement())
+            .bind::<BigInt, _>(end_version)
+            .get_result(conn)
+            .await
+            .with_context(|| format!("Failed to query the versions of {}", self.history_table))?;
+
+        let (Some(first_version), Some(last_version)) =
+            (current.first_version, current.last_version)
+        else {
+            // Nothing to lose
+            return Ok(());
+        };
+        let covered = matches!(
+            (history.first_version, history.last_version),
+            (Some(first), Some(last)) if first <= first_version && last >= last_version
+        );
+        anyhow::ensure!(
+            covered,
+            "{history} isn't in tables_to_write and doesn't cover versions {first_version} to \
+             {last_version} of {current}, recomputing would drop rows of {current}. Add {flag} \
+             to tables_to_write and reindex the processor before recomputing",
+            history = self.history_table,
+            current = self.current_table,
+            // Table flags are named after their table
+            flag = self.history_table.to_uppercase(),
+        );
+        Ok(())
+    }
+
+    /// Returns the statements that upsert the history rows in `[$1, $2)` into the current table.
+    pub fn upsert_statements(&self) -> Vec<String> {
+        self.upserts
+            .iter()
+            .map(|upsert| {
+                let keys = upsert.key_columns.join(", ");
+                let history_keys = upsert
+                    .key_columns
+                    .iter()
+                    .map(|key| format!("h.{key}"))
+                    .join(", ");
+                let columns = upsert
+                    .key_columns
+                    .iter()
+                    .copied()
+                    .chain(upsert.columns.iter().map(|column| column.name))
+                    .join(", ");
+                let expressions = upsert
+                    .key_columns
+                    .iter()
+                    .map(|key| format!("h.{key}"))
+                    .chain(
+                        upsert
+                            .columns
+                            .iter()
+                            .map(|column| column.expression.clone()),
+                    )
+                    .join(", ");
+                let updates = upsert
+                    .columns
+                    .iter()
+                    .filter_map(|column| match column.on_conflict {
+                        OnConflict::Update => Some(format!("{0} = EXCLUDED.{0}", column.name)),
+                        OnConflict::Coalesce => Some(format!(
+                            "{0} = COALESCE(EXCLUDED.{0}, {1}.{0})",
+                            column.name, self.current_table
+                        )),
+                        OnConflict::Keep => None,
+                    })
+                    .join(", ");
+                let filter = upsert
+                    .filter
+                    .map(|filter| format!(" AND {filter}"))
+                    .unwrap_or_default();
+
+                format!(
+                    "INSERT INTO {current} ({columns}) \
+                     SELECT DISTINCT ON ({history_keys}) {expressions} \
+                     FROM {history} h{joins} \
+                     WHERE h.transaction_version >= $1 AND h.transaction_version < $2{filter} \
+                     ORDER BY {history_keys}, h.transaction_version DESC, \
+                     h.write_set_change_index DESC \
+                     ON CONFLICT ({keys}) DO UPDATE SET {updates} \
+                     WHERE {current}.{version} IS NULL \
+                     OR {current}.{version} <= EXCLUDED.{version}",
+                    current = self.current_table,
+                    history = self.history_table,
+                    joins = upsert.joins,
+                    version = upsert.version_column,
+                )
+            })
+            .collect()
+    }
+}
+
+fn current_objects() -> CurrentTableRecompute {
+    CurrentTableRecompute {
+        current_table: "current_objects",
+        history_table: "objects",
+        history_flag: TableFlags::OBJECTS,
+        history_opt_in: false,
+        upserts: vec![UpsertFromHistory {
+            key_columns: &["object_address"],
+            columns: vec![
+                column("owner_address", "h.owner_address"),
+                column("state_key_hash", "h.state_key_hash"),
+                column("allow_ungated_transfer", "h.allow_ungated_transfer"),
+                column("last_guid_creation_num", "h.guid_creation_num"),
+                column("last_transaction_version", "h.transaction_version"),
+                column("is_deleted", "h.is_deleted"),
+                column("untransferrable", "h.untransferrable"),
+                column("inserted_at", "NOW()"),
+            ],
+            version_column: "last_transaction_version",
+            joins: "",
+            filter: None,
+        }],
+    }
+}
+
+fn current_delegator_balances() -> CurrentTableRecompute {
+    CurrentTableRecompute {
+        current_table: "current_delegator_balances",
+        history_table: "delegator_balances",
+        history_flag: TableFlags::DELEGATOR_BALANCES,
+        history_opt_in: false,
+        upserts: vec![UpsertFromHistory {
+            key_columns: &[
+                "delegator_address",
+                "pool_address",
+                "pool_type",
+                "table_handle",
+            ],
+            columns: vec![
+                column("last_transaction_version", "h.transaction_version"),
+                column("shares", "h.shares"),
+                column("parent_table_handle", "h.parent_table_handle"),
+                column("inserted_at", "NOW()"),
+            ],
+            version_column: "last_transaction_version",
+            joins: "",
+            filter: None,
+        }],
+    }
+}
+
+fn current_ans_lookup_v2() -> CurrentTableRecompute {
+    CurrentTableRecompute {
+        current_table: "current_ans_lookup_v2",
+        history_table: "ans_lookup_v2",
+        history_flag: TableFlags::ANS_LOOKUP_V2,
+        history_opt_in: true,
+        upserts: vec![UpsertFromHistory {
+            key_columns: &["domain", "subdomain", "token_standard"],
+            columns: vec![
+                column("token_name", "h.token_name"),
+                column("registered_address", "h.registered_address"),
+                column("expiration_timestamp", "h.expiration_timestamp"),
+                column("last_transaction_version", "h.transaction_version"),
+                column("is_deleted", "h.is_deleted"),
+                column(
+                    "subdomain_expiration_policy",
+                    "h.subdomain_expiration_policy",
+                ),
+                column("inserted_at", "NOW()"),
+            ],
+            version_column: "last_transaction_version",
+            joins: "",
+            // The current table requires an expiration timestamp
+            filter: Some("h.expiration_timestamp IS NOT NULL"),
+        }],
+    }
+}
+
+fn current_token_ownerships_v2() -> CurrentTableRecompute {
+    CurrentTableRecompute {
+        current_table: "current_token_ownerships_v2",
+        history_table: "token_ownerships_v2",
+        history_flag: TableFlags::TOKEN_OWNERSHIPS_V2,
+        history_opt_in: true,
+        upserts: vec![UpsertFromHistory {
+            key_columns: &[
+                "token_data_id",
+                "property_version_v1",
+                "owner_address",
+                "storage_id",
+            ],
+            columns: vec![
+                column("amount", "h.amount"),
+                column("table_type_v1", "h.table_type_v1"),
+                column(
+                    "token_properties_mutated_v1",
+                    "h.token_properties_mutated_v1",
+                ),
+                column("is_soulbound_v2", "h.is_soulbound_v2"),
+                column("token_standard", "h.token_standard"),
+                column("is_fungible_v2", "h.is_fungible_v2"),
+                column("last_transaction_version", "h.transaction_version"),
+                column("last_transaction_timestamp", "h.transaction_timestamp"),
+                column("non_transferrable_by_owner", "h.non_transferrable_by_owner"),
+                column("inserted_at", "NOW()"),
+            ],
+            version_column: "last_transaction_version",
+            joins: "",
+            // Ownerships without an owner are never written to the current table
+            filter: Some("h.owner_address IS NOT NULL"),
+        }],
+    }
+}
+
+/// Mirrors the v1 and v2 upserts of the fungible asset processor: v1 (coin) and v2 (fungible
+/// store) balances of the same storage id are merged into one row.
+fn current_fungible_asset_balances() -> CurrentTableRecompute {
+    // Same lookup as `FungibleAssetToCoinMapping::get_asset_type_v1`, the static mapping takes
+    // precedence over the mappings stored in the database.
+    let static_mappings = FA_TO_COIN_MAPPING
+        .iter()
+        .sorted()
+        .map(|(fa, coin)| format!("WHEN '{}' THEN '{}'", fa, coin.replace('\'', "''")))
+        .join(" ");
+    let asset_type_v1 = format!("CASE h.asset_type {static_mappings} ELSE m.coin_type END");
+
+    CurrentTableRecompute {
+        current_table: "current_fungible_asset_balances",
+        history_table: "fungible_asset_balances",
+        history_flag: TableFlags::FUNGIBLE_ASSET_BALANCES,
+        history_opt_in: true,
+        upserts: vec![
+            UpsertFromHistory {
+                key_columns: &["storage_id"],
+                columns: vec![
+                    column("owner_address", "h.owner_address"),
+                    column("asset_type_v1", "h.asset_type"),
+                    Column {
+                        name: "is_primary",
+                        expression: "TRUE".to_string(),
+                        on_conflict: OnConflict::Keep,
+                    },
+                    column("is_frozen", "h.is_frozen"),
+                    column("amount_v1", "h.amount"),
+                    column("last_transaction_timestamp_v1", "h.transaction_timestamp"),
+                    column("last_transaction_version_v1", "h.transaction_version"),
+                    column("inserted_at", "NOW()"),
+                ],
+                version_column: "last_transaction_version_v1",
+                joins: "",
+                filter: Some("h.token_standard = 'v1'"),
+            },
+            UpsertFromHistory {
+                key_columns: &["storage_id"],
+                columns: vec![
+                    column("owner_address", "h.owner_address"),
+                    Column {
+                        name: "asset_type_v1",
+                        expression: asset_type_v1,
+                        on_conflict: OnConflict::Coalesce,
+                    },
+                    column("asset_type_v2", "h.asset_type"),
+                    column("is_primary", "h.is_primary"),
+                    column("is_frozen", "h.is_frozen"),
+                    column("amount_v2", "h.amount"),
+                    column("last_transaction_timestamp_v2", "h.transaction_timestamp"),
+                    column("last_transaction_version_v2", "h.transaction_version"),
+                    column("inserted_at", "NOW()"),
+                ],
+                version_column: "last_transaction_version_v2",
+                joins: " LEFT JOIN fungible_asset_to_coin_mappings m \
+                        ON m.fungible_asset_metadata_address = h.asset_type",
+                filter: Some("h.token_standard = 'v2'"),
+            },
+        ],
+    }
+}
+
+/// Rebuilds the current tables of the processor from their history tables.
+///
+/// Every current table is cleared and then filled from its history table in chunks of
+/// `args.chunk_size` versions, keeping the latest row per primary key. Each table is rebuilt in
+/// one transaction, so readers see the old rows until it commits and an interrupted run leaves
+/// the table unchanged. The processor should be stopped while this runs.
+///
+/// A table is only rebuilt when its history table is written by the processor or spans every
+/// version of the current table, otherwise clearing it would lose rows that can't be rebuilt.
+pub async fn run_recompute(config: &IndexerProcessorConfig, args: &RecomputeArgs) -> Result<()> {
+    let processor_name = config.processor_config.name();
+    let mut tables =
+        CurrentTableRecompute::for_processor(ProcessorName::from(&config.processor_config))?;
+    if !args.tables.is_empty() {
+        if let Some(unknown) = args
+            .tables
+            .iter()
+            .find(|table| !tables.iter().any(|t| t.current_table == table.as_str()))
+        {
+            anyhow::bail!(
+                "Table {unknown} can't be recomputed for {processor_name}. Expected one of: {}",
+                tables.iter().map(|t| t.current_table).join(", ")
+            );
+        }
+        tables.retain(|t| args.tables.iter().any(|table| table == t.current_table));
+    }
+    anyhow::ensure!(args.chunk_size > 0, "Chunk size must be greater than 0");
+
+    let postgres_config = match &config.db_config {
+        DbConfig::PostgresConfig(postgres_config) => postgres_config,
+        _ => anyhow::bail!(
+            "Recomputing current tables requires a PostgresConfig, got {:?}",
+            config.db_config
+        ),
+    };
+    let db_pool = new_db_pool(&postgres_config.connection_string, Some(1))
+        .await
+        .context("Failed to create connection pool for PostgresConfig")?;
+    let mut conn = db_pool
+        .get()
+        .await
+        .context("Failed to get database connection")?;
+
+    let end_version = match args.end_version {
+        Some(end_version) => end_version,
+        None => ProcessorStatusQuery::get_by_processor(processor_name, &mut conn)
+            .await
+            .context("Failed to query processor_status table")?
+            .map(|status| status.last_success_version as u64)
+            .with_context(|| {
+                format!("{processor_name} has no checkpoint, pass --end-version instead")
+            })?,
+    };
+
+    let tables_to_write = config.processor_config.tables_to_write();
+    // Check every table first, so that nothing is rebuilt if one of them can't be
+    for table in &tables {
+        table
+            .ensure_history_covers(&mut conn, &tables_to_write, end_version)
+            .await?;
+    }
+
+    for table in tables {
+        info!(
+            current_table = table.current_table,
+            history_table = table.history_table,
+            end_version,
+            "Recomputing current table from history"
+        );
+        let current_table = table.current_table;
+        let statements = table.upsert_statements();
+        let chunk_size = args.chunk_size;
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            async move {
+                diesel::sql_query(format!("DELETE FROM {current_table}"))
+                    .execute(conn)
+                    .await
+                    .with_context(|| format!("Failed to clear {current_table}"))?;
+
+                let mut chunk_start = 0;
+                while chunk_start <= end_version {
+                    let chunk_end = chunk_start.saturating_add(chunk_size).min(end_version + 1);
+                    for statement in &statements {
+                        diesel::sql_query(statement)
+                            .bind::<BigInt, _>(chunk_start as i64)
+                            .bind::<BigInt, _>(chunk_end as i64)
+                            .execute(conn)
+                            .await
+                            .with_context(|| {
+                                format!(
+                                    "Failed to recompute {} for versions [{}, {})",
+                                    current_table, chunk_start, chunk_end
+                                )
+                            })?;
+                    }
+                    info!(
+                        current_table,
+                        "Recomputed versions [{}, {})", chunk_start, chunk_end
+                    );
+                    chunk_start = chunk_end;
+                }
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+        .with_context(|| format!("Failed to recompute {current_table}, it was left unchanged"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{rollback::RollbackTables, tests::table_definition};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_recompute_columns_exist_in_schema() {
+        for processor in ProcessorName::iter() {
+            let Ok(tables) = CurrentTableRecompute::for_processor(processor) else {
+                continue;
+            };
+            for table in tables {
+                let current = table_definition(table.current_table).unwrap();
+                let history = table_definition(table.history_table).unwrap();
+                for upsert in &table.upserts {
+                    for key in upsert.key_columns {
+                        assert!(current.contains(&format!(" {key} -> ")), "{key}");
+                        assert!(history.contains(&format!(" {key} -> ")), "{key}");
+                    }
+                    for column in &upsert.columns {
+                        let name = column.name;
+                        assert!(current.contains(&format!(" {name} -> ")), "{name}");
+                    }
+                }
+
+                // Anything that can be recomputed must be reported by rollback as well
+                assert!(RollbackTables::for_processor(processor)
+                    .unwrap()
+                    .current_tables
+                    .contains(&table.current_table));
+            }
+        }
+    }
+
+    #[test]
+    fn test_upsert_statement() {
+        let statements = current_objects().upsert_statements();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(
+            statements[0],
+            "INSERT INTO current_objects (object_address, owner_address, state_key_hash, \
+             allow_ungated_transfer, last_guid_creation_num, last_transaction_version, \
+             is_deleted, untransferrable, inserted_at) \
+             SELECT DISTINCT ON (h.object_address) h.object_address, h.owner_address, \
+             h.state_key_hash, h.allow_ungated_transfer, h.guid_creation_num, \
+             h.transaction_version, h.is_deleted, h.untransferrable, NOW() \
+             FROM objects h \
+             WHERE h.transaction_version >= $1 AND h.transaction_version < $2 \
+             ORDER BY h.object_address, h.transaction_version DESC, \
+             h.write_set_change_index DESC \
+             ON CONFLICT (object_address) DO UPDATE SET owner_address = EXCLUDED.owner_address, \
+             state_key_hash = EXCLUDED.state_key_hash, \
+             allow_ungated_transfer = EXCLUDED.allow_ungated_transfer, \
+             last_guid_creation_num = EXCLUDED.last_guid_creation_num, \
+             last_transaction_version = EXCLUDED.last_transaction_version, \
+             is_deleted = EXCLUDED.is_deleted, untransferrable = EXCLUDED.untransferrable, \
+             inserted_at = EXCLUDED.inserted_at \
+             WHERE current_objects.last_transaction_version IS NULL \
+             OR current_objects.last_transaction_version <= EXCLUDED.last_transaction_version"
+        );
+    }
+
+    #[test]
+    fn test_history_is_written() {
+        let all_tables = TableFlags::OBJECTS | TableFlags::FUNGIBLE_ASSET_BALANCES;
+        // Default tables are written unless tables_to_write leaves them out
+        assert!(current_objects().history_is_written(&TableFlags::empty()));
+        assert!(current_objects().history_is_written(&all_tables));
+        assert!(!current_objects().history_is_written(&TableFlags::CURRENT_OBJECTS));
+        // Opt-in tables are only written when they're in tables_to_write
+        let balances = current_fungible_asset_balances();
+        assert!(!balances.history_is_written(&TableFlags::empty()));
+        assert!(balances.history_is_written(&all_tables));
+    }
+
+    #[test]
+    fn test_version_range_statements() {
+        assert_eq!(
+            current_fungible_asset_balances().current_version_range_statement(),
+            "SELECT MIN(c.version) AS first_version, MAX(c.version) AS last_version \
+             FROM (SELECT last_transaction_version_v1 AS version \
+             FROM current_fungible_asset_balances UNION ALL \
+             SELECT last_transaction_version_v2 AS version \
+             FROM current_fungible_asset_balances) c WHERE c.version <= $1"
+        );
+        assert_eq!(
+            current_objects().history_version_range_statement(),
+            "SELECT MIN(transaction_version) AS first_version, \
+             MAX(transaction_version) AS last_version \
+             FROM objects WHERE transaction_version <= $1"
+        );
+    }
+
+    #[test]
+    fn test_fungible_asset_balances_keep_asset_type_v1() {
+        let statements = current_fungible_asset_balances().upsert_statements();
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].contains("h.token_standard = 'v1'"));
+        assert!(!statements[0].contains("is_primary = EXCLUDED.is_primary"));
+        assert!(statements[1].contains("h.token_standard = 'v2'"));
+        assert!(statements[1].contains(
+            "asset_type_v1 = COALESCE(EXCLUDED.asset_type_v1, \
+             current_fungible_asset_balances.asset_type_v1)"
+        ));
+        assert!(statements[1].contains(
+            "WHEN '0x000000000000000000000000000000000000000000000000000000000000000a' \
+             THEN '0x1::libra2_coin::Libra2Coin'"
+        ));
+    }
+}
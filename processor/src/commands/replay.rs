@@ -0,0 +1,347 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    config::indexer_processor_config::IndexerProcessorConfig,
+    processors::capture::capture_writer_step::CAPTURE_FILE_EXTENSION,
+    utils::raw_data_server::serve_raw_data,
+};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::{
+    libra2_protos::{
+        indexer::v1::{raw_data_server::RawData, GetTransactionsRequest, TransactionsResponse},
+        transaction::v1::Transaction,
+    },
+    server_framework::RunnableConfig,
+};
+use clap::Args;
+use flate2::read::GzDecoder;
+use futures::Stream;
+use prost::Message;
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+};
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+#[derive(Clone, Debug, Args)]
+pub struct ReplayArgs {
+    /// Transaction file, or directory that is searched recursively for transaction files.
+    ///
+    /// `.json` files hold one transaction or an array of transactions. `.pb` files hold
+    /// length-delimited `Transaction` protos, and `.pb.gz` files are written by the capture
+    /// processor.
+    #[clap(long)]
+    pub path: PathBuf,
+    /// Chain id to report to the processor. Must match the chain id in the processor's database.
+    #[clap(long)]
+    pub chain_id: u64,
+}
+
+/// Reads the transactions in `path` and returns them sorted by version. Fails if the files don't
+/// cover a continuous range of versions, since processors expect every version of the range.
+pub fn read_transaction_files(path: &Path) -> Result<Vec<Transaction>> {
+    let mut transactions = vec![];
+    if path.is_dir() {
+        let mut directories = vec![path.to_path_buf()];
+        while let Some(directory) = directories.pop() {
+            for entry in std::fs::read_dir(&directory)
+                .with_context(|| format!("Failed to read directory {directory:?}"))?
+            {
+                let entry_path = entry?.path();
+                if entry_path.is_dir() {
+                    directories.push(entry_path);
+                } else if let Some(format) = transaction_file_format(&entry_path) {
+                    transactions.extend(read_transaction_file(&entry_path, format)?);
+                } else {
+                    warn!(path = ?entry_path, "Skipping file that is not a transaction file");
+                }
+            }
+        }
+    } else if let Some(format) = transaction_file_format(path) {
+        transactions = read_transaction_file(path, format)?;
+    } else {
+        anyhow::bail!("{path:?} is not a .json, .pb or .{CAPTURE_FILE_EXTENSION} transaction file");
+    }
+
+    transactions.sort_by_key(|txn| txn.version);
+    let count = transactions.len();
+    transactions.dedup_by_key(|txn| txn.version);
+    if transactions.len() < count {
+        warn!(
+            duplicates = count - transactions.len(),
+            "Dropped transactions with duplicate versions"
+        );
+    }
+    if let Some(pair) = transactions
+        .windows(2)
+        .find(|pair| pair[1].version != pair[0].version + 1)
+    {
+        anyhow::bail!(
+            "Versions {} to {} are missing from {path:?}",
+            pair[0].version + 1,
+            pair[1].version - 1
+        );
+    }
+    Ok(transactions)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TransactionFileFormat {
+    Json,
+    Protobuf,
+    /// Written by the capture processor
+    CompressedProtobuf,
+}
+
+fn transaction_file_format(path: &Path) -> Option<TransactionFileFormat> {
+    let file_name = path.file_name()?.to_str()?;
+    if file_name.ends_with(".json") {
+        Some(TransactionFileFormat::Json)
+    } else if file_name.ends_with(".pb") {
+        Some(TransactionFileFormat::Protobuf)
+    } else if file_name.ends_with(&format!(".{CAPTURE_FILE_EXTENSION}")) {
+        Some(TransactionFileFormat::CompressedProtobuf)
+    } else {
+        None
+    }
+}
+
+fn read_transaction_file(path: &Path, format: TransactionFileFormat) -> Result<Vec<Transaction>> {
+    let mut bytes = std::fs::read(path).with_context(|| format!("Failed to read {path:?}"))?;
+    if format == TransactionFileFormat::Json {
+        let json: serde_json::Value = serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to parse JSON in {path:?}"))?;
+        let transactions = if json.is_array() {
+            serde_json::from_value(json)
+        } else {
+            serde_json::from_value(json).map(|txn| vec![txn])
+        };
+        return transactions
+            .with_context(|| format!("Failed to deserialize transactions in {path:?}"));
+    }
+
+    if format == TransactionFileFormat::CompressedProtobuf {
+        let mut decompressed = vec![];
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut decompressed)
+            .with_context(|| format!("Failed to decompress {path:?}"))?;
+        bytes = decompressed;
+    }
+    let mut buffer = bytes.as_slice();
+    let mut transactions = vec![];
+    while !buffer.is_empty() {
+        transactions.push(
+            Transaction::decode_length_delimited(&mut buffer)
+                .with_context(|| format!("Failed to decode transaction in {path:?}"))?,
+        );
+    }
+    Ok(transactions)
+}
+
+type TransactionsResponseStream =
+    Pin<Box<dyn Stream<Item = Result<TransactionsResponse, Status>> + Send>>;
+
+/// Serves transactions from memory in place of the indexer gRPC data service.
+pub struct ReplayService {
+    transactions: Arc<Vec<Transaction>>,
+    chain_id: u64,
+}
+
+impl ReplayService {
+    /// `transactions` must be sorted by version, without gaps.
+    pub fn new(transactions: Vec<Transaction>, chain_id: u64) -> Self {
+        Self {
+            transactions: Arc::new(transactions),
+            chain_id,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl RawData for ReplayService {
+    type GetTransactionsStream = TransactionsResponseStream;
+
+    async fn get_transactions(
+        &self,
+        request: Request<GetTransactionsRequest>,
+    ) -> Result<Response<Self::GetTransactionsStream>, Status> {
+        let request = request.into_inner();
+        let first_version = self.transactions.first().map_or(0, |txn| txn.version);
+        let starting_version = request.starting_version.unwrap_or(first_version);
+        if starting_version < first_version {
+            return Err(Status::out_of_range(format!(
+                "Version {starting_version} is before the first replayed version {first_version}"
+            )));
+        }
+        let ending_version = request
+            .transactions_count
+            .map_or(u64::MAX, |count| starting_version.saturating_add(count));
+        let batch_size = request
+            .batch_size
+            .map_or(DEFAULT_BATCH_SIZE, |batch_size| batch_size as usize)
+            .max(1);
+
+        let start = self
+            .transactions
+            .partition_point(|txn| txn.version < starting_version);
+        let end = self
+            .transactions
+            .partition_point(|txn| txn.version < ending_version);
+
+        let transactions = self.transactions.clone();
+        let chain_id = self.chain_id;
+        let responses =
+            futures::stream::iter((start..end).step_by(batch_size).map(move |batch_start| {
+                let batch_end = (batch_start + batch_size).min(end);
+                Ok(TransactionsResponse {
+                    transactions: transactions[batch_start..batch_end].to_vec(),
+                    chain_id: Some(chain_id),
+                    ..Default::default()
+                })
+            }));
+        Ok(Response::new(Box::pin(responses)))
+    }
+}
+
+/// Runs the processor against the transactions in `args.path` instead of the transaction stream.
+///
+/// Everything else, including the database and the checkpoints, comes from the config. The
+/// processor exits after the last transaction in the files.
+pub async fn run_replay(config: &IndexerProcessorConfig, args: &ReplayArgs) -> Result<()> {
+    let transactions = read_transaction_files(&args.path)?;
+    let (Some(first), Some(last)) = (transactions.first(), transactions.last()) else {
+        anyhow::bail!("No transactions found in {:?}", args.path);
+    };
+    let (first_version, last_version) = (first.version, last.version);
+    info!(
+        count = transactions.len(),
+        first_version, last_version, "Replaying transactions from {:?}", args.path
+    );
+
+    let mut config = config.clone();
+    let stream_config = &mut config.transaction_stream_config;
+    stream_config.indexer_grpc_data_service_address =
+        serve_raw_data(ReplayService::new(transactions, args.chain_id)).await?;
+    stream_config.request_ending_version = Some(
+        stream_config
+            .request_ending_version
+            .map_or(last_version, |version| version.min(last_version)),
+    );
+    config.run().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn transaction(version: u64) -> Transaction {
+        Transaction {
+            version,
+            ..Transaction::default()
+        }
+    }
+
+    fn versions(transactions: &[Transaction]) -> Vec<u64> {
+        transactions.iter().map(|txn| txn.version).collect()
+    }
+
+    #[test]
+    fn test_read_transaction_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let nested = directory.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+
+        std::fs::write(
+            directory.path().join("single.json"),
+            serde_json::to_vec(&transaction(4)).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(
+            nested.join("array.json"),
+            serde_json::to_vec(&vec![transaction(2), transaction(1)]).unwrap(),
+        )
+        .unwrap();
+        let mut protobuf = vec![];
+        for version in [3, 4] {
+            transaction(version)
+                .encode_length_delimited(&mut protobuf)
+                .unwrap();
+        }
+        std::fs::write(directory.path().join("transactions.pb"), protobuf).unwrap();
+        std::fs::write(directory.path().join("README.md"), "not a transaction").unwrap();
+
+        let transactions = read_transaction_files(directory.path()).unwrap();
+        assert_eq!(versions(&transactions), vec![1, 2, 3, 4]);
+
+        let transactions = read_transaction_files(&nested.join("array.json")).unwrap();
+        assert_eq!(versions(&transactions), vec![1, 2]);
+        assert!(read_transaction_files(&directory.path().join("README.md")).is_err());
+        assert!(read_transaction_files(&directory.path().join("transactions.pb")).is_ok());
+
+        // Gaps in the versions are errors, in a single file as well as across files
+        std::fs::write(
+            directory.path().join("gap.json"),
+            serde_json::to_vec(&vec![transaction(1), transaction(3)]).unwrap(),
+        )
+        .unwrap();
+        assert!(read_transaction_files(&directory.path().join("gap.json")).is_err());
+        std::fs::remove_file(directory.path().join("gap.json")).unwrap();
+        std::fs::write(
+            nested.join("gap.json"),
+            serde_json::to_vec(&transaction(7)).unwrap(),
+        )
+        .unwrap();
+        assert!(read_transaction_files(directory.path()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_transactions() {
+        let service = ReplayService::new((1..=5).map(transaction).collect(), 7);
+
+        let response = service
+            .get_transactions(Request::new(GetTransactionsRequest {
+                starting_version: Some(2),
+                transactions_count: Some(3),
+                batch_size: Some(2),
+                ..GetTransactionsRequest::default()
+            }))
+            .await
+            .unwrap();
+        let responses: Vec<_> = response.into_inner().collect().await;
+        let responses: Vec<_> = responses.into_iter().map(Result::unwrap).collect();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(versions(&responses[0].transactions), vec![2, 3]);
+        assert_eq!(versions(&responses[1].transactions), vec![4]);
+        assert!(responses
+            .iter()
+            .all(|response| response.chain_id == Some(7)));
+
+        // Without a starting version, the stream starts at the first transaction
+        let response = service
+            .get_transactions(Request::new(GetTransactionsRequest::default()))
+            .await
+            .unwrap();
+        let responses: Vec<_> = response.into_inner().collect().await;
+        assert_eq!(responses.len(), 1);
+        assert_eq!(
+            versions(&responses[0].as_ref().unwrap().transactions),
+            vec![1, 2, 3, 4, 5]
+        );
+
+        // Versions before the files can't be replayed
+        assert!(service
+            .get_transactions(Request::new(GetTransactionsRequest {
+                starting_version: Some(0),
+                ..GetTransactionsRequest::default()
+            }))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    commands::recompute::CurrentTableRecompute,
+    config::{
+        db_config::DbConfig, indexer_processor_config::IndexerProcessorConfig,
+        processor_config::ProcessorName,
+    },
+    db::backfill_processor_status::BackfillStatus,
+    schema::backfill_processor_status,
+};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::postgres::{
+    processor_metadata_schema::processor_metadata::processor_status, utils::database::new_db_pool,
+};
+use clap::Args;
+use diesel::{sql_types::BigInt, ExpressionMethods, QueryDsl, TextExpressionMethods};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+use tracing::info;
+
+#[derive(Clone, Debug, Args)]
+pub struct RollbackArgs {
+    /// Last version to keep. Every row written for a transaction above this version is deleted.
+    #[clap(long)]
+    pub version: u64,
+    /// Print what would be deleted and rebuilt without touching the database.
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// A table that rows are only ever appended to, one or more rows per transaction.
+///
+/// Rolling these back is exact: deleting every row above the version leaves the table as it was
+/// right after that version was processed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionedTable {
+    pub table_name: &'static str,
+    pub version_column: &'static str,
+}
+
+const fn versioned(table_name: &'static str, version_column: &'static str) -> VersionedTable {
+    VersionedTable {
+        table_name,
+        version_column,
+    }
+}
+
+/// The tables a processor writes to, split by how they have to be rolled back.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RollbackTables {
+    /// Tables where every row above the rollback version is deleted.
+    pub versioned_tables: Vec<VersionedTable>,
+    /// Tables that are upserted in place. A row above the rollback version may have overwritten
+    /// state from before it, so these can't be rolled back by deleting rows and have to be
+    /// rebuilt from the versioned tables instead.
+    pub current_tables: Vec<&'static str>,
+}
+
+impl RollbackTables {
+    /// Get the tables written by a Postgres processor. Returns an error for processors that
+    /// don't write to Postgres.
+    pub fn for_processor(processor: ProcessorName) -> Result<Self> {
+        let (versioned_tables, current_tables) = match processor {
+            ProcessorName::AccountActivityProcessor => (
+                vec![versioned("account_activities", "transaction_version")],
+                vec![],
+            ),
+            ProcessorName::AccountRestorationProcessor => (vec![], vec![
+                "auth_key_account_addresses",
+                "public_key_auth_keys",
+            ]),
+            ProcessorName::AccountTransactionsProcessor => (
+                vec![versioned("account_transactions", "transaction_version")],
+                vec![],
+            ),
+            ProcessorName::AnsProcessor => (
+                vec![
+                    versioned("ans_lookup_v2", "transaction_version"),
+                    versioned("ans_primary_name_v2", "transaction_version"),
+                    versioned("ans_renewal_activities", "transaction_version"),
+                ],
+                vec!["current_ans_lookup_v2", "current_ans_primary_name_v2"],
+            ),
+            ProcessorName::DefaultProcessor => (
+                vec![
+                    versioned("block_metadata_transactions", "version"),
+                    versioned("move_modules", "transaction_version"),
+                    versioned("table_items", "transaction_version"),
+                ],
+                vec!["current_table_items", "table_metadatas"],
+            ),
+            ProcessorName::EventsProcessor => {
+                (vec![versioned("events", "transaction_version")], vec![])
+            },
+            ProcessorName::EventFilterProcessor => (
+                vec![versioned("filtered_events", "transaction_version")],
+                vec![],
+            ),
+            ProcessorName::FungibleAssetProcessor => (
+                vec![
+                    versioned("coin_supply", "transaction_version"),
+                    versioned("fungible_asset_activities", "transaction_version"),
+                    versioned("fungible_asset_balances", "transaction_version"),
+                ],
+                vec![
+                    "current_fungible_asset_balances",
+                    "fungible_asset_metadata",
+                    "fungible_asset_to_coin_mappings",
+                ],
+            ),
+            ProcessorName::UserTransactionProcessor => (
+                vec![
+                    versioned("signatures", "transaction_version"),
+                    versioned("user_transactions", "version"),
+                ],
+                vec![],
+            ),
+            ProcessorName::StakeProcessor => (
+                vec![
+                    versioned("delegated_staking_activities", "transaction_version"),
+                    versioned("delegated_staking_pool_balances", "transaction_version"),
+                    // Pools are only inserted once, when they are first seen
+                    versioned("delegated_staking_pools", "first_transaction_version"),
+                    versioned("delegator_balances", "transaction_version"),
+                    versioned("proposal_votes", "transaction_version"),
+                ],
+                vec![
+                    "current_delegated_staking_pool_balances",
+                    "current_delegated_voter",
+                    "current_delegator_balances",
+                    "current_staking_pool_voter",
+                ],
+            ),
+            ProcessorName::TokenV2Processor => (
+                vec![
+                    versioned("token_activities_v2", "transaction_version"),
+                    // Only written when they're in tables_to_write
+                    versioned("collections_v2", "transaction_version"),
+                    versioned("token_datas_v2", "transaction_version"),
+                    versioned("token_ownerships_v2", "transaction_version"),
+                ],
+                vec![
+                    "current_collections_v2",
+                    "current_token_datas_v2",
+                    "current_token_ownerships_v2",
+                    "current_token_pending_claims",
+                    "current_token_royalty_v1",
+                ],
+            ),
+            ProcessorName::ObjectsProcessor => {
+                (vec![versioned("objects", "transaction_version")], vec![
+                    "current_objects",
+                ])
+            },
+            ProcessorName::MonitoringProcessor | ProcessorName::CaptureProcessor => {
+                (vec![], vec![])
+            },
+            // Spam flags aren't tied to a version, so they're kept
+            ProcessorName::SpamAssetProcessor => (vec![], vec![]),
+            ProcessorName::NftPointsProcessor => {
+                (vec![versioned("nft_points", "transaction_version")], vec![])
+            },
+            ProcessorName::GasFeeProcessor => {
+                (vec![versioned("gas_fees", "transaction_version")], vec![])
+            },
+            ProcessorName::ParquetDefaultProcessor
+            | ProcessorName::ParquetObjectsProcessor
+            | ProcessorName::ParquetUserTransactionProcessor
+            | ProcessorName::ParquetEventsProcessor
+            | ProcessorName::ParquetAnsProcessor
+            | ProcessorName::ParquetFungibleAssetProcessor
+            | ProcessorName::ParquetTransactionMetadataProcessor
+            | ProcessorName::ParquetAccountTransactionsProcessor
+            | ProcessorName::ParquetTokenV2Processor
+            | ProcessorName::ParquetStakeProcessor
+            | ProcessorName::ParquetNftPointsProcessor
+            | ProcessorName::ParquetAccountRestorationProcessor
+            | ProcessorName::ParquetGasFeeProcessor => {
+                anyhow::bail!("Rollback is not supported for parquet processor {processor}")
+            },
+        };
+
+        Ok(Self {
+            versioned_tables,
+            current_tables,
+        })
+    }
+}
+
+/// Escapes the wildcards of a `LIKE` pattern, so `value` only matches itself.
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Deletes every row above `args.version` from the versioned tables of the processor and rewinds
+/// its `processor_status` and `backfill_processor_status` checkpoints, all in one transaction.
+///
+/// The current tables of the processor are only reported, since they have to be rebuilt.
+pub async fn run_rollback(config: &IndexerProcessorConfig, args: &RollbackArgs) -> Result<()> {
+    let processor_name = config.processor_config.name();
+    let processor = ProcessorName::from(&config.processor_config);
+    let tables = RollbackTables::for_processor(processor)?;
+    let postgres_config = match &config.db_config {
+        DbConfig::PostgresConfig(postgres_config) => postgres_config,
+        _ => anyhow::bail!(
+            "Rollback requires a PostgresConfig, got {:?}",
+            config.db_config
+        ),
+    };
+    let version = i64::try_from(args.version).context("Rollback version is too large")?;
+
+    for table in &tables.versioned_tables {
+        println!(
+            "Deleting rows from {} where {} > {}",
+            table.table_name, table.version_column, version
+        );
+    }
+    println!(
+        "Rewinding processor_status and backfill_processor_status of {processor_name} to {version}"
+    );
+
+    if !args.dry_run {
+        let db_pool = new_db_pool(&postgres_config.connection_string, Some(1))
+            .await
+            .context("Failed to create connection pool for PostgresConfig")?;
+        let mut conn = db_pool
+            .get()
+            .await
+            .context("Failed to get database connection")?;
+
+        let versioned_tables = tables.versioned_tables.clone();
+        let deleted_rows = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let mut deleted_rows = vec![];
+                    for table in versioned_tables {
+                        let rows = diesel::sql_query(format!(
+                            "DELETE FROM {} WHERE {} > $1",
+                            table.table_name, table.version_column
+                        ))
+                        .bind::<BigInt, _>(version)
+                        .execute(conn)
+                        .await?;
+                        deleted_rows.push((table.table_name, rows));
+                    }
+
+                    diesel::update(
+                        processor_status::table
+                            .filter(processor_status::processor.eq(processor_name))
+                            .filter(processor_status::last_success_version.gt(version)),
+                    )
+                    .set((
+                        processor_status::last_success_version.eq(version),
+                        processor_status::last_updated.eq(diesel::dsl::now),
+                        processor_status::last_transaction_timestamp
+                            .eq(None::<chrono::NaiveDateTime>),
+                    ))
+                    .execute(conn)
+                    .await?;
+
+                    // Backfill aliases are formatted as `{processor_name}_{backfill_id}`
+                    diesel::update(
+                        backfill_processor_status::table
+                            .filter(
+                                backfill_processor_status::backfill_alias
+                                    .like(format!("{}\\_%", escape_like(processor_name))),
+                            )
+                            .filter(backfill_processor_status::last_success_version.gt(version)),
+                    )
+                    .set((
+                        backfill_processor_status::backfill_status.eq(BackfillStatus::InProgress),
+                        backfill_processor_status::last_success_version.eq(version),
+                        backfill_processor_status::last_updated.eq(diesel::dsl::now),
+                        backfill_processor_status::last_transaction_timestamp
+                            .eq(None::<chrono::NaiveDateTime>),
+                    ))
+                    .execute(conn)
+                    .await?;
+
+                    Ok(deleted_rows)
+                }
+                .scope_boxed()
+            })
+            .await
+            .context("Failed to roll back, no changes were made")?;
+
+        for (table_name, rows) in deleted_rows {
+            info!(table_name, rows, "Deleted rows above version {}", version);
+        }
+    }
+
+    // Tables whose history table isn't written can't be recomputed either
+    let tables_to_write = config.processor_config.tables_to_write();
+    let recomputable_tables: Vec<&str> = CurrentTableRecompute::for_processor(processor)
+        .map(|tables| {
+            tables
+                .iter()
+                .filter(|table| table.history_is_written(&tables_to_write))
+                .map(|table| table.current_table)
+                .collect()
+        })
+        .unwrap_or_default();
+    let (recomputed_tables, reindexed_tables): (Vec<&str>, Vec<&str>) = tables
+        .current_tables
+        .iter()
+        .partition(|table_name| recomputable_tables.contains(table_name));
+    if !recomputed_tables.is_empty() {
+        println!(
+            "The following tables may contain state from above version {} and must be rebuilt \
+             with the recompute command: {}",
+            version,
+            recomputed_tables.join(", ")
+        );
+    }
+    if !reindexed_tables.is_empty() {
+        println!(
+            "The following tables may contain state from above version {} and can't be \
+             recomputed, they must be reindexed from version {}: {}",
+            version,
+            version,
+            reindexed_tables.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::tests::table_definition;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_rollback_tables_exist_in_schema() {
+        for processor in ProcessorName::iter() {
+            let Ok(tables) = RollbackTables::for_processor(processor) else {
+                continue;
+            };
+            for table in tables.versioned_tables {
+                let definition = table_definition(table.table_name).unwrap_or_else(|| {
+                    panic!(
+                        "{processor}: table {} is not in schema.rs",
+                        table.table_name
+                    )
+                });
+                assert!(
+                    definition.contains(&format!("{} -> Int8", table.version_column)),
+                    "{processor}: {} has no version column {}",
+                    table.table_name,
+                    table.version_column
+                );
+            }
+            for table_name in tables.current_tables {
+                assert!(
+                    table_definition(table_name).is_some(),
+                    "{processor}: table {table_name} is not in schema.rs"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("events_processor"), "events\\_processor");
+        assert_eq!(escape_like("100%\\"), "100\\%\\\\");
+    }
+
+    #[test]
+    fn test_parquet_processors_are_not_supported() {
+        assert!(RollbackTables::for_processor(ProcessorName::ParquetEventsProcessor).is_err());
+        assert!(RollbackTables::for_processor(ProcessorName::EventsProcessor).is_ok());
+    }
+}
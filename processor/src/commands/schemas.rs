@@ -0,0 +1,26 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::parquet_processors::parquet_utils::schema_registry::parquet_table_schemas;
+use anyhow::{Context, Result};
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Args)]
+pub struct SchemasArgs {
+    /// Write the schemas to this file instead of stdout.
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Prints the current schema and schema version of every parquet table as JSON. Doesn't need a
+/// config, since the schemas don't depend on the processor.
+pub fn run_schemas(args: &SchemasArgs) -> Result<()> {
+    let json = serde_json::to_string_pretty(&parquet_table_schemas())?;
+    match &args.output {
+        Some(path) => std::fs::write(path, json)
+            .with_context(|| format!("Failed to write schemas to {path:?}"))?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
@@ -33,6 +33,7 @@ use serde::{Deserialize, Serialize};
 pub enum DbConfig {
     PostgresConfig(PostgresConfig),
     ParquetConfig(ParquetConfig),
+    ClickhouseConfig(ClickhouseConfig),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -50,6 +51,43 @@ impl PostgresConfig {
     }
 }
 
+/// Writes the `Parquet*` models of the parquet processors into ClickHouse tables instead of
+/// uploading parquet files. Checkpoints are stored in the same ClickHouse database.
+///
+/// Example:
+/// ```yaml
+/// db_config:
+///   type: clickhouse_config
+///   url: "http://localhost:8123"
+///   database: indexer
+///   user: default
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClickhouseConfig {
+    // URL of the ClickHouse HTTP interface
+    pub url: String,
+    #[serde(default = "ClickhouseConfig::default_database")]
+    pub database: String,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    // Timeout for a single request to ClickHouse
+    #[serde(default = "ClickhouseConfig::default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+}
+
+impl ClickhouseConfig {
+    pub fn default_database() -> String {
+        "default".to_string()
+    }
+
+    pub const fn default_request_timeout_secs() -> u64 {
+        60
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct ParquetConfig {
@@ -64,4 +102,81 @@ pub struct ParquetConfig {
     pub bucket_name: String,
     #[serde(default)]
     pub bucket_root: String,
+    // Storage backend that parquet files are uploaded to. Defaults to GCS.
+    #[serde(default)]
+    pub storage_backend: ParquetStorageBackendConfig,
+    // How parquet files are laid out under each table. Defaults to a flat layout.
+    #[serde(default)]
+    pub partition_scheme: ParquetPartitionScheme,
+}
+
+/// How parquet files are partitioned under `bucket_root`.
+///
+/// - `none`: `{table}/v{schema_version}/{start_version}_{end_version}.parquet`
+/// - `date`: `table={table}/schema_version={schema_version}/date={YYYY-MM-DD}/...`, from the
+///   timestamp of each row. Tables without a timestamp go to `date=__HIVE_DEFAULT_PARTITION__`.
+/// - `version_bucket`: `table={table}/schema_version={schema_version}/version_bucket={bucket_start}/...`,
+///   where `bucket_start` is the row's version rounded down to a multiple of `bucket_size`.
+///
+/// `schema_version` is the `HasSchemaVersion::SCHEMA_VERSION` of the table's model.
+///
+/// Example:
+/// ```yaml
+/// partition_scheme:
+///   type: version_bucket
+///   bucket_size: 1000000
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParquetPartitionScheme {
+    #[default]
+    None,
+    Date,
+    VersionBucket {
+        bucket_size: i64,
+    },
+}
+
+/// The storage backends that parquet files can be uploaded to.
+///
+/// `bucket_name` and `bucket_root` from `ParquetConfig` apply to every object store backend.
+/// The local backend ignores `bucket_name` and writes files under `root_dir/bucket_root`.
+///
+/// Example:
+/// ```yaml
+/// storage_backend:
+///   type: s3
+///   region: us-east-1
+///   endpoint: "http://localhost:9000"
+///   allow_http: true
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParquetStorageBackendConfig {
+    #[default]
+    Gcs,
+    S3(S3StorageConfig),
+    Local(LocalStorageConfig),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct S3StorageConfig {
+    pub region: String,
+    // Custom endpoint for S3-compatible stores, e.g. MinIO or R2
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    // Credentials fall back to the AWS_* environment variables when not set
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+    #[serde(default)]
+    pub secret_access_key: Option<String>,
+    #[serde(default)]
+    pub allow_http: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocalStorageConfig {
+    pub root_dir: String,
 }
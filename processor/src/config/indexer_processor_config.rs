@@ -6,11 +6,14 @@ use super::{
 };
 use crate::{
     parquet_processors::{
+        parquet_account_restoration::parquet_account_restoration_processor::ParquetAccountRestorationProcessor,
         parquet_account_transactions::parquet_account_transactions_processor::ParquetAccountTransactionsProcessor,
         parquet_ans::parquet_ans_processor::ParquetAnsProcessor,
         parquet_default::parquet_default_processor::ParquetDefaultProcessor,
         parquet_events::parquet_events_processor::ParquetEventsProcessor,
         parquet_fungible_asset::parquet_fungible_asset_processor::ParquetFungibleAssetProcessor,
+        parquet_gas_fees::parquet_gas_fee_processor::ParquetGasFeeProcessor,
+        parquet_nft_points::parquet_nft_points_processor::ParquetNftPointsProcessor,
         parquet_objects::parquet_objects_processor::ParquetObjectsProcessor,
         parquet_stake::parquet_stake_processor::ParquetStakeProcessor,
         parquet_token_v2::parquet_token_v2_processor::ParquetTokenV2Processor,
@@ -18,17 +21,23 @@ use crate::{
         parquet_user_transaction::parquet_user_transaction_processor::ParquetUserTransactionProcessor,
     },
     processors::{
+        account_activity::account_activity_processor::AccountActivityProcessor,
         account_restoration::account_restoration_processor::AccountRestorationProcessor,
         account_transactions::account_transactions_processor::AccountTransactionsProcessor,
-        ans::ans_processor::AnsProcessor, default::default_processor::DefaultProcessor,
+        ans::ans_processor::AnsProcessor, capture::capture_processor::CaptureProcessor,
+        default::default_processor::DefaultProcessor,
+        event_filter::event_filter_processor::EventFilterProcessor,
         events::events_processor::EventsProcessor,
         fungible_asset::fungible_asset_processor::FungibleAssetProcessor,
         gas_fees::gas_fee_processor::GasFeeProcessor,
         monitoring::monitoring_processor::MonitoringProcessor,
-        objects::objects_processor::ObjectsProcessor, stake::stake_processor::StakeProcessor,
-        token_v2::token_v2_processor::TokenV2Processor,
+        nft_points::nft_points_processor::NftPointsProcessor,
+        objects::objects_processor::ObjectsProcessor,
+        spam_assets::spam_asset_processor::SpamAssetProcessor,
+        stake::stake_processor::StakeProcessor, token_v2::token_v2_processor::TokenV2Processor,
         user_transaction::user_transaction_processor::UserTransactionProcessor,
     },
+    utils::transaction_fanout::run_fanout,
 };
 use anyhow::Result;
 use libra2_indexer_processor_sdk::{
@@ -47,11 +56,18 @@ pub struct IndexerProcessorConfig {
     pub transaction_stream_config: TransactionStreamConfig,
     pub db_config: DbConfig,
     pub processor_mode: ProcessorMode,
+    /// More processors to run from the same transaction stream, each with its own checkpoint. They
+    /// share `db_config` and `processor_mode`, and the stream starts from the lowest checkpoint.
+    #[serde(default)]
+    pub additional_processor_configs: Vec<ProcessorConfig>,
 }
 
 #[async_trait::async_trait]
 impl RunnableConfig for IndexerProcessorConfig {
     async fn run(&self) -> Result<()> {
+        if !self.additional_processor_configs.is_empty() {
+            return run_fanout(self).await;
+        }
         match self.processor_config {
             ProcessorConfig::AccountTransactionsProcessor(_) => {
                 let acc_txns_processor = AccountTransactionsProcessor::new(self.clone()).await?;
@@ -61,6 +77,11 @@ impl RunnableConfig for IndexerProcessorConfig {
                 let ans_processor = AnsProcessor::new(self.clone()).await?;
                 ans_processor.run_processor().await
             },
+            ProcessorConfig::AccountActivityProcessor(_) => {
+                let account_activity_processor =
+                    AccountActivityProcessor::new(self.clone()).await?;
+                account_activity_processor.run_processor().await
+            },
             ProcessorConfig::AccountRestorationProcessor(_) => {
                 let acc_rest_processor = AccountRestorationProcessor::new(self.clone()).await?;
                 acc_rest_processor.run_processor().await
@@ -101,6 +122,22 @@ impl RunnableConfig for IndexerProcessorConfig {
                 let gas_fee_processor = GasFeeProcessor::new(self.clone()).await?;
                 gas_fee_processor.run_processor().await
             },
+            ProcessorConfig::CaptureProcessor(_) => {
+                let capture_processor = CaptureProcessor::new(self.clone()).await?;
+                capture_processor.run_processor().await
+            },
+            ProcessorConfig::EventFilterProcessor(_) => {
+                let event_filter_processor = EventFilterProcessor::new(self.clone()).await?;
+                event_filter_processor.run_processor().await
+            },
+            ProcessorConfig::SpamAssetProcessor(_) => {
+                let spam_asset_processor = SpamAssetProcessor::new(self.clone()).await?;
+                spam_asset_processor.run_processor().await
+            },
+            ProcessorConfig::NftPointsProcessor(_) => {
+                let nft_points_processor = NftPointsProcessor::new(self.clone()).await?;
+                nft_points_processor.run_processor().await
+            },
             ProcessorConfig::ParquetDefaultProcessor(_) => {
                 let parquet_default_processor = ParquetDefaultProcessor::new(self.clone()).await?;
                 parquet_default_processor.run_processor().await
@@ -145,6 +182,20 @@ impl RunnableConfig for IndexerProcessorConfig {
                 let parquet_objects_processor = ParquetObjectsProcessor::new(self.clone()).await?;
                 parquet_objects_processor.run_processor().await
             },
+            ProcessorConfig::ParquetNftPointsProcessor(_) => {
+                let parquet_nft_points_processor =
+                    ParquetNftPointsProcessor::new(self.clone()).await?;
+                parquet_nft_points_processor.run_processor().await
+            },
+            ProcessorConfig::ParquetAccountRestorationProcessor(_) => {
+                let parquet_account_restoration_processor =
+                    ParquetAccountRestorationProcessor::new(self.clone()).await?;
+                parquet_account_restoration_processor.run_processor().await
+            },
+            ProcessorConfig::ParquetGasFeeProcessor(_) => {
+                let parquet_gas_fee_processor = ParquetGasFeeProcessor::new(self.clone()).await?;
+                parquet_gas_fee_processor.run_processor().await
+            },
         }
     }
 
@@ -5,10 +5,16 @@
 use crate::{
     parquet_processors::{
         parquet_ans::parquet_ans_processor::ParquetAnsProcessorConfig,
+        parquet_nft_points::parquet_nft_points_processor::ParquetNftPointsProcessorConfig,
         parquet_transaction_metadata::transaction_metadata_models::write_set_size_info::ParquetWriteSetSize,
         parquet_utils::util::{format_table_name, NamedTable, VALID_TABLE_NAMES},
     },
     processors::{
+        account_activity::account_activity_processor::AccountActivityProcessorConfig,
+        account_restoration::account_restoration_models::{
+            auth_key_account_addresses::ParquetAuthKeyAccountAddress,
+            public_key_auth_keys::ParquetPublicKeyAuthKey,
+        },
         account_transactions::account_transactions_model::ParquetAccountTransaction,
         ans::{
             ans_processor::AnsProcessorConfig,
@@ -17,6 +23,7 @@ use crate::{
                 ans_primary_name_v2::{ParquetAnsPrimaryNameV2, ParquetCurrentAnsPrimaryNameV2},
             },
         },
+        capture::capture_processor::CaptureProcessorConfig,
         default::models::{
             block_metadata_transactions::ParquetBlockMetadataTransaction,
             move_modules::ParquetMoveModule,
@@ -25,22 +32,39 @@ use crate::{
             transactions::ParquetTransaction,
             write_set_changes::ParquetWriteSetChange,
         },
+        event_filter::event_filter_processor::EventFilterProcessorConfig,
         events::events_model::ParquetEvent,
-        fungible_asset::fungible_asset_models::{
-            v2_fungible_asset_activities::ParquetFungibleAssetActivity,
-            v2_fungible_asset_balances::ParquetFungibleAssetBalance,
-            v2_fungible_asset_to_coin_mappings::ParquetFungibleAssetToCoinMapping,
-            v2_fungible_metadata::ParquetFungibleAssetMetadataModel,
+        fungible_asset::{
+            coin_models::{
+                coin_balances::ParquetCoinBalance, coin_infos::ParquetCoinInfo,
+                coin_supply::ParquetCoinSupply,
+            },
+            fungible_asset_models::{
+                v2_fungible_asset_activities::ParquetFungibleAssetActivity,
+                v2_fungible_asset_balances::ParquetFungibleAssetBalance,
+                v2_fungible_asset_to_coin_mappings::ParquetFungibleAssetToCoinMapping,
+                v2_fungible_metadata::ParquetFungibleAssetMetadataModel,
+            },
+        },
+        gas_fees::models::ParquetGasFee,
+        nft_points::{
+            nft_points_model::ParquetNftPoints, nft_points_processor::NftPointsProcessorConfig,
         },
         objects::{
             objects_processor::ObjectsProcessorConfig,
             v2_objects_models::{ParquetCurrentObject, ParquetObject},
         },
+        spam_assets::spam_asset_processor::SpamAssetProcessorConfig,
         stake::{
             models::{
                 delegator_activities::ParquetDelegatedStakingActivity,
                 delegator_balances::{ParquetCurrentDelegatorBalance, ParquetDelegatorBalance},
+                delegator_pools::{
+                    ParquetCurrentDelegatorPoolBalance, ParquetDelegatorPool,
+                    ParquetDelegatorPoolBalance,
+                },
                 proposal_votes::ParquetProposalVote,
+                staking_pool_voter::ParquetCurrentStakingPoolVoter,
             },
             stake_processor::StakeProcessorConfig,
         },
@@ -59,10 +83,11 @@ use crate::{
         },
         user_transaction::models::user_transactions::ParquetUserTransaction,
     },
+    utils::table_flags::TableFlags,
 };
 use ahash::AHashMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// This enum captures the configs for all the different processors that are defined.
 ///
@@ -98,6 +123,7 @@ use std::collections::HashSet;
     strum(serialize_all = "snake_case")
 )]
 pub enum ProcessorConfig {
+    AccountActivityProcessor(AccountActivityProcessorConfig),
     AccountRestorationProcessor(DefaultProcessorConfig),
     AccountTransactionsProcessor(DefaultProcessorConfig),
     AnsProcessor(AnsProcessorConfig),
@@ -110,6 +136,10 @@ pub enum ProcessorConfig {
     ObjectsProcessor(ObjectsProcessorConfig),
     MonitoringProcessor(DefaultProcessorConfig),
     GasFeeProcessor(DefaultProcessorConfig),
+    CaptureProcessor(CaptureProcessorConfig),
+    EventFilterProcessor(EventFilterProcessorConfig),
+    SpamAssetProcessor(SpamAssetProcessorConfig),
+    NftPointsProcessor(NftPointsProcessorConfig),
     // ParquetProcessor
     ParquetDefaultProcessor(ParquetDefaultProcessorConfig),
     ParquetObjectsProcessor(ParquetDefaultProcessorConfig),
@@ -121,6 +151,9 @@ pub enum ProcessorConfig {
     ParquetAccountTransactionsProcessor(ParquetDefaultProcessorConfig),
     ParquetTokenV2Processor(ParquetDefaultProcessorConfig),
     ParquetStakeProcessor(ParquetDefaultProcessorConfig),
+    ParquetNftPointsProcessor(ParquetNftPointsProcessorConfig),
+    ParquetAccountRestorationProcessor(ParquetDefaultProcessorConfig),
+    ParquetGasFeeProcessor(ParquetDefaultProcessorConfig),
 }
 
 impl ProcessorConfig {
@@ -130,6 +163,38 @@ impl ProcessorConfig {
         self.into()
     }
 
+    /// Get the default config of a Postgres processor, e.g. to read its tables_to_write. Returns
+    /// None for the capture processor and the parquet processors.
+    pub fn default_config(&self) -> Option<&DefaultProcessorConfig> {
+        match self {
+            ProcessorConfig::AccountRestorationProcessor(config)
+            | ProcessorConfig::AccountTransactionsProcessor(config)
+            | ProcessorConfig::DefaultProcessor(config)
+            | ProcessorConfig::EventsProcessor(config)
+            | ProcessorConfig::FungibleAssetProcessor(config)
+            | ProcessorConfig::UserTransactionProcessor(config)
+            | ProcessorConfig::MonitoringProcessor(config)
+            | ProcessorConfig::GasFeeProcessor(config) => Some(config),
+            ProcessorConfig::AnsProcessor(config) => Some(&config.default),
+            ProcessorConfig::AccountActivityProcessor(config) => Some(&config.default_config),
+            ProcessorConfig::StakeProcessor(config) => Some(&config.default_config),
+            ProcessorConfig::TokenV2Processor(config) => Some(&config.default_config),
+            ProcessorConfig::ObjectsProcessor(config) => Some(&config.default_config),
+            ProcessorConfig::EventFilterProcessor(config) => Some(&config.default_config),
+            ProcessorConfig::SpamAssetProcessor(config) => Some(&config.default_config),
+            ProcessorConfig::NftPointsProcessor(config) => Some(&config.default_config),
+            _ => None,
+        }
+    }
+
+    /// Get the tables_to_write of a Postgres processor as table flags. Empty when the processor
+    /// has no default config.
+    pub fn tables_to_write(&self) -> TableFlags {
+        self.default_config()
+            .map(|default_config| TableFlags::from_set(&default_config.tables_to_write))
+            .unwrap_or_else(TableFlags::empty)
+    }
+
     // TODO: uncomment after we migrate all parquet processors
     /// Get the Vec of table names for parquet processors only.
     ///
@@ -145,8 +210,11 @@ impl ProcessorConfig {
             | ProcessorConfig::ParquetStakeProcessor(config)
             | ProcessorConfig::ParquetObjectsProcessor(config)
             | ProcessorConfig::ParquetFungibleAssetProcessor(config)
-            | ProcessorConfig::ParquetUserTransactionProcessor(config) => config,
+            | ProcessorConfig::ParquetUserTransactionProcessor(config)
+            | ProcessorConfig::ParquetAccountRestorationProcessor(config)
+            | ProcessorConfig::ParquetGasFeeProcessor(config) => config,
             ProcessorConfig::ParquetAnsProcessor(config) => &config.default,
+            ProcessorConfig::ParquetNftPointsProcessor(config) => &config.default,
             _ => {
                 return Err(anyhow::anyhow!(
                     "Invalid parquet processor config: {:?}",
@@ -205,6 +273,9 @@ impl ProcessorConfig {
                 ParquetFungibleAssetBalance::TABLE_NAME.to_string(),
                 ParquetFungibleAssetMetadataModel::TABLE_NAME.to_string(),
                 ParquetFungibleAssetToCoinMapping::TABLE_NAME.to_string(),
+                ParquetCoinSupply::TABLE_NAME.to_string(),
+                ParquetCoinInfo::TABLE_NAME.to_string(),
+                ParquetCoinBalance::TABLE_NAME.to_string(),
             ]),
             ProcessorName::ParquetTransactionMetadataProcessor => {
                 HashSet::from([ParquetWriteSetSize::TABLE_NAME.to_string()])
@@ -231,7 +302,21 @@ impl ProcessorConfig {
                 ParquetProposalVote::TABLE_NAME.to_string(),
                 ParquetDelegatorBalance::TABLE_NAME.to_string(),
                 ParquetCurrentDelegatorBalance::TABLE_NAME.to_string(),
+                ParquetCurrentStakingPoolVoter::TABLE_NAME.to_string(),
+                ParquetDelegatorPool::TABLE_NAME.to_string(),
+                ParquetDelegatorPoolBalance::TABLE_NAME.to_string(),
+                ParquetCurrentDelegatorPoolBalance::TABLE_NAME.to_string(),
             ]),
+            ProcessorName::ParquetNftPointsProcessor => {
+                HashSet::from([ParquetNftPoints::TABLE_NAME.to_string()])
+            },
+            ProcessorName::ParquetAccountRestorationProcessor => HashSet::from([
+                ParquetAuthKeyAccountAddress::TABLE_NAME.to_string(),
+                ParquetPublicKeyAuthKey::TABLE_NAME.to_string(),
+            ]),
+            ProcessorName::ParquetGasFeeProcessor => {
+                HashSet::from([ParquetGasFee::TABLE_NAME.to_string()])
+            },
             _ => HashSet::new(), // Default case for unsupported processors
         }
     }
@@ -299,6 +384,12 @@ pub struct ParquetDefaultProcessorConfig {
     // Set of table name to backfill. Using HashSet for fast lookups, and for future extensibility.
     #[serde(default)]
     pub backfill_table: HashSet<String>,
+    // Parquet writer settings for every table of the processor.
+    #[serde(default)]
+    pub writer_properties: ParquetWriterConfig,
+    // Table name to writer settings, used instead of `writer_properties` for that table.
+    #[serde(default)]
+    pub table_writer_properties: HashMap<String, ParquetWriterConfig>,
 }
 
 impl ParquetDefaultProcessorConfig {
@@ -319,6 +410,81 @@ impl ParquetDefaultProcessorConfig {
     }
 }
 
+/// Settings for the parquet files written for a table.
+///
+/// Example:
+/// ```yaml
+/// writer_properties:
+///   compression: zstd
+///   compression_level: 3
+///   max_row_group_rows: 1000000
+///   bloom_filter_columns: ["owner_address"]
+/// ```
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetWriterConfig {
+    #[serde(default)]
+    pub compression: ParquetCompression,
+    // Only used by zstd, which defaults to level 1
+    #[serde(default)]
+    pub compression_level: Option<i32>,
+    // Buffers with more rows are split into several row groups
+    #[serde(default = "ParquetWriterConfig::default_max_row_group_rows")]
+    pub max_row_group_rows: usize,
+    #[serde(default = "ParquetWriterConfig::default_dictionary_enabled")]
+    pub dictionary_enabled: bool,
+    #[serde(default)]
+    pub statistics: ParquetStatisticsLevel,
+    // Top-level columns to write a bloom filter for
+    #[serde(default)]
+    pub bloom_filter_columns: Vec<String>,
+}
+
+impl ParquetWriterConfig {
+    /// Same as the parquet crate default
+    pub const fn default_max_row_group_rows() -> usize {
+        1024 * 1024
+    }
+
+    pub const fn default_dictionary_enabled() -> bool {
+        true
+    }
+}
+
+impl Default for ParquetWriterConfig {
+    fn default() -> Self {
+        Self {
+            compression: ParquetCompression::default(),
+            compression_level: None,
+            max_row_group_rows: Self::default_max_row_group_rows(),
+            dictionary_enabled: Self::default_dictionary_enabled(),
+            statistics: ParquetStatisticsLevel::default(),
+            bloom_filter_columns: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetCompression {
+    Uncompressed,
+    #[default]
+    Lz4,
+    Lz4Raw,
+    Zstd,
+}
+
+/// How much column statistics are written: none, one per column chunk, or one per page.
+/// Files merged by `compact` only keep the column chunk statistics.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetStatisticsLevel {
+    None,
+    Chunk,
+    #[default]
+    Page,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,6 +496,7 @@ mod tests {
             channel_size: 10,
             max_buffer_size: 100000,
             upload_interval: 1800,
+            ..Default::default()
         });
 
         let result = config.get_processor_status_table_names();
@@ -349,6 +516,7 @@ mod tests {
             channel_size: 10,
             max_buffer_size: 100000,
             upload_interval: 1800,
+            ..Default::default()
         });
 
         let result = config.get_processor_status_table_names();
@@ -366,6 +534,7 @@ mod tests {
             channel_size: 10,
             max_buffer_size: 100000,
             upload_interval: 1800,
+            ..Default::default()
         });
         let result = config.get_processor_status_table_names();
         assert!(result.is_ok());
@@ -396,6 +565,7 @@ mod tests {
             channel_size: 10,
             max_buffer_size: 100000,
             upload_interval: 1800,
+            ..Default::default()
         });
 
         let result = config.get_processor_status_table_names();
@@ -0,0 +1,380 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Point-in-time balance queries. The latest balances are read from the `current_*` tables, and
+//! balances at a version or timestamp from the history tables, which have to be written for the
+//! range that's queried.
+
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::{
+    postgres::utils::database::DbPoolConnection, utils::convert::standardize_address,
+};
+use bigdecimal::{BigDecimal, Zero};
+use diesel::{
+    sql_types::{BigInt, Bool, Nullable, Numeric, Text},
+    QueryableByName,
+};
+use diesel_async::RunQueryDsl;
+use serde::Serialize;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 100;
+pub const MAX_PAGE_SIZE: i64 = 1000;
+
+/// When to read the balances at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointInTime {
+    /// The latest indexed balances
+    Latest,
+    /// The balances after this transaction version
+    Version(i64),
+    /// The balances after the last transaction at or before this time
+    Timestamp(chrono::NaiveDateTime),
+}
+
+impl PointInTime {
+    /// Condition on the rows of a history table that were written at or before the point in
+    /// time. The values are formatted from typed values, so they're safe to put in the query.
+    fn history_filter(&self, alias: &str, timestamp_column: Option<&str>) -> Result<String> {
+        match (self, timestamp_column) {
+            (PointInTime::Latest, _) => {
+                anyhow::bail!("The latest balances are read from the current tables")
+            },
+            (PointInTime::Version(version), _) => {
+                Ok(format!("{alias}.transaction_version <= {version}"))
+            },
+            (PointInTime::Timestamp(timestamp), Some(column)) => Ok(format!(
+                "{alias}.{column} <= '{}'::timestamp",
+                timestamp.format("%Y-%m-%d %H:%M:%S%.f")
+            )),
+            (PointInTime::Timestamp(_), None) => {
+                anyhow::bail!("The history table has no timestamp column, query by version")
+            },
+        }
+    }
+}
+
+/// Keyset pagination over the rows of a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page {
+    /// Only return rows after this cursor, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub limit: i64,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self {
+            cursor: None,
+            limit: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BalancePage<T> {
+    pub balances: Vec<T>,
+    /// Cursor of the next page, or None if this is the last page
+    pub next_cursor: Option<String>,
+}
+
+/// The balance of a fungible asset store. Current balances unify v1 coin stores with the paired
+/// fungible asset store, while history balances have a row per store and standard.
+#[derive(Clone, Debug, PartialEq, QueryableByName, Serialize)]
+pub struct FungibleAssetStoreBalance {
+    #[diesel(sql_type = Text)]
+    pub storage_id: String,
+    #[diesel(sql_type = Text)]
+    pub owner_address: String,
+    #[diesel(sql_type = Text)]
+    pub asset_type: String,
+    #[diesel(sql_type = Text)]
+    pub token_standard: String,
+    #[diesel(sql_type = Bool)]
+    pub is_primary: bool,
+    #[diesel(sql_type = Bool)]
+    pub is_frozen: bool,
+    #[diesel(sql_type = Numeric)]
+    pub amount: BigDecimal,
+    /// Version of the transaction that last changed the balance
+    #[diesel(sql_type = BigInt)]
+    pub transaction_version: i64,
+}
+
+/// Shares of a delegator in one of the share pools of a delegation pool.
+#[derive(Clone, Debug, PartialEq, QueryableByName, Serialize)]
+pub struct DelegatorPoolShares {
+    #[diesel(sql_type = Text)]
+    pub pool_type: String,
+    #[diesel(sql_type = Text)]
+    pub table_handle: String,
+    #[diesel(sql_type = Numeric)]
+    pub shares: BigDecimal,
+    /// Version of the transaction that last changed the shares
+    #[diesel(sql_type = BigInt)]
+    pub transaction_version: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DelegatorBalance {
+    pub delegator_address: String,
+    pub pool_address: String,
+    pub shares: Vec<DelegatorPoolShares>,
+    /// Coins of the active shares at the share price of the pool, if the pool balance is indexed
+    pub active_coins: Option<BigDecimal>,
+}
+
+#[derive(Debug, QueryableByName)]
+struct PoolBalance {
+    #[diesel(sql_type = Numeric)]
+    total_coins: BigDecimal,
+    #[diesel(sql_type = Numeric)]
+    total_shares: BigDecimal,
+}
+
+/// Standardizes the address of a v2 asset type, and leaves v1 coin types as they are.
+fn standardize_asset_type(asset_type: &str) -> String {
+    if asset_type.contains("::") {
+        asset_type.to_string()
+    } else {
+        standardize_address(asset_type)
+    }
+}
+
+fn fungible_asset_balances_query(at: PointInTime, has_asset_type: bool) -> Result<String> {
+    // $2 is NULL when all asset types are queried
+    let asset_type_filter = match (at, has_asset_type) {
+        (_, false) => "$2 IS NULL",
+        (PointInTime::Latest, true) => "(c.asset_type_v1 = $2 OR c.asset_type_v2 = $2)",
+        (_, true) => "s.asset_type = $2",
+    };
+    Ok(match at {
+        PointInTime::Latest => format!(
+            "SELECT c.storage_id, c.owner_address, c.asset_type, c.token_standard, c.is_primary, \
+             c.is_frozen, c.amount, c.last_transaction_version AS transaction_version \
+             FROM current_fungible_asset_balances c \
+             WHERE c.owner_address = $1 AND {asset_type_filter} AND c.storage_id > $3 \
+             ORDER BY c.storage_id LIMIT $4"
+        ),
+        // The stores the owner had at some point, and then the last row of each store. A store
+        // can change owners, so the rows of stores that moved to another owner are dropped after
+        // the page is loaded.
+        _ => format!(
+            "SELECT h.storage_id, h.owner_address, h.asset_type, h.token_standard, h.is_primary, \
+             h.is_frozen, h.amount, h.transaction_version \
+             FROM ( \
+                 SELECT DISTINCT s.storage_id FROM fungible_asset_balances s \
+                 WHERE s.owner_address = $1 AND {asset_type_filter} AND s.storage_id > $3 AND {} \
+                 ORDER BY s.storage_id LIMIT $4 \
+             ) s \
+             CROSS JOIN LATERAL ( \
+                 SELECT * FROM fungible_asset_balances h \
+                 WHERE h.storage_id = s.storage_id AND {} \
+                 ORDER BY h.transaction_version DESC, h.write_set_change_index DESC \
+                 LIMIT 1 \
+             ) h \
+             ORDER BY h.storage_id",
+            at.history_filter("s", Some("transaction_timestamp"))?,
+            at.history_filter("h", Some("transaction_timestamp"))?,
+        ),
+    })
+}
+
+/// Gets the fungible asset balances of an owner, for one asset type or for all of them, one page
+/// of stores at a time ordered by storage id.
+pub async fn get_fungible_asset_balances(
+    conn: &mut DbPoolConnection<'_>,
+    owner_address: &str,
+    asset_type: Option<&str>,
+    at: PointInTime,
+    page: &Page,
+) -> Result<BalancePage<FungibleAssetStoreBalance>> {
+    let owner_address = standardize_address(owner_address);
+    let limit = page.limit.clamp(1, MAX_PAGE_SIZE);
+    let rows: Vec<FungibleAssetStoreBalance> =
+        diesel::sql_query(fungible_asset_balances_query(at, asset_type.is_some())?)
+            .bind::<Text, _>(&owner_address)
+            .bind::<Nullable<Text>, _>(asset_type.map(standardize_asset_type))
+            .bind::<Text, _>(page.cursor.clone().unwrap_or_default())
+            .bind::<BigInt, _>(limit)
+            .load(conn)
+            .await
+            .context("Failed to query fungible asset balances")?;
+
+    let next_cursor = (rows.len() as i64 == limit)
+        .then(|| rows.last().map(|row| row.storage_id.clone()))
+        .flatten();
+    Ok(BalancePage {
+        balances: rows
+            .into_iter()
+            .filter(|row| row.owner_address == owner_address)
+            .collect(),
+        next_cursor,
+    })
+}
+
+fn delegator_shares_query(at: PointInTime) -> Result<String> {
+    Ok(match at {
+        PointInTime::Latest => "SELECT c.pool_type, c.table_handle, c.shares, \
+                                c.last_transaction_version AS transaction_version \
+                                FROM current_delegator_balances c \
+                                WHERE c.delegator_address = $1 AND c.pool_address = $2 \
+                                ORDER BY c.pool_type, c.table_handle"
+            .to_string(),
+        _ => format!(
+            "SELECT h.pool_type, h.table_handle, h.shares, h.transaction_version \
+             FROM ( \
+                 SELECT DISTINCT s.table_handle FROM delegator_balances s \
+                 WHERE s.delegator_address = $1 AND s.pool_address = $2 AND {} \
+             ) s \
+             CROSS JOIN LATERAL ( \
+                 SELECT * FROM delegator_balances h \
+                 WHERE h.delegator_address = $1 AND h.pool_address = $2 \
+                 AND h.table_handle = s.table_handle AND {} \
+                 ORDER BY h.transaction_version DESC, h.write_set_change_index DESC \
+                 LIMIT 1 \
+             ) h \
+             ORDER BY h.pool_type, h.table_handle",
+            at.history_filter("s", None)?,
+            at.history_filter("h", None)?,
+        ),
+    })
+}
+
+fn pool_balance_query(at: PointInTime) -> Result<String> {
+    Ok(match at {
+        PointInTime::Latest => "SELECT c.total_coins, c.total_shares \
+                                FROM current_delegated_staking_pool_balances c \
+                                WHERE c.staking_pool_address = $1"
+            .to_string(),
+        _ => format!(
+            "SELECT h.total_coins, h.total_shares FROM delegated_staking_pool_balances h \
+             WHERE h.staking_pool_address = $1 AND {} \
+             ORDER BY h.transaction_version DESC LIMIT 1",
+            at.history_filter("h", None)?,
+        ),
+    })
+}
+
+/// Coins of the active shares at the share price of the pool, rounded down to whole octas.
+fn active_coins(shares: &[DelegatorPoolShares], pool: &PoolBalance) -> BigDecimal {
+    if pool.total_shares.is_zero() {
+        return BigDecimal::zero();
+    }
+    let active_shares: BigDecimal = shares
+        .iter()
+        .filter(|shares| shares.pool_type == "active_shares")
+        .map(|shares| &shares.shares)
+        .sum();
+    (active_shares * &pool.total_coins / &pool.total_shares).with_scale(0)
+}
+
+/// Gets the shares of a delegator in a delegation pool. `delegator_balances` has no timestamp,
+/// so past balances can only be queried by version.
+pub async fn get_delegator_balance(
+    conn: &mut DbPoolConnection<'_>,
+    delegator_address: &str,
+    pool_address: &str,
+    at: PointInTime,
+) -> Result<DelegatorBalance> {
+    let delegator_address = standardize_address(delegator_address);
+    let pool_address = standardize_address(pool_address);
+    let shares: Vec<DelegatorPoolShares> = diesel::sql_query(delegator_shares_query(at)?)
+        .bind::<Text, _>(&delegator_address)
+        .bind::<Text, _>(&pool_address)
+        .load(conn)
+        .await
+        .context("Failed to query delegator balances")?;
+    let pool: Option<PoolBalance> = diesel::sql_query(pool_balance_query(at)?)
+        .bind::<Text, _>(&pool_address)
+        .load(conn)
+        .await
+        .context("Failed to query delegation pool balance")?
+        .pop();
+
+    Ok(DelegatorBalance {
+        active_coins: pool.map(|pool| active_coins(&shares, &pool)),
+        delegator_address,
+        pool_address,
+        shares,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::tests::table_definition;
+    use regex::Regex;
+
+    #[test]
+    fn test_history_filter() {
+        let timestamp = chrono::NaiveDate::from_ymd_opt(2024, 5, 1)
+            .unwrap()
+            .and_hms_opt(12, 30, 0)
+            .unwrap();
+        assert_eq!(
+            PointInTime::Version(42).history_filter("h", None).unwrap(),
+            "h.transaction_version <= 42"
+        );
+        assert_eq!(
+            PointInTime::Timestamp(timestamp)
+                .history_filter("h", Some("transaction_timestamp"))
+                .unwrap(),
+            "h.transaction_timestamp <= '2024-05-01 12:30:00'::timestamp"
+        );
+        assert!(PointInTime::Timestamp(timestamp)
+            .history_filter("h", None)
+            .is_err());
+        assert!(PointInTime::Latest.history_filter("h", None).is_err());
+    }
+
+    #[test]
+    fn test_queries_reference_existing_columns() {
+        let column_regex = Regex::new(r"\b(?:FROM|JOIN) (\w+) (\w)\b").unwrap();
+        let points = [PointInTime::Latest, PointInTime::Version(1)];
+        let queries = points.iter().flat_map(|at| {
+            [
+                fungible_asset_balances_query(*at, true).unwrap(),
+                fungible_asset_balances_query(*at, false).unwrap(),
+                delegator_shares_query(*at).unwrap(),
+                pool_balance_query(*at).unwrap(),
+            ]
+        });
+        for query in queries {
+            for table in column_regex.captures_iter(&query) {
+                let (table_name, alias) = (&table[1], &table[2]);
+                let definition = table_definition(table_name)
+                    .unwrap_or_else(|| panic!("Table {table_name} is not in schema.rs"));
+                let column_regex = Regex::new(&format!(r"\b{alias}\.(\w+)")).unwrap();
+                for column in column_regex.captures_iter(&query) {
+                    assert!(
+                        definition.contains(&format!(" {} ->", &column[1])),
+                        "Column {} is not in {table_name}",
+                        &column[1]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_active_coins() {
+        let shares = |pool_type: &str, shares: u64| DelegatorPoolShares {
+            pool_type: pool_type.to_string(),
+            table_handle: "0x1".to_string(),
+            shares: BigDecimal::from(shares),
+            transaction_version: 1,
+        };
+        let pool = PoolBalance {
+            total_coins: BigDecimal::from(300),
+            total_shares: BigDecimal::from(200),
+        };
+        let balances = [shares("active_shares", 11), shares("inactive_shares", 50)];
+        assert_eq!(active_coins(&balances, &pool), BigDecimal::from(16));
+
+        let empty_pool = PoolBalance {
+            total_coins: BigDecimal::zero(),
+            total_shares: BigDecimal::zero(),
+        };
+        assert_eq!(active_coins(&balances, &empty_pool), BigDecimal::zero());
+    }
+}
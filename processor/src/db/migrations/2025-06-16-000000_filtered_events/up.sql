@@ -0,0 +1,17 @@
+-- Your SQL goes here
+-- Events kept by the event filter processor. Columns projected from `data` are added by the
+-- processor at startup, from its config.
+CREATE TABLE IF NOT EXISTS filtered_events (
+  transaction_version BIGINT NOT NULL,
+  event_index BIGINT NOT NULL,
+  account_address VARCHAR(66) NOT NULL,
+  type TEXT NOT NULL,
+  indexed_type VARCHAR(300) NOT NULL,
+  data JSONB NOT NULL,
+  block_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (transaction_version, event_index)
+);
+CREATE INDEX IF NOT EXISTS fe_indexed_type_index ON filtered_events (indexed_type);
+CREATE INDEX IF NOT EXISTS fe_account_address_index ON filtered_events (account_address);
+CREATE INDEX IF NOT EXISTS fe_insat_index ON filtered_events (inserted_at);
@@ -0,0 +1,23 @@
+-- Your SQL goes here
+-- Renewals of ans v2 names, one row per RenewNameEvent
+-- Expiries have no event, query expiration_timestamp to find expired names
+CREATE TABLE IF NOT EXISTS ans_renewal_activities (
+  transaction_version BIGINT NOT NULL,
+  event_index BIGINT NOT NULL,
+  domain VARCHAR(64) NOT NULL,
+  -- if subdomain is null set to empty string
+  subdomain VARCHAR(64) NOT NULL,
+  token_standard VARCHAR(10) NOT NULL,
+  token_name VARCHAR(140) NOT NULL,
+  registered_address VARCHAR(66),
+  -- expiration after the renewal
+  expiration_timestamp TIMESTAMP NOT NULL,
+  is_primary_name BOOLEAN NOT NULL,
+  block_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  -- Constraints
+  PRIMARY KEY (transaction_version, event_index)
+);
+CREATE INDEX IF NOT EXISTS ara_tn_index ON ans_renewal_activities (token_name, token_standard);
+CREATE INDEX IF NOT EXISTS ara_et_index ON ans_renewal_activities (expiration_timestamp);
+CREATE INDEX IF NOT EXISTS ara_insat_index ON ans_renewal_activities (inserted_at);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS fab_sid_tv_index;
+DROP INDEX IF EXISTS fab_owner_sid_index;
+DROP INDEX IF EXISTS cufab_owner_sid_index;
+DROP INDEX IF EXISTS db_da_pa_th_tv_index;
+DROP INDEX IF EXISTS dspb_spa_tv_index;
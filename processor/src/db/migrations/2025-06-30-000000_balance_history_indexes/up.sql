@@ -0,0 +1,24 @@
+-- Your SQL goes here
+-- Indexes for point-in-time balance queries, see db/balances.rs
+-- latest row of a store at or before a version
+CREATE INDEX IF NOT EXISTS fab_sid_tv_index ON fungible_asset_balances (
+  storage_id,
+  transaction_version DESC,
+  write_set_change_index DESC
+);
+-- stores of an owner, in pagination order
+CREATE INDEX IF NOT EXISTS fab_owner_sid_index ON fungible_asset_balances (owner_address, storage_id);
+CREATE INDEX IF NOT EXISTS cufab_owner_sid_index ON current_fungible_asset_balances (owner_address, storage_id);
+-- latest shares of a delegator in a pool at or before a version
+CREATE INDEX IF NOT EXISTS db_da_pa_th_tv_index ON delegator_balances (
+  delegator_address,
+  pool_address,
+  table_handle,
+  transaction_version DESC,
+  write_set_change_index DESC
+);
+-- latest balance of a pool at or before a version
+CREATE INDEX IF NOT EXISTS dspb_spa_tv_index ON delegated_staking_pool_balances (
+  staking_pool_address,
+  transaction_version DESC
+);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE nft_points DROP CONSTRAINT IF EXISTS nft_points_pkey;
+ALTER TABLE nft_points DROP COLUMN IF EXISTS event_index;
+ALTER TABLE nft_points
+ADD PRIMARY KEY (transaction_version);
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- A transaction can award points with several events. Points awarded by the entry function of
+-- the transaction have an event_index of -1.
+ALTER TABLE nft_points
+ADD COLUMN IF NOT EXISTS event_index BIGINT NOT NULL DEFAULT -1;
+ALTER TABLE nft_points DROP CONSTRAINT IF EXISTS nft_points_transaction_version_key;
+ALTER TABLE nft_points DROP CONSTRAINT IF EXISTS nft_points_pkey;
+ALTER TABLE nft_points
+ADD PRIMARY KEY (transaction_version, event_index);
@@ -0,0 +1,26 @@
+-- Your SQL goes here
+-- One row per account and activity, so a wallet's activity feed is a single index scan. A
+-- transaction can have several activities of an account, so pages are read with a cursor on the
+-- rest of the primary key, the last row of the previous page:
+-- `WHERE account_address = $1 AND (transaction_version, activity_kind, activity_index) <
+-- ($version, $kind, $index) ORDER BY transaction_version DESC, activity_kind DESC,
+-- activity_index DESC`.
+CREATE TABLE IF NOT EXISTS account_activities (
+  transaction_version BIGINT NOT NULL,
+  account_address VARCHAR(66) NOT NULL,
+  activity_kind VARCHAR(50) NOT NULL,
+  activity_index BIGINT NOT NULL,
+  counterparty_address VARCHAR(66),
+  asset VARCHAR(1000),
+  amount NUMERIC,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (
+    account_address,
+    transaction_version,
+    activity_kind,
+    activity_index
+  )
+);
+CREATE INDEX IF NOT EXISTS aa_transaction_version_index ON account_activities (transaction_version);
+CREATE INDEX IF NOT EXISTS aa_insat_index ON account_activities (inserted_at);
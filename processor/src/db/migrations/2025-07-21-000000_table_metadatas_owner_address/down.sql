@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS tm_owner_address_index;
+ALTER TABLE table_metadatas DROP COLUMN IF EXISTS owner_address;
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+-- Address of the resource that holds the table handle, used to attribute table item writes
+-- to accounts. Null when the holder hasn't been seen yet.
+ALTER TABLE table_metadatas
+ADD COLUMN IF NOT EXISTS owner_address VARCHAR(66);
+CREATE INDEX IF NOT EXISTS tm_owner_address_index ON table_metadatas (owner_address);
@@ -0,0 +1,20 @@
+-- Your SQL goes here
+-- One row per parquet file a parquet processor uploaded. Rows are inserted uncommitted before the
+-- upload and committed in the same transaction that advances the table's checkpoint, so an
+-- uncommitted row after a restart is a file that may have landed without its checkpoint.
+CREATE TABLE IF NOT EXISTS parquet_files (
+  file_path VARCHAR(1000) NOT NULL,
+  processor VARCHAR(100) NOT NULL,
+  table_name VARCHAR(100) NOT NULL,
+  partition VARCHAR(200),
+  start_version BIGINT NOT NULL,
+  end_version BIGINT NOT NULL,
+  row_count BIGINT NOT NULL,
+  file_size_bytes BIGINT NOT NULL,
+  schema_hash VARCHAR(64) NOT NULL,
+  is_committed BOOLEAN NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (file_path)
+);
+CREATE INDEX IF NOT EXISTS pf_processor_table_end_version_index ON parquet_files (processor, table_name, end_version);
+CREATE INDEX IF NOT EXISTS pf_insat_index ON parquet_files (inserted_at);
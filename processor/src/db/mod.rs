@@ -1,2 +1,4 @@
 pub mod backfill_processor_status;
+pub mod balances;
+pub mod parquet_files;
 pub mod resources;
@@ -0,0 +1,98 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::{
+    parquet_processors::parquet_utils::parquet_uploader::ParquetFileManifest, schema::parquet_files,
+};
+use libra2_indexer_processor_sdk::postgres::utils::database::DbPoolConnection;
+use diesel::{dsl::max, ExpressionMethods, Insertable, QueryDsl, Queryable};
+use diesel_async::RunQueryDsl;
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = parquet_files)]
+/// A parquet file in the bucket, committed once the checkpoint covering it is saved
+pub struct ParquetFile {
+    pub file_path: String,
+    pub processor: String,
+    pub table_name: String,
+    pub partition: Option<String>,
+    pub start_version: i64,
+    pub end_version: i64,
+    pub row_count: i64,
+    pub file_size_bytes: i64,
+    pub schema_hash: String,
+    pub is_committed: bool,
+}
+
+impl ParquetFile {
+    pub fn from_manifest(manifest: &ParquetFileManifest, is_committed: bool) -> Self {
+        Self {
+            file_path: manifest.file_path.clone(),
+            processor: manifest.processor_name.clone(),
+            table_name: manifest.table_name.clone(),
+            partition: manifest.partition.clone(),
+            start_version: manifest.start_version,
+            end_version: manifest.end_version,
+            row_count: manifest.row_count,
+            file_size_bytes: manifest.file_size_bytes,
+            schema_hash: manifest.schema_hash.clone(),
+            is_committed,
+        }
+    }
+}
+
+#[derive(Debug, Queryable)]
+#[diesel(table_name = parquet_files)]
+pub struct ParquetFileQuery {
+    pub file_path: String,
+    pub processor: String,
+    pub table_name: String,
+    pub partition: Option<String>,
+    pub start_version: i64,
+    pub end_version: i64,
+    pub row_count: i64,
+    pub file_size_bytes: i64,
+    pub schema_hash: String,
+    pub is_committed: bool,
+    pub inserted_at: chrono::NaiveDateTime,
+}
+
+impl ParquetFileQuery {
+    /// Files that were about to be uploaded when the processor stopped, and may or may not be in
+    /// the bucket.
+    pub async fn get_uncommitted(
+        processor_name: &str,
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<Vec<Self>> {
+        parquet_files::table
+            .filter(parquet_files::processor.eq(processor_name))
+            .filter(parquet_files::is_committed.eq(false))
+            .load::<Self>(conn)
+            .await
+    }
+
+    /// The highest version in a committed file of each table.
+    pub async fn get_committed_end_versions(
+        processor_name: &str,
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<Vec<(String, Option<i64>)>> {
+        parquet_files::table
+            .filter(parquet_files::processor.eq(processor_name))
+            .filter(parquet_files::is_committed.eq(true))
+            .group_by(parquet_files::table_name)
+            .select((parquet_files::table_name, max(parquet_files::end_version)))
+            .load::<(String, Option<i64>)>(conn)
+            .await
+    }
+
+    pub async fn delete_by_file_paths(
+        file_paths: &[String],
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<usize> {
+        diesel::delete(parquet_files::table.filter(parquet_files::file_path.eq_any(file_paths)))
+            .execute(conn)
+            .await
+    }
+}
@@ -1,5 +1,23 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    account_activities (account_address, transaction_version, activity_kind, activity_index) {
+        transaction_version -> Int8,
+        #[max_length = 66]
+        account_address -> Varchar,
+        #[max_length = 50]
+        activity_kind -> Varchar,
+        activity_index -> Int8,
+        #[max_length = 66]
+        counterparty_address -> Nullable<Varchar>,
+        #[max_length = 1000]
+        asset -> Nullable<Varchar>,
+        amount -> Nullable<Numeric>,
+        transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     account_transactions (account_address, transaction_version) {
         transaction_version -> Int8,
@@ -84,6 +102,27 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    ans_renewal_activities (transaction_version, event_index) {
+        transaction_version -> Int8,
+        event_index -> Int8,
+        #[max_length = 64]
+        domain -> Varchar,
+        #[max_length = 64]
+        subdomain -> Varchar,
+        #[max_length = 10]
+        token_standard -> Varchar,
+        #[max_length = 140]
+        token_name -> Varchar,
+        #[max_length = 66]
+        registered_address -> Nullable<Varchar>,
+        expiration_timestamp -> Timestamp,
+        is_primary_name -> Bool,
+        block_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     auth_key_account_addresses (account_address) {
         #[max_length = 66]
@@ -791,6 +830,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    filtered_events (transaction_version, event_index) {
+        transaction_version -> Int8,
+        event_index -> Int8,
+        #[max_length = 66]
+        account_address -> Varchar,
+        #[sql_name = "type"]
+        type_ -> Text,
+        #[max_length = 300]
+        indexed_type -> Varchar,
+        data -> Jsonb,
+        block_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     fungible_asset_activities (transaction_version, event_index) {
         transaction_version -> Int8,
@@ -949,7 +1004,7 @@ diesel::table! {
 }
 
 diesel::table! {
-    nft_points (transaction_version) {
+    nft_points (transaction_version, event_index) {
         transaction_version -> Int8,
         #[max_length = 66]
         owner_address -> Varchar,
@@ -958,6 +1013,7 @@ diesel::table! {
         amount -> Numeric,
         transaction_timestamp -> Timestamp,
         inserted_at -> Timestamp,
+        event_index -> Int8,
     }
 }
 
@@ -979,6 +1035,27 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    parquet_files (file_path) {
+        #[max_length = 1000]
+        file_path -> Varchar,
+        #[max_length = 100]
+        processor -> Varchar,
+        #[max_length = 100]
+        table_name -> Varchar,
+        #[max_length = 200]
+        partition -> Nullable<Varchar>,
+        start_version -> Int8,
+        end_version -> Int8,
+        row_count -> Int8,
+        file_size_bytes -> Int8,
+        #[max_length = 64]
+        schema_hash -> Varchar,
+        is_committed -> Bool,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     processor_status (processor, processor) {
         #[max_length = 100]
@@ -1073,6 +1150,8 @@ diesel::table! {
         key_type -> Text,
         value_type -> Text,
         inserted_at -> Timestamp,
+        #[max_length = 66]
+        owner_address -> Nullable<Varchar>,
     }
 }
 
@@ -1358,11 +1437,13 @@ diesel::table! {
 }
 
 diesel::allow_tables_to_appear_in_same_query!(
+    account_activities,
     account_transactions,
     ans_lookup,
     ans_lookup_v2,
     ans_primary_name,
     ans_primary_name_v2,
+    ans_renewal_activities,
     auth_key_account_addresses,
     backfill_processor_status,
     block_metadata_transactions,
@@ -1400,6 +1481,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     delegator_balances,
     event_size_info,
     events,
+    filtered_events,
     fungible_asset_activities,
     fungible_asset_balances,
     fungible_asset_metadata,
@@ -1411,6 +1493,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     move_resources,
     nft_points,
     objects,
+    parquet_files,
     processor_status,
     proposal_votes,
     public_key_auth_keys,
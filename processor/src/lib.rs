@@ -9,6 +9,7 @@ extern crate canonical_json;
 extern crate parquet;
 extern crate parquet_derive;
 
+pub mod commands;
 pub mod config;
 pub mod db;
 pub mod parquet_processors;
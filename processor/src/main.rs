@@ -1,9 +1,12 @@
 // Copyright © A-p-t-o-s Foundation
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::Result;
-use libra2_indexer_processor_sdk::server_framework::ServerArgs;
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::server_framework::{
+    load, setup_logging, GenericConfig, ServerArgs,
+};
 use clap::Parser;
-use processor::config::indexer_processor_config::IndexerProcessorConfig;
+use processor::{commands::Command, config::indexer_processor_config::IndexerProcessorConfig};
+use std::path::PathBuf;
 
 #[cfg(unix)]
 #[global_allocator]
@@ -11,6 +14,16 @@ static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
 const RUNTIME_WORKER_MULTIPLIER: usize = 2;
 
+#[derive(Parser)]
+struct Args {
+    /// Required to run the processor and for every command but `schemas`
+    #[clap(short, long, value_parser)]
+    config_path: Option<PathBuf>,
+    /// Runs the processor if no command is given
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
 fn main() -> Result<()> {
     let num_cpus = num_cpus::get();
     let worker_threads = (num_cpus * RUNTIME_WORKER_MULTIPLIER).max(16);
@@ -23,8 +36,28 @@ fn main() -> Result<()> {
         .build()
         .unwrap()
         .block_on(async {
-            let args = ServerArgs::parse();
-            args.run::<IndexerProcessorConfig>(tokio::runtime::Handle::current())
-                .await
+            let args = Args::parse();
+            match args.command {
+                Some(command) => {
+                    setup_logging();
+                    let config = args
+                        .config_path
+                        .map(|config_path| {
+                            load::<GenericConfig<IndexerProcessorConfig>>(&config_path)
+                        })
+                        .transpose()?;
+                    command
+                        .run(config.as_ref().map(|config| &config.server_config))
+                        .await
+                },
+                None => {
+                    let config_path = args
+                        .config_path
+                        .context("--config-path is required to run the processor")?;
+                    ServerArgs { config_path }
+                        .run::<IndexerProcessorConfig>(tokio::runtime::Handle::current())
+                        .await
+                },
+            }
         })
 }
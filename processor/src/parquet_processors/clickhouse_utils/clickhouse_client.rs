@@ -0,0 +1,112 @@
+use crate::config::db_config::ClickhouseConfig;
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use tracing::debug;
+
+/// A minimal client for the ClickHouse HTTP interface.
+///
+/// Rows are inserted in the `JSONEachRow` format, so any model that implements `Serialize` can be
+/// written without a ClickHouse specific row type.
+#[derive(Clone)]
+pub struct ClickhouseClient {
+    client: reqwest::Client,
+    url: String,
+    pub database: String,
+    user: Option<String>,
+    password: Option<String>,
+}
+
+impl ClickhouseClient {
+    pub fn new(config: &ClickhouseConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.request_timeout_secs))
+            .build()
+            .context("Failed to create ClickHouse HTTP client")?;
+
+        Ok(Self {
+            client,
+            url: config.url.clone(),
+            database: config.database.clone(),
+            user: config.user.clone(),
+            password: config.password.clone(),
+        })
+    }
+
+    /// Runs a statement that doesn't return rows, e.g. DDL.
+    pub async fn execute(&self, sql: &str) -> Result<()> {
+        self.send(&[], sql.as_bytes().to_vec()).await?;
+        Ok(())
+    }
+
+    /// Inserts newline delimited JSON rows into `table`.
+    pub async fn insert_json_each_row(&self, table: &str, rows: Vec<u8>) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let query = format!("INSERT INTO {table} FORMAT JSONEachRow");
+        // Chrono serializes timestamps as ISO 8601 strings without a time zone, which ClickHouse
+        // only parses in best effort mode. Timestamp columns are created as UTC, so they're read as
+        // UTC instead of in the server's time zone.
+        self.send(
+            &[
+                ("query", query.as_str()),
+                ("date_time_input_format", "best_effort"),
+            ],
+            rows,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Runs a `SELECT` and deserializes every returned row into `T`.
+    pub async fn query_json_each_row<T: DeserializeOwned>(&self, sql: &str) -> Result<Vec<T>> {
+        let sql = format!("{sql} FORMAT JSONEachRow");
+        // ClickHouse quotes 64 bit integers in JSON output by default
+        let response = self
+            .send(
+                &[("output_format_json_quote_64bit_integers", "0")],
+                sql.into_bytes(),
+            )
+            .await?;
+
+        response
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Failed to parse ClickHouse row: {line}"))
+            })
+            .collect()
+    }
+
+    async fn send(&self, params: &[(&str, &str)], body: Vec<u8>) -> Result<String> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .query(&[("database", self.database.as_str())])
+            .query(params)
+            .body(body);
+        if let Some(user) = &self.user {
+            request = request.header("X-ClickHouse-User", user);
+        }
+        if let Some(password) = &self.password {
+            request = request.header("X-ClickHouse-Key", password);
+        }
+
+        let response = request
+            .send()
+            .await
+            .context("Failed to send request to ClickHouse")?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .context("Failed to read ClickHouse response")?;
+        if !status.is_success() {
+            anyhow::bail!("ClickHouse request failed with status {status}: {text}");
+        }
+        debug!(status = status.as_u16(), "ClickHouse request succeeded");
+        Ok(text)
+    }
+}
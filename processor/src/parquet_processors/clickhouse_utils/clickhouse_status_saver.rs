@@ -0,0 +1,353 @@
+use crate::{
+    config::{
+        indexer_processor_config::IndexerProcessorConfig,
+        processor_mode::{BootStrapConfig, ProcessorMode, TestingConfig},
+    },
+    parquet_processors::{
+        clickhouse_utils::clickhouse_client::ClickhouseClient,
+        parquet_utils::{
+            parquet_uploader::ParquetFileManifest,
+            parquet_version_tracker_step::ParquetProcessorStatusSaverTrait,
+            util::format_table_name,
+        },
+    },
+};
+use anyhow::Result;
+use libra2_indexer_processor_sdk::{
+    libra2_indexer_transaction_stream::utils::time::parse_timestamp,
+    types::transaction_context::TransactionContext,
+    utils::{chain_id_check::ChainIdChecker, errors::ProcessorError},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+const PROCESSOR_STATUS_TABLE: &str = "processor_status";
+const LEDGER_INFOS_TABLE: &str = "ledger_infos";
+
+/// Creates the tables that hold processor checkpoints and the chain id, next to the data.
+pub async fn create_clickhouse_metadata_tables(client: &ClickhouseClient) -> Result<()> {
+    client
+        .execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {PROCESSOR_STATUS_TABLE} (
+    `processor` String,
+    `last_success_version` Int64,
+    `last_transaction_timestamp` Nullable(DateTime64(6, 'UTC')),
+    `last_updated` DateTime64(6, 'UTC')
+) ENGINE = ReplacingMergeTree(last_updated)
+ORDER BY processor"
+        ))
+        .await?;
+    client
+        .execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {LEDGER_INFOS_TABLE} (
+    `chain_id` Int64
+) ENGINE = ReplacingMergeTree
+ORDER BY chain_id"
+        ))
+        .await
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ClickhouseProcessorStatus {
+    processor: String,
+    last_success_version: i64,
+    last_transaction_timestamp: Option<chrono::NaiveDateTime>,
+    last_updated: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClickhouseProcessorCheckpoint {
+    last_success_version: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ClickhouseLedgerInfo {
+    chain_id: i64,
+}
+
+/// Saves the per-table checkpoints of a parquet processor to ClickHouse.
+pub struct ClickhouseProcessorStatusSaver {
+    pub config: IndexerProcessorConfig,
+    pub client: ClickhouseClient,
+}
+
+impl ClickhouseProcessorStatusSaver {
+    pub fn new(config: IndexerProcessorConfig, client: ClickhouseClient) -> Self {
+        Self { config, client }
+    }
+}
+
+#[async_trait]
+impl ParquetProcessorStatusSaverTrait for ClickhouseProcessorStatusSaver {
+    async fn save_parquet_processor_status(
+        &self,
+        last_success_batch: &TransactionContext<()>,
+        table_name: &str,
+        _files: &[ParquetFileManifest],
+    ) -> Result<(), ProcessorError> {
+        // Testing mode never persists checkpoints, and backfills are rejected at startup.
+        if !matches!(self.config.processor_mode, ProcessorMode::Default(_)) {
+            return Ok(());
+        }
+
+        let status = ClickhouseProcessorStatus {
+            processor: format_table_name(self.config.processor_config.name(), table_name),
+            last_success_version: last_success_batch.metadata.end_version as i64,
+            last_transaction_timestamp: last_success_batch
+                .metadata
+                .end_transaction_timestamp
+                .as_ref()
+                .map(|t| parse_timestamp(t, last_success_batch.metadata.end_version as i64))
+                .map(|t| t.naive_utc()),
+            last_updated: chrono::Utc::now().naive_utc(),
+        };
+        let row = serde_json::to_vec(&status).map_err(|e| ProcessorError::ProcessError {
+            message: format!("Failed to serialize processor status: {e:?}"),
+        })?;
+
+        self.client
+            .insert_json_each_row(PROCESSOR_STATUS_TABLE, row)
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to save processor status to ClickHouse: {e:?}"),
+                query: None,
+            })
+    }
+}
+
+/// Get the starting version for a parquet processor that writes to ClickHouse.
+///
+/// Mirrors `get_parquet_starting_version`: the minimum checkpoint across all of the processor's
+/// tables is used, so no table skips any versions.
+pub async fn get_clickhouse_starting_version(
+    config: &IndexerProcessorConfig,
+    client: &ClickhouseClient,
+) -> Result<Option<u64>, ProcessorError> {
+    match &config.processor_mode {
+        ProcessorMode::Default(BootStrapConfig {
+            initial_starting_version,
+        }) => {
+            let min_processed_version = get_min_processed_version(config, client).await?;
+            Ok(Some(
+                min_processed_version.map_or(*initial_starting_version, |version| {
+                    std::cmp::max(version, *initial_starting_version)
+                }),
+            ))
+        },
+        ProcessorMode::Testing(TestingConfig {
+            override_starting_version,
+            ..
+        }) => Ok(Some(*override_starting_version)),
+        ProcessorMode::Backfill(_) => Err(ProcessorError::ProcessError {
+            message: "Backfill mode is not supported with ClickhouseConfig".to_string(),
+        }),
+    }
+}
+
+pub fn get_clickhouse_end_version(
+    config: &IndexerProcessorConfig,
+) -> Result<Option<u64>, ProcessorError> {
+    match &config.processor_mode {
+        // Normally unset. The replay command sets it to stop after the last replayed transaction.
+        ProcessorMode::Default(_) => Ok(config.transaction_stream_config.request_ending_version),
+        ProcessorMode::Testing(TestingConfig {
+            override_starting_version,
+            ending_version,
+        }) => Ok(Some(ending_version.unwrap_or(*override_starting_version))),
+        ProcessorMode::Backfill(_) => Err(ProcessorError::ProcessError {
+            message: "Backfill mode is not supported with ClickhouseConfig".to_string(),
+        }),
+    }
+}
+
+async fn get_min_processed_version(
+    config: &IndexerProcessorConfig,
+    client: &ClickhouseClient,
+) -> Result<Option<u64>, ProcessorError> {
+    let table_names = config
+        .processor_config
+        .get_processor_status_table_names()
+        .map_err(|e| ProcessorError::ProcessError {
+            message: format!("Failed to get processor status table names. {e:?}"),
+        })?;
+    let processors = table_names
+        .iter()
+        .map(|name| format!("'{}'", name.replace('\'', "\\'")))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // Rows are only deduplicated on merge, so pick the latest checkpoint per processor explicitly.
+    let checkpoints: Vec<ClickhouseProcessorCheckpoint> = client
+        .query_json_each_row(&format!(
+            "SELECT argMax(last_success_version, last_updated) AS last_success_version \
+             FROM {PROCESSOR_STATUS_TABLE} WHERE processor IN ({processors}) GROUP BY processor"
+        ))
+        .await
+        .map_err(|e| ProcessorError::ProcessError {
+            message: format!("Failed to query processor_status table in ClickHouse. {e:?}"),
+        })?;
+
+    Ok(checkpoints
+        .iter()
+        .map(|checkpoint| checkpoint.last_success_version as u64)
+        .min())
+}
+
+/// Stores the chain id in ClickHouse, so a processor can't be pointed at a different chain than
+/// the one its data came from.
+pub struct ClickhouseChainIdChecker {
+    client: ClickhouseClient,
+}
+
+impl ClickhouseChainIdChecker {
+    pub fn new(client: ClickhouseClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ChainIdChecker for ClickhouseChainIdChecker {
+    async fn save_chain_id(&self, chain_id: u64) -> Result<()> {
+        let row = serde_json::to_vec(&ClickhouseLedgerInfo {
+            chain_id: chain_id as i64,
+        })?;
+        self.client
+            .insert_json_each_row(LEDGER_INFOS_TABLE, row)
+            .await
+    }
+
+    async fn get_chain_id(&self) -> Result<Option<u64>> {
+        let ledger_infos: Vec<ClickhouseLedgerInfo> = self
+            .client
+            .query_json_each_row(&format!(
+                "SELECT chain_id FROM {LEDGER_INFOS_TABLE} LIMIT 1"
+            ))
+            .await?;
+        Ok(ledger_infos
+            .first()
+            .map(|ledger_info| ledger_info.chain_id as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{
+            db_config::{ClickhouseConfig, DbConfig},
+            processor_config::{ParquetDefaultProcessorConfig, ProcessorConfig},
+        },
+        parquet_processors::clickhouse_utils::mock_clickhouse_server::MockClickhouseServer,
+    };
+    use libra2_indexer_processor_sdk::{
+        libra2_indexer_transaction_stream::{
+            utils::additional_headers::AdditionalHeaders, TransactionStreamConfig,
+        },
+        types::transaction_context::TransactionMetadata,
+    };
+    use url::Url;
+
+    fn create_indexer_config(url: String, processor_mode: ProcessorMode) -> IndexerProcessorConfig {
+        IndexerProcessorConfig {
+            processor_config: ProcessorConfig::ParquetDefaultProcessor(
+                ParquetDefaultProcessorConfig::default(),
+            ),
+            db_config: DbConfig::ClickhouseConfig(ClickhouseConfig {
+                url,
+                database: "indexer".to_string(),
+                user: None,
+                password: None,
+                request_timeout_secs: 5,
+            }),
+            processor_mode,
+            additional_processor_configs: vec![],
+            transaction_stream_config: TransactionStreamConfig {
+                indexer_grpc_data_service_address: Url::parse("https://test.com").unwrap(),
+                starting_version: None,
+                request_ending_version: None,
+                auth_token: "test".to_string(),
+                request_name_header: "test".to_string(),
+                indexer_grpc_http2_ping_interval_secs: 1,
+                indexer_grpc_http2_ping_timeout_secs: 1,
+                indexer_grpc_reconnection_timeout_secs: 1,
+                indexer_grpc_reconnection_max_retries: 1,
+                indexer_grpc_response_item_timeout_secs: 1,
+                additional_headers: AdditionalHeaders::default(),
+                transaction_filter: None,
+            },
+        }
+    }
+
+    fn create_client(config: &IndexerProcessorConfig) -> ClickhouseClient {
+        match &config.db_config {
+            DbConfig::ClickhouseConfig(clickhouse_config) => {
+                ClickhouseClient::new(clickhouse_config).unwrap()
+            },
+            _ => panic!("Expected ClickhouseConfig"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_starting_version_is_min_checkpoint() {
+        let server = MockClickhouseServer::start(|_| {
+            "{\"last_success_version\":120}\n{\"last_success_version\":100}\n".to_string()
+        })
+        .await;
+        let config = create_indexer_config(
+            server.url(),
+            ProcessorMode::Default(BootStrapConfig {
+                initial_starting_version: 0,
+            }),
+        );
+
+        let starting_version = get_clickhouse_starting_version(&config, &create_client(&config))
+            .await
+            .unwrap();
+
+        assert_eq!(starting_version, Some(100));
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].body.contains("FROM processor_status"));
+        assert!(requests[0]
+            .body
+            .contains("'parquet_default_processor.move_resources'"));
+    }
+
+    #[tokio::test]
+    async fn test_save_processor_status() {
+        let server = MockClickhouseServer::start(|_| String::new()).await;
+        let config = create_indexer_config(
+            server.url(),
+            ProcessorMode::Default(BootStrapConfig {
+                initial_starting_version: 0,
+            }),
+        );
+        let saver = ClickhouseProcessorStatusSaver::new(config.clone(), create_client(&config));
+
+        saver
+            .save_parquet_processor_status(
+                &TransactionContext {
+                    data: (),
+                    metadata: TransactionMetadata {
+                        start_version: 0,
+                        end_version: 42,
+                        ..TransactionMetadata::default()
+                    },
+                },
+                "transactions",
+                &[],
+            )
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].param("query").unwrap(),
+            "INSERT INTO processor_status FORMAT JSONEachRow"
+        );
+        let status: ClickhouseProcessorStatus = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(status.processor, "parquet_default_processor.transactions");
+        assert_eq!(status.last_success_version, 42);
+    }
+}
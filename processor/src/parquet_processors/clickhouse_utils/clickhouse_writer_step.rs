@@ -0,0 +1,410 @@
+use crate::parquet_processors::{
+    clickhouse_utils::clickhouse_client::ClickhouseClient,
+    parquet_utils::{parquet_version_tracker_step::TableBatchMetadata, util::HasVersion},
+    ParquetTypeEnum, ParquetTypeStructs, ParquetTypeTrait,
+};
+use anyhow::Context;
+use libra2_indexer_processor_sdk::{
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use parquet::{
+    basic::{ConvertedType, Repetition, Type as PhysicalType},
+    schema::types::Type,
+};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+use tracing::debug;
+
+/// Column holding `HasVersion::version` of the row.
+pub const VERSION_COLUMN: &str = "_version";
+
+/// Natural key of the table of a parquet model, the same as the primary key of its Postgres
+/// table. History tables are keyed on the transaction version and the index of the row in the
+/// transaction, current tables on their primary key without the version.
+pub fn sorting_key(parquet_type: ParquetTypeEnum) -> &'static [&'static str] {
+    match parquet_type {
+        ParquetTypeEnum::MoveResources
+        | ParquetTypeEnum::WriteSetChanges
+        | ParquetTypeEnum::TableItems
+        | ParquetTypeEnum::MoveModules
+        | ParquetTypeEnum::AnsPrimaryNameV2
+        | ParquetTypeEnum::AnsLookupV2
+        | ParquetTypeEnum::FungibleAssetBalances
+        | ParquetTypeEnum::TokenDatasV2
+        | ParquetTypeEnum::TokenOwnershipsV2
+        | ParquetTypeEnum::Objects
+        | ParquetTypeEnum::CollectionsV2 => &["txn_version", "write_set_change_index"],
+        ParquetTypeEnum::Events
+        | ParquetTypeEnum::FungibleAssetActivities
+        | ParquetTypeEnum::TokenActivitiesV2
+        | ParquetTypeEnum::NftPoints => &["txn_version", "event_index"],
+        ParquetTypeEnum::Transactions
+        | ParquetTypeEnum::BlockMetadataTransactions
+        | ParquetTypeEnum::UserTransactions
+        | ParquetTypeEnum::GasFees => &["txn_version"],
+        ParquetTypeEnum::Signatures => &[
+            "txn_version",
+            "multi_agent_index",
+            "multi_sig_index",
+            "is_sender_primary",
+        ],
+        ParquetTypeEnum::CoinSupply => &["txn_version", "coin_type_hash"],
+        ParquetTypeEnum::CoinBalances => &["txn_version", "owner_address", "coin_type_hash"],
+        ParquetTypeEnum::WriteSetSize => &["txn_version", "change_index"],
+        ParquetTypeEnum::AccountTransactions => &["txn_version", "account_address"],
+        ParquetTypeEnum::DelegatedStakingActivities => &["transaction_version", "event_index"],
+        ParquetTypeEnum::DelegatorBalances => &["transaction_version", "write_set_change_index"],
+        ParquetTypeEnum::ProposalVotes => &["transaction_version", "proposal_id", "voter_address"],
+        ParquetTypeEnum::DelegatedStakingPoolBalances => {
+            &["transaction_version", "staking_pool_address"]
+        },
+        ParquetTypeEnum::CurrentTableItems => &["table_handle", "key_hash"],
+        ParquetTypeEnum::TableMetadata => &["handle"],
+        ParquetTypeEnum::CurrentAnsPrimaryNameV2 => &["registered_address", "token_standard"],
+        ParquetTypeEnum::CurrentAnsLookupV2 => &["domain", "subdomain", "token_standard"],
+        ParquetTypeEnum::FungibleAssetMetadata => &["asset_type"],
+        ParquetTypeEnum::FungibleAssetToCoinMappings => &["fungible_asset_metadata_address"],
+        ParquetTypeEnum::CoinInfos => &["coin_type_hash"],
+        ParquetTypeEnum::CurrentTokenPendingClaims => &[
+            "token_data_id_hash",
+            "property_version",
+            "from_address",
+            "to_address",
+        ],
+        ParquetTypeEnum::CurrentTokenRoyaltiesV1 | ParquetTypeEnum::CurrentTokenDatasV2 => {
+            &["token_data_id"]
+        },
+        ParquetTypeEnum::CurrentTokenV2Metadata => &["object_address", "resource_type"],
+        ParquetTypeEnum::CurrentTokenOwnershipsV2 => &[
+            "token_data_id",
+            "property_version_v1",
+            "owner_address",
+            "storage_id",
+        ],
+        ParquetTypeEnum::CurrentDelegatorBalances => &[
+            "delegator_address",
+            "pool_address",
+            "pool_type",
+            "table_handle",
+        ],
+        ParquetTypeEnum::CurrentStakingPoolVoter
+        | ParquetTypeEnum::DelegatedStakingPools
+        | ParquetTypeEnum::CurrentDelegatedStakingPoolBalances => &["staking_pool_address"],
+        ParquetTypeEnum::CurrentObjects => &["object_address"],
+        ParquetTypeEnum::AuthKeyAccountAddresses => &["account_address"],
+        ParquetTypeEnum::PublicKeyAuthKeys => &["auth_key", "public_key", "public_key_type"],
+    }
+}
+
+/// Builds the `CREATE TABLE` statement for a parquet model.
+///
+/// Column types are derived from the parquet schema of the model. Tables use
+/// `ReplacingMergeTree` ordered by the natural key of the model, keeping the row with the
+/// highest version per key. Replaying a version range, e.g. after a parser fix, replaces the rows
+/// that were already written, and current tables only keep the latest state of each key.
+pub fn create_table_statement(table_name: &str, schema: &Type, sorting_key: &[&str]) -> String {
+    let mut columns: Vec<String> = schema
+        .get_fields()
+        .iter()
+        .map(|field| format!("    `{}` {}", field.name(), clickhouse_column_type(field)))
+        .collect();
+    columns.push(format!("    `{VERSION_COLUMN}` Int64"));
+
+    format!(
+        "CREATE TABLE IF NOT EXISTS {table_name} (\n{}\n) \
+         ENGINE = ReplacingMergeTree({VERSION_COLUMN})\nORDER BY ({})",
+        columns.join(",\n"),
+        sorting_key.join(", ")
+    )
+}
+
+fn clickhouse_column_type(field: &Type) -> String {
+    let column_type = if field.is_primitive() {
+        match (
+            field.get_physical_type(),
+            field.get_basic_info().converted_type(),
+        ) {
+            // Timestamps are in UTC, without a time zone in the JSON rows
+            (_, ConvertedType::TIMESTAMP_MILLIS) => "DateTime64(3, 'UTC')",
+            (_, ConvertedType::TIMESTAMP_MICROS) => "DateTime64(6, 'UTC')",
+            (
+                PhysicalType::INT32,
+                ConvertedType::UINT_8 | ConvertedType::UINT_16 | ConvertedType::UINT_32,
+            ) => "UInt32",
+            (PhysicalType::INT64, ConvertedType::UINT_64) => "UInt64",
+            (PhysicalType::BOOLEAN, _) => "Bool",
+            (PhysicalType::INT32, _) => "Int32",
+            (PhysicalType::INT64, _) => "Int64",
+            (PhysicalType::INT96, _) => "DateTime64(9, 'UTC')",
+            (PhysicalType::FLOAT, _) => "Float32",
+            (PhysicalType::DOUBLE, _) => "Float64",
+            (PhysicalType::BYTE_ARRAY | PhysicalType::FIXED_LEN_BYTE_ARRAY, _) => "String",
+        }
+    } else {
+        "String"
+    };
+
+    let basic_info = field.get_basic_info();
+    if basic_info.has_repetition() && basic_info.repetition() == Repetition::OPTIONAL {
+        format!("Nullable({column_type})")
+    } else {
+        column_type.to_string()
+    }
+}
+
+/// Serializes rows into the `JSONEachRow` format, adding the version column.
+pub fn to_json_each_row<ParquetType>(rows: &[ParquetType]) -> anyhow::Result<Vec<u8>>
+where
+    ParquetType: HasVersion + Serialize,
+{
+    let mut body = Vec::new();
+    for row in rows {
+        let mut value = serde_json::to_value(row).context("Failed to serialize row")?;
+        let serde_json::Value::Object(columns) = &mut value else {
+            anyhow::bail!("Expected row to serialize into a JSON object");
+        };
+        columns.insert(VERSION_COLUMN.to_string(), row.version().into());
+
+        serde_json::to_writer(&mut body, &value)?;
+        body.push(b'\n');
+    }
+    Ok(body)
+}
+
+/// Creates the tables for all the given parquet types if they don't exist yet.
+pub async fn create_clickhouse_tables(
+    client: &ClickhouseClient,
+    parquet_type_to_schemas: &HashMap<ParquetTypeEnum, Arc<Type>>,
+) -> anyhow::Result<()> {
+    for (parquet_type, schema) in parquet_type_to_schemas {
+        client
+            .execute(&create_table_statement(
+                &parquet_type.to_string(),
+                schema,
+                sorting_key(*parquet_type),
+            ))
+            .await
+            .with_context(|| format!("Failed to create ClickHouse table {parquet_type}"))?;
+    }
+    Ok(())
+}
+
+/// `ClickhouseWriterStep` writes every batch of parquet models straight into ClickHouse.
+///
+/// It takes the place of `ParquetBufferStep` when the processor is configured with
+/// `ClickhouseConfig`, and emits the same per-table metadata so `ParquetVersionTrackerStep` can
+/// checkpoint each table.
+pub struct ClickhouseWriterStep
+where
+    Self: Sized + Send + 'static,
+{
+    client: ClickhouseClient,
+}
+
+impl ClickhouseWriterStep {
+    pub fn new(client: ClickhouseClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Processable for ClickhouseWriterStep {
+    type Input = HashMap<ParquetTypeEnum, ParquetTypeStructs>;
+    type Output = HashMap<ParquetTypeEnum, TableBatchMetadata>;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        item: TransactionContext<Self::Input>,
+    ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
+        let mut written_metadata = HashMap::new();
+        for (parquet_type, parquet_data) in &item.data {
+            let table_name = parquet_type.to_string();
+            let rows =
+                parquet_data
+                    .to_clickhouse_rows()
+                    .map_err(|e| ProcessorError::ProcessError {
+                        message: format!("Failed to serialize rows for {table_name}: {e:?}"),
+                    })?;
+            self.client
+                .insert_json_each_row(&table_name, rows)
+                .await
+                .map_err(|e| ProcessorError::DBStoreError {
+                    message: format!(
+                        "Failed to store versions {} to {} in {}: {:?}",
+                        item.metadata.start_version, item.metadata.end_version, table_name, e,
+                    ),
+                    query: None,
+                })?;
+            debug!(
+                "Wrote versions [{}, {}] to ClickHouse table {}",
+                item.metadata.start_version, item.metadata.end_version, table_name,
+            );
+            written_metadata.insert(*parquet_type, item.metadata.clone().into());
+        }
+
+        Ok(Some(TransactionContext {
+            data: written_metadata,
+            metadata: item.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for ClickhouseWriterStep {}
+
+impl NamedStep for ClickhouseWriterStep {
+    fn name(&self) -> String {
+        "ClickhouseWriterStep".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::db_config::ClickhouseConfig,
+        parquet_processors::{
+            clickhouse_utils::mock_clickhouse_server::MockClickhouseServer,
+            parquet_utils::util::HasParquetSchema,
+        },
+        processors::{
+            default::models::move_resources::ParquetMoveResource,
+            objects::v2_objects_models::ParquetCurrentObject,
+        },
+    };
+    use libra2_indexer_processor_sdk::types::transaction_context::TransactionMetadata;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_create_table_statement() {
+        let statement = create_table_statement(
+            "move_resources",
+            &ParquetMoveResource::schema(),
+            sorting_key(ParquetTypeEnum::MoveResources),
+        );
+
+        assert!(statement.starts_with("CREATE TABLE IF NOT EXISTS move_resources ("));
+        assert!(statement.contains("`txn_version` Int64,"));
+        assert!(statement.contains("`block_timestamp` DateTime64(3, 'UTC'),"));
+        assert!(statement.contains("`is_deleted` Bool,"));
+        assert!(statement.contains("`generic_type_params` Nullable(String),"));
+        assert!(statement.ends_with(
+            "ENGINE = ReplacingMergeTree(_version)\n\
+             ORDER BY (txn_version, write_set_change_index)"
+        ));
+
+        // Current tables are keyed without the version, so they only keep the latest row
+        let statement = create_table_statement(
+            "current_objects",
+            &ParquetCurrentObject::schema(),
+            sorting_key(ParquetTypeEnum::CurrentObjects),
+        );
+        assert!(
+            statement.ends_with("ENGINE = ReplacingMergeTree(_version)\nORDER BY (object_address)")
+        );
+    }
+
+    #[test]
+    fn test_sorting_key_columns_exist() {
+        for parquet_type in ParquetTypeEnum::iter() {
+            let schema = ParquetTypeStructs::default_for_type(&parquet_type).schema();
+            for column in sorting_key(parquet_type) {
+                let field = schema
+                    .get_fields()
+                    .iter()
+                    .find(|field| field.name() == *column)
+                    .unwrap_or_else(|| panic!("{parquet_type}: {column} is not a column"));
+                // ClickHouse doesn't allow nullable columns in the sorting key
+                assert!(
+                    !clickhouse_column_type(field).starts_with("Nullable"),
+                    "{parquet_type}: {column} is nullable"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_each_row_adds_version() {
+        let rows = vec![
+            ParquetMoveResource {
+                txn_version: 7,
+                write_set_change_index: 0,
+                ..Default::default()
+            },
+            ParquetMoveResource {
+                txn_version: 7,
+                write_set_change_index: 1,
+                ..Default::default()
+            },
+        ];
+        let body = to_json_each_row(&rows).unwrap();
+        assert_eq!(body, to_json_each_row(&rows).unwrap());
+
+        let values: Vec<serde_json::Value> = body
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0][VERSION_COLUMN], 7);
+        assert_eq!(values[1][VERSION_COLUMN], 7);
+        assert_eq!(values[1]["write_set_change_index"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_writer_step_inserts_rows_and_emits_metadata() {
+        let server = MockClickhouseServer::start(|_| String::new()).await;
+        let client = ClickhouseClient::new(&ClickhouseConfig {
+            url: server.url(),
+            database: "indexer".to_string(),
+            user: None,
+            password: None,
+            request_timeout_secs: 5,
+        })
+        .unwrap();
+        let mut step = ClickhouseWriterStep::new(client);
+
+        let metadata = TransactionMetadata {
+            start_version: 10,
+            end_version: 12,
+            ..TransactionMetadata::default()
+        };
+        let output = step
+            .process(TransactionContext {
+                data: HashMap::from([(
+                    ParquetTypeEnum::MoveResources,
+                    ParquetTypeStructs::MoveResource(vec![
+                        ParquetMoveResource {
+                            txn_version: 10,
+                            ..Default::default()
+                        },
+                        ParquetMoveResource {
+                            txn_version: 12,
+                            ..Default::default()
+                        },
+                    ]),
+                )]),
+                metadata,
+            })
+            .await
+            .unwrap()
+            .unwrap();
+
+        let written = output.data.get(&ParquetTypeEnum::MoveResources).unwrap();
+        assert_eq!(written.metadata.start_version, 10);
+        assert_eq!(written.metadata.end_version, 12);
+        assert!(written.files.is_empty());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].param("database").unwrap(), "indexer");
+        assert_eq!(
+            requests[0].param("query").unwrap(),
+            "INSERT INTO move_resources FORMAT JSONEachRow"
+        );
+        assert_eq!(requests[0].body.lines().count(), 2);
+    }
+}
@@ -0,0 +1,118 @@
+//! A minimal HTTP server that records requests and answers them with canned responses, so the
+//! ClickHouse sink can be tested without a running ClickHouse.
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+#[derive(Clone, Debug)]
+pub struct MockRequest {
+    pub path: String,
+    pub body: String,
+}
+
+impl MockRequest {
+    /// Returns the decoded value of a URL query parameter.
+    pub fn param(&self, name: &str) -> Option<String> {
+        url::Url::parse(&format!("http://localhost{}", self.path))
+            .ok()?
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    }
+}
+
+pub struct MockClickhouseServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockClickhouseServer {
+    /// Starts the server. `respond` returns the response body for every request.
+    pub async fn start<F>(respond: F) -> Self
+    where
+        F: Fn(&MockRequest) -> String + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let recorded_requests = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let request = read_request(&mut stream).await;
+                let response_body = respond(&request);
+                recorded_requests.lock().unwrap().push(request);
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response_body.len(),
+                    response_body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        Self { addr, requests }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> MockRequest {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await.unwrap();
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position + 4;
+        }
+        if read == 0 {
+            break buffer.len();
+        }
+    };
+
+    let headers = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let path = headers
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+    let content_length = headers
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.eq_ignore_ascii_case("content-length") {
+                value.trim().parse::<usize>().ok()
+            } else {
+                None
+            }
+        })
+        .unwrap_or(0);
+
+    while buffer.len() < header_end + content_length {
+        let read = stream.read(&mut chunk).await.unwrap();
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let body_end = buffer.len().min(header_end + content_length);
+    let body = String::from_utf8_lossy(&buffer[header_end..body_end]).to_string();
+
+    MockRequest { path, body }
+}
@@ -0,0 +1,5 @@
+pub mod clickhouse_client;
+pub mod clickhouse_status_saver;
+pub mod clickhouse_writer_step;
+#[cfg(test)]
+pub mod mock_clickhouse_server;
@@ -1,13 +1,45 @@
 use crate::{
-    config::db_config::DbConfig,
+    config::{
+        db_config::{DbConfig, ParquetConfig, ParquetPartitionScheme, ParquetStorageBackendConfig},
+        indexer_processor_config::IndexerProcessorConfig,
+        processor_config::{ParquetDefaultProcessorConfig, ParquetWriterConfig},
+        processor_mode::ProcessorMode,
+    },
     parquet_processors::{
+        clickhouse_utils::{
+            clickhouse_client::ClickhouseClient,
+            clickhouse_status_saver::{
+                create_clickhouse_metadata_tables, get_clickhouse_end_version,
+                get_clickhouse_starting_version, ClickhouseChainIdChecker,
+                ClickhouseProcessorStatusSaver,
+            },
+            clickhouse_writer_step::{
+                create_clickhouse_tables, to_json_each_row, ClickhouseWriterStep,
+            },
+        },
+        parquet_processor_status_saver::{
+            get_parquet_end_version, get_parquet_starting_version, ParquetProcessorStatusSaver,
+        },
         parquet_transaction_metadata::transaction_metadata_models::write_set_size_info::ParquetWriteSetSize,
         parquet_utils::{
-            gcs_uploader::{create_new_writer, GCSUploader},
+            gcs_uploader::GCSUploader,
+            local_uploader::LocalUploader,
             parquet_buffer_step::ParquetBufferStep,
+            parquet_file_ledger::ParquetFileLedger,
+            parquet_uploader::{
+                create_new_writer, partition_for_row, writer_properties, ParquetFileManifest,
+                ParquetUploader, Uploadable,
+            },
+            parquet_version_tracker_step::ParquetVersionTrackerStep,
+            s3_uploader::S3Uploader,
+            util::{HasParquetSchema, HasVersion},
         },
     },
     processors::{
+        account_restoration::account_restoration_models::{
+            auth_key_account_addresses::ParquetAuthKeyAccountAddress,
+            public_key_auth_keys::ParquetPublicKeyAuthKey,
+        },
         account_transactions::account_transactions_model::ParquetAccountTransaction,
         ans::models::{
             ans_lookup_v2::{ParquetAnsLookupV2, ParquetCurrentAnsLookupV2},
@@ -22,17 +54,30 @@ use crate::{
             write_set_changes::ParquetWriteSetChange,
         },
         events::events_model::ParquetEvent,
-        fungible_asset::fungible_asset_models::{
-            v2_fungible_asset_activities::ParquetFungibleAssetActivity,
-            v2_fungible_asset_balances::ParquetFungibleAssetBalance,
-            v2_fungible_asset_to_coin_mappings::ParquetFungibleAssetToCoinMapping,
-            v2_fungible_metadata::ParquetFungibleAssetMetadataModel,
+        fungible_asset::{
+            coin_models::{
+                coin_balances::ParquetCoinBalance, coin_infos::ParquetCoinInfo,
+                coin_supply::ParquetCoinSupply,
+            },
+            fungible_asset_models::{
+                v2_fungible_asset_activities::ParquetFungibleAssetActivity,
+                v2_fungible_asset_balances::ParquetFungibleAssetBalance,
+                v2_fungible_asset_to_coin_mappings::ParquetFungibleAssetToCoinMapping,
+                v2_fungible_metadata::ParquetFungibleAssetMetadataModel,
+            },
         },
+        nft_points::nft_points_model::ParquetNftPoints,
+        gas_fees::models::ParquetGasFee,
         objects::v2_objects_models::{ParquetCurrentObject, ParquetObject},
         stake::models::{
             delegator_activities::ParquetDelegatedStakingActivity,
             delegator_balances::{ParquetCurrentDelegatorBalance, ParquetDelegatorBalance},
+            delegator_pools::{
+                ParquetCurrentDelegatorPoolBalance, ParquetDelegatorPool,
+                ParquetDelegatorPoolBalance,
+            },
             proposal_votes::ParquetProposalVote,
+            staking_pool_voter::ParquetCurrentStakingPoolVoter,
         },
         token_v2::{
             token_models::{
@@ -52,29 +97,44 @@ use crate::{
         },
     },
     utils::table_flags::TableFlags,
+    MIGRATIONS,
 };
 use libra2_indexer_processor_sdk::{
-    postgres::utils::database::{new_db_pool, ArcDbPool},
-    utils::errors::ProcessorError,
+    builder::ProcessorBuilder,
+    common_steps::{TransactionStreamStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS},
+    libra2_indexer_transaction_stream::TransactionStreamConfig,
+    libra2_protos::transaction::v1::Transaction,
+    postgres::utils::{
+        checkpoint::PostgresChainIdChecker,
+        database::{new_db_pool, run_migrations, ArcDbPool},
+    },
+    traits::{async_step::AsyncRunType, AsyncStep, IntoRunnableStep},
+    utils::{chain_id_check::check_or_update_chain_id, errors::ProcessorError},
 };
+use anyhow::Context;
 use async_trait::async_trait;
 use enum_dispatch::enum_dispatch;
 use google_cloud_storage::client::{Client as GCSClient, ClientConfig as GcsClientConfig};
-use parquet::schema::types::Type;
+use parquet::{file::properties::WriterProperties, schema::types::Type};
 #[allow(unused_imports)]
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
     time::Duration,
 };
 use strum::{Display, EnumIter};
+use tracing::{debug, info};
 
+pub mod clickhouse_utils;
+pub mod parquet_account_restoration;
 pub mod parquet_account_transactions;
 pub mod parquet_ans;
 pub mod parquet_default;
 pub mod parquet_events;
 pub mod parquet_fungible_asset;
+pub mod parquet_gas_fees;
+pub mod parquet_nft_points;
 pub mod parquet_objects;
 pub mod parquet_processor_status_saver;
 pub mod parquet_stake;
@@ -131,6 +191,10 @@ pub enum ParquetTypeEnum {
     FungibleAssetMetadata,
     FungibleAssetBalances,
     FungibleAssetToCoinMappings,
+    // coin v1
+    CoinSupply,
+    CoinInfos,
+    CoinBalances,
     // txn metadata,
     WriteSetSize,
     // account transactions
@@ -150,9 +214,20 @@ pub enum ParquetTypeEnum {
     CurrentDelegatorBalances,
     DelegatorBalances,
     ProposalVotes,
+    CurrentStakingPoolVoter,
+    DelegatedStakingPools,
+    DelegatedStakingPoolBalances,
+    CurrentDelegatedStakingPoolBalances,
     // Objects
     Objects,
     CurrentObjects,
+    // nft points
+    NftPoints,
+    // account restoration
+    AuthKeyAccountAddresses,
+    PublicKeyAuthKeys,
+    // gas fees
+    GasFees,
 }
 
 /// Trait for handling various Parquet types.
@@ -162,17 +237,44 @@ pub trait ParquetTypeTrait: std::fmt::Debug + Send + Sync {
     fn parquet_type(&self) -> ParquetTypeEnum;
     fn calculate_size(&self) -> usize;
 
-    async fn upload_to_gcs(
+    /// The parquet schema of the rows, the same for empty buffers.
+    fn schema(&self) -> Arc<Type>;
+    fn schema_version(&self) -> u32;
+
+    /// Moves the rows into one buffer per partition of `partition_scheme`, leaving `self` empty.
+    /// The partition is `None` when the scheme doesn't partition files.
+    fn split_by_partition(
+        &mut self,
+        partition_scheme: &ParquetPartitionScheme,
+    ) -> Vec<(Option<String>, ParquetTypeStructs)>;
+
+    /// Drops the rows at or below `version`.
+    fn retain_after_version(&mut self, version: i64);
+
+    /// Returns the manifest of the uploaded file, or `None` when there were no rows to upload.
+    async fn upload_to_storage(
         &self,
-        uploader: &mut GCSUploader,
+        uploader: &mut ParquetUploader,
         parquet_type: ParquetTypeEnum,
         table_name: &str,
-    ) -> anyhow::Result<()>;
+        partition: Option<&str>,
+    ) -> anyhow::Result<Option<ParquetFileManifest>>;
+
+    /// Serializes the rows into the `JSONEachRow` format used to insert into ClickHouse.
+    fn to_clickhouse_rows(&self) -> anyhow::Result<Vec<u8>>;
 }
 
 /// Macro for implementing ParquetTypeTrait for multiple types.
+///
+/// The optional third argument is the timestamp field used by date partitioning.
 macro_rules! impl_parquet_trait {
     ($type:ty, $enum_variant:expr) => {
+        impl_parquet_trait!(@impl $type, $enum_variant, |_row: &$type| None);
+    };
+    ($type:ty, $enum_variant:expr, $timestamp_field:ident) => {
+        impl_parquet_trait!(@impl $type, $enum_variant, |row: &$type| Some(row.$timestamp_field));
+    };
+    (@impl $type:ty, $enum_variant:expr, $get_timestamp:expr) => {
         #[async_trait]
         impl ParquetTypeTrait for Vec<$type> {
             fn parquet_type(&self) -> ParquetTypeEnum {
@@ -183,103 +285,261 @@ macro_rules! impl_parquet_trait {
                 allocative::size_of_unique(self)
             }
 
-            async fn upload_to_gcs(
+            fn schema(&self) -> Arc<Type> {
+                <$type>::schema()
+            }
+
+            fn schema_version(&self) -> u32 {
+                <$type>::schema_version()
+            }
+
+            fn split_by_partition(
+                &mut self,
+                partition_scheme: &ParquetPartitionScheme,
+            ) -> Vec<(Option<String>, ParquetTypeStructs)> {
+                let get_timestamp: fn(&$type) -> Option<chrono::NaiveDateTime> = $get_timestamp;
+                let mut partitions: BTreeMap<Option<String>, Vec<$type>> = BTreeMap::new();
+                for row in std::mem::take(self) {
+                    let partition =
+                        partition_for_row(partition_scheme, row.version(), get_timestamp(&row));
+                    partitions.entry(partition).or_default().push(row);
+                }
+                partitions
+                    .into_iter()
+                    .map(|(partition, rows)| (partition, ParquetTypeStructs::from(rows)))
+                    .collect()
+            }
+
+            fn retain_after_version(&mut self, version: i64) {
+                self.retain(|row| row.version() > version);
+            }
+
+            async fn upload_to_storage(
                 &self,
-                uploader: &mut GCSUploader,
+                uploader: &mut ParquetUploader,
                 parquet_type: ParquetTypeEnum,
                 table_name: &str,
-            ) -> anyhow::Result<()> {
+                partition: Option<&str>,
+            ) -> anyhow::Result<Option<ParquetFileManifest>> {
                 uploader
-                    .upload_generic(self, parquet_type, table_name)
+                    .upload_generic(self, parquet_type, table_name, partition)
                     .await
             }
+
+            fn to_clickhouse_rows(&self) -> anyhow::Result<Vec<u8>> {
+                to_json_each_row(self)
+            }
         }
     };
 }
 
 // Apply macro to supported types
-impl_parquet_trait!(ParquetMoveResource, ParquetTypeEnum::MoveResources);
-impl_parquet_trait!(ParquetWriteSetChange, ParquetTypeEnum::WriteSetChanges);
-impl_parquet_trait!(ParquetTransaction, ParquetTypeEnum::Transactions);
-impl_parquet_trait!(ParquetTableItem, ParquetTypeEnum::TableItems);
-impl_parquet_trait!(ParquetMoveModule, ParquetTypeEnum::MoveModules);
-impl_parquet_trait!(ParquetCurrentTableItem, ParquetTypeEnum::CurrentTableItems);
+impl_parquet_trait!(
+    ParquetMoveResource,
+    ParquetTypeEnum::MoveResources,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetWriteSetChange,
+    ParquetTypeEnum::WriteSetChanges,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetTransaction,
+    ParquetTypeEnum::Transactions,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetTableItem,
+    ParquetTypeEnum::TableItems,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetMoveModule,
+    ParquetTypeEnum::MoveModules,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetCurrentTableItem,
+    ParquetTypeEnum::CurrentTableItems,
+    block_timestamp
+);
 impl_parquet_trait!(
     ParquetBlockMetadataTransaction,
-    ParquetTypeEnum::BlockMetadataTransactions
+    ParquetTypeEnum::BlockMetadataTransactions,
+    block_timestamp
 );
 impl_parquet_trait!(ParquetTableMetadata, ParquetTypeEnum::TableMetadata);
-impl_parquet_trait!(ParquetEvent, ParquetTypeEnum::Events);
-impl_parquet_trait!(ParquetUserTransaction, ParquetTypeEnum::UserTransactions);
-impl_parquet_trait!(ParquetSignature, ParquetTypeEnum::Signatures);
-impl_parquet_trait!(ParquetAnsPrimaryNameV2, ParquetTypeEnum::AnsPrimaryNameV2);
+impl_parquet_trait!(ParquetEvent, ParquetTypeEnum::Events, block_timestamp);
+impl_parquet_trait!(
+    ParquetUserTransaction,
+    ParquetTypeEnum::UserTransactions,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetSignature,
+    ParquetTypeEnum::Signatures,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetAnsPrimaryNameV2,
+    ParquetTypeEnum::AnsPrimaryNameV2,
+    block_timestamp
+);
 impl_parquet_trait!(
     ParquetCurrentAnsPrimaryNameV2,
     ParquetTypeEnum::CurrentAnsPrimaryNameV2
 );
-impl_parquet_trait!(ParquetAnsLookupV2, ParquetTypeEnum::AnsLookupV2);
+impl_parquet_trait!(
+    ParquetAnsLookupV2,
+    ParquetTypeEnum::AnsLookupV2,
+    block_timestamp
+);
 impl_parquet_trait!(
     ParquetCurrentAnsLookupV2,
     ParquetTypeEnum::CurrentAnsLookupV2
 );
 impl_parquet_trait!(
     ParquetFungibleAssetActivity,
-    ParquetTypeEnum::FungibleAssetActivities
+    ParquetTypeEnum::FungibleAssetActivities,
+    block_timestamp
 );
 impl_parquet_trait!(
     ParquetFungibleAssetMetadataModel,
-    ParquetTypeEnum::FungibleAssetMetadata
+    ParquetTypeEnum::FungibleAssetMetadata,
+    last_transaction_timestamp
 );
 impl_parquet_trait!(
     ParquetFungibleAssetBalance,
-    ParquetTypeEnum::FungibleAssetBalances
+    ParquetTypeEnum::FungibleAssetBalances,
+    block_timestamp
 );
 impl_parquet_trait!(
     ParquetFungibleAssetToCoinMapping,
     ParquetTypeEnum::FungibleAssetToCoinMappings
 );
-impl_parquet_trait!(ParquetWriteSetSize, ParquetTypeEnum::WriteSetSize);
+impl_parquet_trait!(
+    ParquetCoinSupply,
+    ParquetTypeEnum::CoinSupply,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetCoinInfo,
+    ParquetTypeEnum::CoinInfos,
+    transaction_created_timestamp
+);
+impl_parquet_trait!(
+    ParquetCoinBalance,
+    ParquetTypeEnum::CoinBalances,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetWriteSetSize,
+    ParquetTypeEnum::WriteSetSize,
+    block_timestamp
+);
 impl_parquet_trait!(
     ParquetAccountTransaction,
-    ParquetTypeEnum::AccountTransactions
+    ParquetTypeEnum::AccountTransactions,
+    block_timestamp
 );
 impl_parquet_trait!(
     ParquetCurrentTokenPendingClaim,
-    ParquetTypeEnum::CurrentTokenPendingClaims
+    ParquetTypeEnum::CurrentTokenPendingClaims,
+    last_transaction_timestamp
 );
 impl_parquet_trait!(
     ParquetCurrentTokenRoyaltyV1,
-    ParquetTypeEnum::CurrentTokenRoyaltiesV1
+    ParquetTypeEnum::CurrentTokenRoyaltiesV1,
+    last_transaction_timestamp
 );
 impl_parquet_trait!(
     ParquetCurrentTokenV2Metadata,
-    ParquetTypeEnum::CurrentTokenV2Metadata
+    ParquetTypeEnum::CurrentTokenV2Metadata,
+    last_transaction_timestamp
+);
+impl_parquet_trait!(
+    ParquetTokenActivityV2,
+    ParquetTypeEnum::TokenActivitiesV2,
+    block_timestamp
 );
-impl_parquet_trait!(ParquetTokenActivityV2, ParquetTypeEnum::TokenActivitiesV2);
 
-impl_parquet_trait!(ParquetTokenDataV2, ParquetTypeEnum::TokenDatasV2);
+impl_parquet_trait!(
+    ParquetTokenDataV2,
+    ParquetTypeEnum::TokenDatasV2,
+    block_timestamp
+);
 impl_parquet_trait!(
     ParquetCurrentTokenDataV2,
-    ParquetTypeEnum::CurrentTokenDatasV2
+    ParquetTypeEnum::CurrentTokenDatasV2,
+    last_transaction_timestamp
+);
+impl_parquet_trait!(
+    ParquetTokenOwnershipV2,
+    ParquetTypeEnum::TokenOwnershipsV2,
+    block_timestamp
 );
-impl_parquet_trait!(ParquetTokenOwnershipV2, ParquetTypeEnum::TokenOwnershipsV2);
 impl_parquet_trait!(
     ParquetCurrentTokenOwnershipV2,
-    ParquetTypeEnum::CurrentTokenOwnershipsV2
+    ParquetTypeEnum::CurrentTokenOwnershipsV2,
+    last_transaction_timestamp
 );
 impl_parquet_trait!(
     ParquetDelegatedStakingActivity,
-    ParquetTypeEnum::DelegatedStakingActivities
+    ParquetTypeEnum::DelegatedStakingActivities,
+    block_timestamp
 );
 impl_parquet_trait!(
     ParquetCurrentDelegatorBalance,
-    ParquetTypeEnum::CurrentDelegatorBalances
+    ParquetTypeEnum::CurrentDelegatorBalances,
+    block_timestamp
 );
-impl_parquet_trait!(ParquetDelegatorBalance, ParquetTypeEnum::DelegatorBalances);
-impl_parquet_trait!(ParquetProposalVote, ParquetTypeEnum::ProposalVotes);
-impl_parquet_trait!(ParquetObject, ParquetTypeEnum::Objects);
-impl_parquet_trait!(ParquetCurrentObject, ParquetTypeEnum::CurrentObjects);
-impl_parquet_trait!(ParquetCollectionV2, ParquetTypeEnum::CollectionsV2);
+impl_parquet_trait!(
+    ParquetDelegatorBalance,
+    ParquetTypeEnum::DelegatorBalances,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetProposalVote,
+    ParquetTypeEnum::ProposalVotes,
+    transaction_timestamp
+);
+impl_parquet_trait!(
+    ParquetCurrentStakingPoolVoter,
+    ParquetTypeEnum::CurrentStakingPoolVoter,
+    block_timestamp
+);
+impl_parquet_trait!(ParquetDelegatorPool, ParquetTypeEnum::DelegatedStakingPools);
+impl_parquet_trait!(
+    ParquetDelegatorPoolBalance,
+    ParquetTypeEnum::DelegatedStakingPoolBalances
+);
+impl_parquet_trait!(
+    ParquetCurrentDelegatorPoolBalance,
+    ParquetTypeEnum::CurrentDelegatedStakingPoolBalances
+);
+impl_parquet_trait!(ParquetObject, ParquetTypeEnum::Objects, block_timestamp);
+impl_parquet_trait!(
+    ParquetCurrentObject,
+    ParquetTypeEnum::CurrentObjects,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetCollectionV2,
+    ParquetTypeEnum::CollectionsV2,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetNftPoints,
+    ParquetTypeEnum::NftPoints,
+    block_timestamp
+);
+impl_parquet_trait!(
+    ParquetAuthKeyAccountAddress,
+    ParquetTypeEnum::AuthKeyAccountAddresses
+);
+impl_parquet_trait!(ParquetPublicKeyAuthKey, ParquetTypeEnum::PublicKeyAuthKeys);
+impl_parquet_trait!(ParquetGasFee, ParquetTypeEnum::GasFees, block_timestamp);
 
 #[derive(Debug, Clone)]
 #[enum_dispatch(ParquetTypeTrait)]
@@ -308,6 +568,10 @@ pub enum ParquetTypeStructs {
     FungibleAssetMetadata(Vec<ParquetFungibleAssetMetadataModel>),
     FungibleAssetBalance(Vec<ParquetFungibleAssetBalance>),
     FungibleAssetToCoinMappings(Vec<ParquetFungibleAssetToCoinMapping>),
+    // Coin v1
+    CoinSupply(Vec<ParquetCoinSupply>),
+    CoinInfo(Vec<ParquetCoinInfo>),
+    CoinBalance(Vec<ParquetCoinBalance>),
     // Txn metadata
     WriteSetSize(Vec<ParquetWriteSetSize>),
     // account txn
@@ -327,9 +591,20 @@ pub enum ParquetTypeStructs {
     CurrentDelegatorBalance(Vec<ParquetCurrentDelegatorBalance>),
     DelegatorBalance(Vec<ParquetDelegatorBalance>),
     ProposalVote(Vec<ParquetProposalVote>),
+    CurrentStakingPoolVoter(Vec<ParquetCurrentStakingPoolVoter>),
+    DelegatorPool(Vec<ParquetDelegatorPool>),
+    DelegatorPoolBalance(Vec<ParquetDelegatorPoolBalance>),
+    CurrentDelegatorPoolBalance(Vec<ParquetCurrentDelegatorPoolBalance>),
     // Objects
     Object(Vec<ParquetObject>),
     CurrentObject(Vec<ParquetCurrentObject>),
+    // Nft points
+    NftPoints(Vec<ParquetNftPoints>),
+    // Account restoration
+    AuthKeyAccountAddress(Vec<ParquetAuthKeyAccountAddress>),
+    PublicKeyAuthKey(Vec<ParquetPublicKeyAuthKey>),
+    // Gas fees
+    GasFee(Vec<ParquetGasFee>),
 }
 
 impl ParquetTypeStructs {
@@ -368,6 +643,9 @@ impl ParquetTypeStructs {
             ParquetTypeEnum::FungibleAssetToCoinMappings => {
                 ParquetTypeStructs::FungibleAssetToCoinMappings(Vec::new())
             },
+            ParquetTypeEnum::CoinSupply => ParquetTypeStructs::CoinSupply(Vec::new()),
+            ParquetTypeEnum::CoinInfos => ParquetTypeStructs::CoinInfo(Vec::new()),
+            ParquetTypeEnum::CoinBalances => ParquetTypeStructs::CoinBalance(Vec::new()),
             ParquetTypeEnum::WriteSetSize => ParquetTypeStructs::WriteSetSize(Vec::new()),
             ParquetTypeEnum::AccountTransactions => {
                 ParquetTypeStructs::AccountTransaction(Vec::new())
@@ -398,9 +676,25 @@ impl ParquetTypeStructs {
             },
             ParquetTypeEnum::DelegatorBalances => ParquetTypeStructs::DelegatorBalance(Vec::new()),
             ParquetTypeEnum::ProposalVotes => ParquetTypeStructs::ProposalVote(Vec::new()),
+            ParquetTypeEnum::CurrentStakingPoolVoter => {
+                ParquetTypeStructs::CurrentStakingPoolVoter(Vec::new())
+            },
+            ParquetTypeEnum::DelegatedStakingPools => ParquetTypeStructs::DelegatorPool(Vec::new()),
+            ParquetTypeEnum::DelegatedStakingPoolBalances => {
+                ParquetTypeStructs::DelegatorPoolBalance(Vec::new())
+            },
+            ParquetTypeEnum::CurrentDelegatedStakingPoolBalances => {
+                ParquetTypeStructs::CurrentDelegatorPoolBalance(Vec::new())
+            },
             ParquetTypeEnum::Objects => ParquetTypeStructs::Object(Vec::new()),
             ParquetTypeEnum::CurrentObjects => ParquetTypeStructs::CurrentObject(Vec::new()),
             ParquetTypeEnum::CollectionsV2 => ParquetTypeStructs::CollectionV2(Vec::new()),
+            ParquetTypeEnum::NftPoints => ParquetTypeStructs::NftPoints(Vec::new()),
+            ParquetTypeEnum::AuthKeyAccountAddresses => {
+                ParquetTypeStructs::AuthKeyAccountAddress(Vec::new())
+            },
+            ParquetTypeEnum::PublicKeyAuthKeys => ParquetTypeStructs::PublicKeyAuthKey(Vec::new()),
+            ParquetTypeEnum::GasFees => ParquetTypeStructs::GasFee(Vec::new()),
         }
     }
 
@@ -501,6 +795,21 @@ impl ParquetTypeStructs {
             ) => {
                 handle_append!(self_data, other_data)
             },
+            (
+                ParquetTypeStructs::CoinSupply(self_data),
+                ParquetTypeStructs::CoinSupply(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
+            (ParquetTypeStructs::CoinInfo(self_data), ParquetTypeStructs::CoinInfo(other_data)) => {
+                handle_append!(self_data, other_data)
+            },
+            (
+                ParquetTypeStructs::CoinBalance(self_data),
+                ParquetTypeStructs::CoinBalance(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
             (
                 ParquetTypeStructs::WriteSetSize(self_data),
                 ParquetTypeStructs::WriteSetSize(other_data),
@@ -610,6 +919,30 @@ impl ParquetTypeStructs {
             ) => {
                 handle_append!(self_data, other_data)
             },
+            (
+                ParquetTypeStructs::CurrentStakingPoolVoter(self_data),
+                ParquetTypeStructs::CurrentStakingPoolVoter(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
+            (
+                ParquetTypeStructs::DelegatorPool(self_data),
+                ParquetTypeStructs::DelegatorPool(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
+            (
+                ParquetTypeStructs::DelegatorPoolBalance(self_data),
+                ParquetTypeStructs::DelegatorPoolBalance(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
+            (
+                ParquetTypeStructs::CurrentDelegatorPoolBalance(self_data),
+                ParquetTypeStructs::CurrentDelegatorPoolBalance(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
             (ParquetTypeStructs::Object(self_data), ParquetTypeStructs::Object(other_data)) => {
                 handle_append!(self_data, other_data)
             },
@@ -625,6 +958,27 @@ impl ParquetTypeStructs {
             ) => {
                 handle_append!(self_data, other_data)
             },
+            (
+                ParquetTypeStructs::NftPoints(self_data),
+                ParquetTypeStructs::NftPoints(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
+            (
+                ParquetTypeStructs::AuthKeyAccountAddress(self_data),
+                ParquetTypeStructs::AuthKeyAccountAddress(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
+            (
+                ParquetTypeStructs::PublicKeyAuthKey(self_data),
+                ParquetTypeStructs::PublicKeyAuthKey(other_data),
+            ) => {
+                handle_append!(self_data, other_data)
+            },
+            (ParquetTypeStructs::GasFee(self_data), ParquetTypeStructs::GasFee(other_data)) => {
+                handle_append!(self_data, other_data)
+            },
             _ => Err(ProcessorError::ProcessError {
                 message: "Mismatched buffer types in append operation".to_string(),
             }),
@@ -645,6 +999,29 @@ async fn initialize_gcs_client(credentials: Option<String>) -> Arc<GCSClient> {
     Arc::new(GCSClient::new(gcs_config))
 }
 
+/// Initializes the storage backend that parquet files are uploaded to.
+pub async fn initialize_uploader(
+    parquet_config: &ParquetConfig,
+) -> anyhow::Result<Arc<dyn Uploadable>> {
+    match &parquet_config.storage_backend {
+        ParquetStorageBackendConfig::Gcs => {
+            let gcs_client =
+                initialize_gcs_client(parquet_config.google_application_credentials.clone()).await;
+            Ok(Arc::new(GCSUploader::new(
+                gcs_client,
+                parquet_config.bucket_name.clone(),
+            )))
+        },
+        ParquetStorageBackendConfig::S3(s3_config) => Ok(Arc::new(S3Uploader::new(
+            parquet_config.bucket_name.clone(),
+            s3_config,
+        )?)),
+        ParquetStorageBackendConfig::Local(local_config) => {
+            Ok(Arc::new(LocalUploader::new(&local_config.root_dir)))
+        },
+    }
+}
+
 /// Initializes the database connection pool.
 async fn initialize_database_pool(config: &DbConfig) -> anyhow::Result<ArcDbPool> {
     match config {
@@ -667,42 +1044,271 @@ async fn initialize_database_pool(config: &DbConfig) -> anyhow::Result<ArcDbPool
     }
 }
 
+/// Builds the writer properties of every table, from `table_writer_properties` when the table
+/// is listed there and from `writer_properties` otherwise.
+fn initialize_writer_properties(
+    parquet_processor_config: &ParquetDefaultProcessorConfig,
+    parquet_type_to_schemas: &HashMap<ParquetTypeEnum, Arc<Type>>,
+) -> anyhow::Result<HashMap<ParquetTypeEnum, Arc<WriterProperties>>> {
+    for table_name in parquet_processor_config.table_writer_properties.keys() {
+        if !parquet_type_to_schemas
+            .keys()
+            .any(|parquet_type| parquet_type.to_string() == *table_name)
+        {
+            anyhow::bail!("table_writer_properties has unknown table {table_name}");
+        }
+    }
+
+    parquet_type_to_schemas
+        .iter()
+        .map(|(parquet_type, schema)| {
+            let table_name = parquet_type.to_string();
+            let writer_config: &ParquetWriterConfig = parquet_processor_config
+                .table_writer_properties
+                .get(&table_name)
+                .unwrap_or(&parquet_processor_config.writer_properties);
+            for column in &writer_config.bloom_filter_columns {
+                if !schema
+                    .get_fields()
+                    .iter()
+                    .any(|field| field.name() == column)
+                {
+                    anyhow::bail!("Bloom filter column {column} doesn't exist in {table_name}");
+                }
+            }
+            let props = writer_properties(writer_config)
+                .with_context(|| format!("Invalid writer properties for {table_name}"))?;
+            Ok((*parquet_type, Arc::new(props)))
+        })
+        .collect()
+}
+
 /// Initializes the Parquet buffer step.
 async fn initialize_parquet_buffer_step(
-    gcs_client: Arc<GCSClient>,
+    uploader: Arc<dyn Uploadable>,
     parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>>,
+    parquet_type_to_properties: HashMap<ParquetTypeEnum, Arc<WriterProperties>>,
     upload_interval: u64,
     max_buffer_size: usize,
-    bucket_name: String,
     bucket_root: String,
+    partition_scheme: ParquetPartitionScheme,
     processor_name: String,
+    file_ledger: Option<ParquetFileLedger>,
+    committed_end_versions: HashMap<ParquetTypeEnum, i64>,
 ) -> anyhow::Result<ParquetBufferStep> {
+    if let ParquetPartitionScheme::VersionBucket { bucket_size } = partition_scheme {
+        if bucket_size <= 0 {
+            anyhow::bail!("partition_scheme.bucket_size must be positive, got {bucket_size}");
+        }
+    }
+
     let parquet_type_to_writer = parquet_type_to_schemas
         .iter()
         .map(|(key, schema)| {
-            let writer = create_new_writer(schema.clone()).expect("Failed to create writer");
+            let props = parquet_type_to_properties[key].clone();
+            let writer = create_new_writer(schema.clone(), props).expect("Failed to create writer");
             (*key, writer)
         })
         .collect();
 
-    let buffer_uploader = GCSUploader::new(
-        gcs_client,
+    let buffer_uploader = ParquetUploader::new(
+        uploader,
         parquet_type_to_schemas,
         parquet_type_to_writer,
-        bucket_name,
+        parquet_type_to_properties,
         bucket_root,
+        partition_scheme,
         processor_name,
+        file_ledger,
     )?;
 
     let default_size_buffer_step = ParquetBufferStep::new(
         Duration::from_secs(upload_interval),
         buffer_uploader,
         max_buffer_size,
+        committed_end_versions,
     );
 
     Ok(default_size_buffer_step)
 }
 
+/// Runs a parquet processor: streams transactions through `extractor` and writes the extracted
+/// parquet models to the sink configured in `db_config`.
+///
+/// - `ParquetConfig`: models are buffered into parquet files and uploaded to the storage backend,
+///   with checkpoints saved to Postgres.
+/// - `ClickhouseConfig`: models are inserted into ClickHouse tables, with checkpoints saved in the
+///   same ClickHouse database.
+async fn run_parquet_processor<E>(
+    config: &IndexerProcessorConfig,
+    extractor: E,
+    parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>>,
+    parquet_processor_config: &ParquetDefaultProcessorConfig,
+) -> anyhow::Result<()>
+where
+    E: AsyncStep<
+        Input = Vec<Transaction>,
+        Output = HashMap<ParquetTypeEnum, ParquetTypeStructs>,
+        RunType = AsyncRunType,
+    >,
+{
+    let processor_name = config.processor_config.name();
+    let channel_size = parquet_processor_config.channel_size;
+
+    let output_receiver = match config.db_config {
+        DbConfig::ParquetConfig(ref parquet_db_config) => {
+            let db_pool = initialize_database_pool(&config.db_config).await?;
+            run_migrations(
+                parquet_db_config.connection_string.clone(),
+                db_pool.clone(),
+                MIGRATIONS,
+            )
+            .await;
+
+            // Check and update the ledger chain id to ensure we're indexing the correct chain
+            check_or_update_chain_id(
+                &config.transaction_stream_config,
+                &PostgresChainIdChecker::new(db_pool.clone()),
+            )
+            .await?;
+
+            let (starting_version, ending_version) = (
+                get_parquet_starting_version(config, db_pool.clone()).await?,
+                get_parquet_end_version(config, db_pool.clone()).await?,
+            );
+            let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+                starting_version,
+                request_ending_version: ending_version,
+                ..config.transaction_stream_config.clone()
+            })
+            .await?;
+
+            let uploader = initialize_uploader(parquet_db_config).await?;
+
+            // Testing mode doesn't save checkpoints, so there's nothing to commit files with
+            let file_ledger = (!matches!(config.processor_mode, ProcessorMode::Testing(_)))
+                .then(|| ParquetFileLedger::new(db_pool.clone(), processor_name.to_string()));
+            let committed_end_versions = match &file_ledger {
+                Some(file_ledger) => file_ledger.recover(uploader.as_ref()).await?,
+                None => HashMap::new(),
+            };
+            // Backfills rewrite the versions they cover, so only the default mode skips them
+            let committed_end_versions = match config.processor_mode {
+                ProcessorMode::Default(_) => parquet_type_to_schemas
+                    .keys()
+                    .filter_map(|parquet_type| {
+                        let end_version = committed_end_versions.get(&parquet_type.to_string())?;
+                        Some((*parquet_type, *end_version))
+                    })
+                    .collect(),
+                _ => HashMap::new(),
+            };
+
+            let parquet_type_to_properties =
+                initialize_writer_properties(parquet_processor_config, &parquet_type_to_schemas)?;
+            let parquet_buffer_step = initialize_parquet_buffer_step(
+                uploader,
+                parquet_type_to_schemas,
+                parquet_type_to_properties,
+                parquet_processor_config.upload_interval,
+                parquet_processor_config.max_buffer_size,
+                parquet_db_config.bucket_root.clone(),
+                parquet_db_config.partition_scheme.clone(),
+                processor_name.to_string(),
+                file_ledger,
+                committed_end_versions,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                panic!("Failed to initialize parquet buffer step: {e:?}");
+            });
+
+            let parquet_version_tracker_step = ParquetVersionTrackerStep::new(
+                ParquetProcessorStatusSaver::new(config.clone(), db_pool),
+                DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+            );
+
+            // Connect processor steps together
+            let (_, output_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+                transaction_stream.into_runnable_step(),
+            )
+            .connect_to(extractor.into_runnable_step(), channel_size)
+            .connect_to(parquet_buffer_step.into_runnable_step(), channel_size)
+            .connect_to(
+                parquet_version_tracker_step.into_runnable_step(),
+                channel_size,
+            )
+            .end_and_return_output_receiver(channel_size);
+            output_receiver
+        },
+        DbConfig::ClickhouseConfig(ref clickhouse_config) => {
+            let client = ClickhouseClient::new(clickhouse_config)?;
+            create_clickhouse_metadata_tables(&client).await?;
+
+            // Check and update the ledger chain id to ensure we're indexing the correct chain
+            check_or_update_chain_id(
+                &config.transaction_stream_config,
+                &ClickhouseChainIdChecker::new(client.clone()),
+            )
+            .await?;
+
+            let (starting_version, ending_version) = (
+                get_clickhouse_starting_version(config, &client).await?,
+                get_clickhouse_end_version(config)?,
+            );
+            let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+                starting_version,
+                request_ending_version: ending_version,
+                ..config.transaction_stream_config.clone()
+            })
+            .await?;
+
+            create_clickhouse_tables(&client, &parquet_type_to_schemas).await?;
+            let clickhouse_writer_step = ClickhouseWriterStep::new(client.clone());
+
+            let parquet_version_tracker_step = ParquetVersionTrackerStep::new(
+                ClickhouseProcessorStatusSaver::new(config.clone(), client),
+                DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+            );
+
+            // Connect processor steps together
+            let (_, output_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+                transaction_stream.into_runnable_step(),
+            )
+            .connect_to(extractor.into_runnable_step(), channel_size)
+            .connect_to(clickhouse_writer_step.into_runnable_step(), channel_size)
+            .connect_to(
+                parquet_version_tracker_step.into_runnable_step(),
+                channel_size,
+            )
+            .end_and_return_output_receiver(channel_size);
+            output_receiver
+        },
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Invalid db config for {}: {:?}",
+                processor_name,
+                config.db_config
+            ));
+        },
+    };
+
+    loop {
+        match output_receiver.recv().await {
+            Ok(txn_context) => {
+                debug!(
+                    "Finished processing versions [{:?}, {:?}]",
+                    txn_context.metadata.start_version, txn_context.metadata.end_version,
+                );
+            },
+            Err(e) => {
+                info!("No more transactions in channel: {:?}", e);
+                break Ok(());
+            },
+        }
+    }
+}
+
 /// Sets the backfill table flag.
 fn set_backfill_table_flag(table_names: HashSet<String>) -> TableFlags {
     let mut backfill_table = TableFlags::empty();
@@ -0,0 +1,73 @@
+use crate::{
+    config::{indexer_processor_config::IndexerProcessorConfig, processor_config::ProcessorConfig},
+    parquet_processors::{
+        parquet_account_restoration::parquet_account_restoration_extractor::ParquetAccountRestorationExtractor,
+        parquet_utils::util::HasParquetSchema, run_parquet_processor, set_backfill_table_flag,
+        ParquetTypeEnum,
+    },
+    processors::account_restoration::account_restoration_models::{
+        auth_key_account_addresses::ParquetAuthKeyAccountAddress,
+        public_key_auth_keys::ParquetPublicKeyAuthKey,
+    },
+};
+use libra2_indexer_processor_sdk::traits::processor_trait::ProcessorTrait;
+use parquet::schema::types::Type;
+use std::{collections::HashMap, sync::Arc};
+
+pub struct ParquetAccountRestorationProcessor {
+    pub config: IndexerProcessorConfig,
+}
+
+impl ParquetAccountRestorationProcessor {
+    pub async fn new(config: IndexerProcessorConfig) -> anyhow::Result<Self> {
+        Ok(Self { config })
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for ParquetAccountRestorationProcessor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    async fn run_processor(&self) -> anyhow::Result<()> {
+        let parquet_processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::ParquetAccountRestorationProcessor(parquet_processor_config) => {
+                parquet_processor_config
+            },
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor configuration for ParquetAccountRestorationProcessor {:?}",
+                    self.config.processor_config
+                ));
+            },
+        };
+
+        let backfill_table =
+            set_backfill_table_flag(parquet_processor_config.backfill_table.clone());
+        let parquet_account_restoration_extractor = ParquetAccountRestorationExtractor {
+            opt_in_tables: backfill_table,
+        };
+
+        let parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>> = [
+            (
+                ParquetTypeEnum::AuthKeyAccountAddresses,
+                ParquetAuthKeyAccountAddress::schema(),
+            ),
+            (
+                ParquetTypeEnum::PublicKeyAuthKeys,
+                ParquetPublicKeyAuthKey::schema(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        run_parquet_processor(
+            &self.config,
+            parquet_account_restoration_extractor,
+            parquet_type_to_schemas,
+            &parquet_processor_config,
+        )
+        .await
+    }
+}
@@ -4,7 +4,8 @@ use crate::{
         ParquetTypeStructs,
     },
     processors::account_transactions::{
-        account_transactions_model::ParquetAccountTransaction, parse_account_transactions,
+        account_transactions_model::{AccountOwners, ParquetAccountTransaction},
+        parse_account_transactions,
     },
     utils::table_flags::TableFlags,
 };
@@ -36,8 +37,10 @@ impl Processable for ParquetAccountTransactionsExtractor {
         &mut self,
         transactions: TransactionContext<Self::Input>,
     ) -> anyhow::Result<Option<TransactionContext<ParquetTypeMap>>, ProcessorError> {
+        // Parquet processors don't have a database, so owners only come from the batch
+        let owners = AccountOwners::from_transactions(&transactions.data);
         let acc_txns: Vec<ParquetAccountTransaction> =
-            parse_account_transactions(transactions.data)
+            parse_account_transactions(transactions.data, &owners)
                 .into_iter()
                 .map(ParquetAccountTransaction::from)
                 .collect();
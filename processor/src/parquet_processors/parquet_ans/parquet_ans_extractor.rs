@@ -48,6 +48,7 @@ impl Processable for ParquetAnsExtractor {
             raw_ans_lookups_v2,
             raw_current_ans_primary_names_v2,
             raw_ans_primary_name_v2,
+            _,
         ) = parse_ans(
             &input.data,
             self.ans_config.ans_v1_primary_names_table_handle.clone(),
@@ -4,6 +4,10 @@ use crate::{
         ParquetTypeStructs,
     },
     processors::fungible_asset::{
+        coin_models::{
+            coin_balances::ParquetCoinBalance, coin_infos::ParquetCoinInfo,
+            coin_supply::ParquetCoinSupply,
+        },
         fungible_asset_models::{
             v2_fungible_asset_activities::ParquetFungibleAssetActivity,
             v2_fungible_asset_balances::ParquetFungibleAssetBalance,
@@ -13,7 +17,7 @@ use crate::{
             },
             v2_fungible_metadata::ParquetFungibleAssetMetadataModel,
         },
-        fungible_asset_processor_helpers::{get_fa_to_coin_mapping, parse_v2_coin},
+        fungible_asset_processor_helpers::{get_fa_to_coin_mapping, parse_v1_coin, parse_v2_coin},
     },
     utils::table_flags::TableFlags,
 };
@@ -75,9 +79,10 @@ impl Processable for ParquetFungibleAssetExtractor {
             raw_fungible_asset_metadata,
             raw_fungible_asset_balances,
             _,
-            _raw_coin_supply,
+            raw_coin_supply,
             raw_fa_to_coin_mappings,
         ) = parse_v2_coin(&transactions.data, Some(&self.fa_to_coin_mapping)).await;
+        let (raw_coin_infos, raw_coin_balances) = parse_v1_coin(&transactions.data);
 
         let parquet_fungible_asset_activities: Vec<ParquetFungibleAssetActivity> =
             raw_fungible_asset_activities
@@ -103,6 +108,21 @@ impl Processable for ParquetFungibleAssetExtractor {
                 .map(ParquetFungibleAssetToCoinMapping::from)
                 .collect();
 
+        let parquet_coin_supply: Vec<ParquetCoinSupply> = raw_coin_supply
+            .into_iter()
+            .map(ParquetCoinSupply::from)
+            .collect();
+
+        let parquet_coin_infos: Vec<ParquetCoinInfo> = raw_coin_infos
+            .into_iter()
+            .map(ParquetCoinInfo::from)
+            .collect();
+
+        let parquet_coin_balances: Vec<ParquetCoinBalance> = raw_coin_balances
+            .into_iter()
+            .map(ParquetCoinBalance::from)
+            .collect();
+
         let mut map: HashMap<ParquetTypeEnum, ParquetTypeStructs> = HashMap::new();
 
         let data_types = [
@@ -126,6 +146,21 @@ impl Processable for ParquetFungibleAssetExtractor {
                 ParquetTypeEnum::FungibleAssetToCoinMappings,
                 ParquetTypeStructs::FungibleAssetToCoinMappings(parquet_fa_to_coin_mappings),
             ),
+            (
+                TableFlags::COIN_SUPPLY,
+                ParquetTypeEnum::CoinSupply,
+                ParquetTypeStructs::CoinSupply(parquet_coin_supply),
+            ),
+            (
+                TableFlags::COIN_INFOS,
+                ParquetTypeEnum::CoinInfos,
+                ParquetTypeStructs::CoinInfo(parquet_coin_infos),
+            ),
+            (
+                TableFlags::COIN_BALANCES,
+                ParquetTypeEnum::CoinBalances,
+                ParquetTypeStructs::CoinBalance(parquet_coin_balances),
+            ),
         ];
 
         // Populate the map based on opt-in tables
@@ -145,3 +180,63 @@ impl NamedStep for ParquetFungibleAssetExtractor {
         "ParquetFungibleAssetExtractor".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::fungible_asset::fungible_asset_processor_helpers::tests::{
+        coin_transaction, OWNER_ADDRESS,
+    };
+    use libra2_indexer_processor_sdk::types::transaction_context::TransactionMetadata;
+
+    async fn extract(opt_in_tables: TableFlags) -> ParquetTypeMap {
+        let mut extractor = ParquetFungibleAssetExtractor::new(opt_in_tables);
+        extractor
+            .process(TransactionContext {
+                data: vec![coin_transaction(1, 100), coin_transaction(2, 40)],
+                metadata: TransactionMetadata {
+                    start_version: 1,
+                    end_version: 2,
+                    ..TransactionMetadata::default()
+                },
+            })
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+    }
+
+    #[tokio::test]
+    async fn test_coin_tables_are_extracted() {
+        let map = extract(TableFlags::empty()).await;
+        assert!(map.contains_key(&ParquetTypeEnum::CoinSupply));
+        match &map[&ParquetTypeEnum::CoinInfos] {
+            ParquetTypeStructs::CoinInfo(coin_infos) => {
+                assert_eq!(coin_infos.len(), 1);
+                assert_eq!(coin_infos[0].transaction_version_created, 1);
+            },
+            other => panic!("Unexpected structs for coin_infos: {other:?}"),
+        }
+        match &map[&ParquetTypeEnum::CoinBalances] {
+            ParquetTypeStructs::CoinBalance(coin_balances) => {
+                let balances: Vec<_> = coin_balances
+                    .iter()
+                    .map(|balance| (balance.owner_address.as_str(), balance.amount.as_str()))
+                    .collect();
+                assert_eq!(balances, vec![
+                    (OWNER_ADDRESS, "100"),
+                    (OWNER_ADDRESS, "40")
+                ]);
+            },
+            other => panic!("Unexpected structs for coin_balances: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coin_tables_respect_opt_in() {
+        let map = extract(TableFlags::COIN_BALANCES).await;
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec![
+            &ParquetTypeEnum::CoinBalances
+        ]);
+    }
+}
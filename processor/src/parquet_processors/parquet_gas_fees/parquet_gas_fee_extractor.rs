@@ -0,0 +1,70 @@
+use crate::{
+    parquet_processors::{
+        parquet_utils::util::add_to_map_if_opted_in_for_backfill, ParquetTypeEnum,
+        ParquetTypeStructs,
+    },
+    processors::gas_fees::models::{GasFee, ParquetGasFee},
+    utils::table_flags::TableFlags,
+};
+use libra2_indexer_processor_sdk::{
+    libra2_protos::transaction::v1::Transaction,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Extracts parquet data from transactions, allowing optional selection of specific tables.
+pub struct ParquetGasFeeExtractor
+where
+    Self: Processable + Send + Sized + 'static,
+{
+    pub opt_in_tables: TableFlags,
+}
+
+type ParquetTypeMap = HashMap<ParquetTypeEnum, ParquetTypeStructs>;
+
+#[async_trait]
+impl Processable for ParquetGasFeeExtractor {
+    type Input = Vec<Transaction>;
+    type Output = ParquetTypeMap;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        transactions: TransactionContext<Self::Input>,
+    ) -> anyhow::Result<Option<TransactionContext<ParquetTypeMap>>, ProcessorError> {
+        let gas_fees: Vec<ParquetGasFee> = transactions
+            .data
+            .par_iter()
+            .filter_map(GasFee::from_transaction)
+            .map(ParquetGasFee::from)
+            .collect();
+
+        let mut map: HashMap<ParquetTypeEnum, ParquetTypeStructs> = HashMap::new();
+
+        let data_types = [(
+            TableFlags::GAS_FEES,
+            ParquetTypeEnum::GasFees,
+            ParquetTypeStructs::GasFee(gas_fees),
+        )];
+
+        // Populate the map based on opt-in tables
+        add_to_map_if_opted_in_for_backfill(self.opt_in_tables, &mut map, data_types.to_vec());
+
+        Ok(Some(TransactionContext {
+            data: map,
+            metadata: transactions.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for ParquetGasFeeExtractor {}
+
+impl NamedStep for ParquetGasFeeExtractor {
+    fn name(&self) -> String {
+        "ParquetGasFeeExtractor".to_string()
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    config::{indexer_processor_config::IndexerProcessorConfig, processor_config::ProcessorConfig},
+    parquet_processors::{
+        parquet_gas_fees::parquet_gas_fee_extractor::ParquetGasFeeExtractor,
+        parquet_utils::util::HasParquetSchema, run_parquet_processor, set_backfill_table_flag,
+        ParquetTypeEnum,
+    },
+    processors::gas_fees::models::ParquetGasFee,
+};
+use libra2_indexer_processor_sdk::traits::processor_trait::ProcessorTrait;
+use parquet::schema::types::Type;
+use std::{collections::HashMap, sync::Arc};
+
+pub struct ParquetGasFeeProcessor {
+    pub config: IndexerProcessorConfig,
+}
+
+impl ParquetGasFeeProcessor {
+    pub async fn new(config: IndexerProcessorConfig) -> anyhow::Result<Self> {
+        Ok(Self { config })
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for ParquetGasFeeProcessor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    async fn run_processor(&self) -> anyhow::Result<()> {
+        let parquet_processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::ParquetGasFeeProcessor(parquet_processor_config) => {
+                parquet_processor_config
+            },
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor configuration for ParquetGasFeeProcessor {:?}",
+                    self.config.processor_config
+                ));
+            },
+        };
+
+        let backfill_table =
+            set_backfill_table_flag(parquet_processor_config.backfill_table.clone());
+        let parquet_gas_fee_extractor = ParquetGasFeeExtractor {
+            opt_in_tables: backfill_table,
+        };
+
+        let parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>> =
+            [(ParquetTypeEnum::GasFees, ParquetGasFee::schema())]
+                .into_iter()
+                .collect();
+
+        run_parquet_processor(
+            &self.config,
+            parquet_gas_fee_extractor,
+            parquet_type_to_schemas,
+            &parquet_processor_config,
+        )
+        .await
+    }
+}
@@ -0,0 +1,2 @@
+pub mod parquet_nft_points_extractor;
+pub mod parquet_nft_points_processor;
@@ -0,0 +1,72 @@
+use crate::{
+    parquet_processors::{
+        parquet_utils::util::add_to_map_if_opted_in_for_backfill, ParquetTypeEnum,
+        ParquetTypeStructs,
+    },
+    processors::nft_points::nft_points_model::{NftPointsRules, ParquetNftPoints},
+    utils::table_flags::TableFlags,
+};
+use libra2_indexer_processor_sdk::{
+    libra2_protos::transaction::v1::Transaction,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Extracts parquet data from transactions, allowing optional selection of specific tables.
+pub struct ParquetNftPointsExtractor
+where
+    Self: Processable + Send + Sized + 'static,
+{
+    pub rules: NftPointsRules,
+    pub opt_in_tables: TableFlags,
+}
+
+type ParquetTypeMap = HashMap<ParquetTypeEnum, ParquetTypeStructs>;
+
+#[async_trait]
+impl Processable for ParquetNftPointsExtractor {
+    type Input = Vec<Transaction>;
+    type Output = ParquetTypeMap;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        transactions: TransactionContext<Self::Input>,
+    ) -> anyhow::Result<Option<TransactionContext<ParquetTypeMap>>, ProcessorError> {
+        let name = self.name();
+        let parquet_nft_points: Vec<ParquetNftPoints> = transactions
+            .data
+            .par_iter()
+            .flat_map(|txn| self.rules.parse_transaction(txn, name.as_str()))
+            .map(ParquetNftPoints::from)
+            .collect();
+
+        let mut map: HashMap<ParquetTypeEnum, ParquetTypeStructs> = HashMap::new();
+
+        let data_types = [(
+            TableFlags::NFT_POINTS,
+            ParquetTypeEnum::NftPoints,
+            ParquetTypeStructs::NftPoints(parquet_nft_points),
+        )];
+
+        // Populate the map based on opt-in tables
+        add_to_map_if_opted_in_for_backfill(self.opt_in_tables, &mut map, data_types.to_vec());
+
+        Ok(Some(TransactionContext {
+            data: map,
+            metadata: transactions.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for ParquetNftPointsExtractor {}
+
+impl NamedStep for ParquetNftPointsExtractor {
+    fn name(&self) -> String {
+        "ParquetNftPointsExtractor".to_string()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{
+    config::{
+        indexer_processor_config::IndexerProcessorConfig,
+        processor_config::{ParquetDefaultProcessorConfig, ProcessorConfig},
+    },
+    parquet_processors::{
+        parquet_nft_points::parquet_nft_points_extractor::ParquetNftPointsExtractor,
+        parquet_utils::util::HasParquetSchema, run_parquet_processor, set_backfill_table_flag,
+        ParquetTypeEnum,
+    },
+    processors::nft_points::nft_points_model::{
+        NftPointsRules, ParquetNftPoints, PointsEntryFunction, PointsEvent,
+    },
+};
+use libra2_indexer_processor_sdk::traits::processor_trait::ProcessorTrait;
+use parquet::schema::types::Type;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParquetNftPointsProcessorConfig {
+    #[serde(flatten)]
+    pub default: ParquetDefaultProcessorConfig,
+    pub points_contract_address: String,
+    #[serde(default)]
+    pub entry_functions: Vec<PointsEntryFunction>,
+    #[serde(default)]
+    pub events: Vec<PointsEvent>,
+}
+
+pub struct ParquetNftPointsProcessor {
+    pub config: IndexerProcessorConfig,
+}
+
+impl ParquetNftPointsProcessor {
+    pub async fn new(config: IndexerProcessorConfig) -> anyhow::Result<Self> {
+        Ok(Self { config })
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for ParquetNftPointsProcessor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    async fn run_processor(&self) -> anyhow::Result<()> {
+        let parquet_processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::ParquetNftPointsProcessor(nft_points_config) => nft_points_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor configuration for ParquetNftPointsProcessor {:?}",
+                    self.config.processor_config
+                ));
+            },
+        };
+
+        let backfill_table =
+            set_backfill_table_flag(parquet_processor_config.default.backfill_table.clone());
+        let parquet_nft_points_extractor = ParquetNftPointsExtractor {
+            rules: NftPointsRules::new(
+                &parquet_processor_config.points_contract_address,
+                &parquet_processor_config.entry_functions,
+                &parquet_processor_config.events,
+            )?,
+            opt_in_tables: backfill_table,
+        };
+
+        let parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>> =
+            [(ParquetTypeEnum::NftPoints, ParquetNftPoints::schema())]
+                .into_iter()
+                .collect();
+
+        run_parquet_processor(
+            &self.config,
+            parquet_nft_points_extractor,
+            parquet_type_to_schemas,
+            &parquet_processor_config.default,
+        )
+        .await
+    }
+}
@@ -1,4 +1,5 @@
 use super::parquet_utils::{
+    parquet_uploader::ParquetFileManifest,
     parquet_version_tracker_step::ParquetProcessorStatusSaverTrait, util::format_table_name,
 };
 use crate::{
@@ -9,8 +10,10 @@ use crate::{
     db::backfill_processor_status::{
         BackfillProcessorStatus, BackfillProcessorStatusQuery, BackfillStatus,
     },
-    processors::processor_status_saver::{log_ascii_warning, save_processor_status},
-    schema::backfill_processor_status,
+    processors::processor_status_saver::{
+        log_ascii_warning, save_processor_status, save_processor_status_with_conn,
+    },
+    schema::{backfill_processor_status, parquet_files},
 };
 use anyhow::Result;
 use libra2_indexer_processor_sdk::{
@@ -22,7 +25,8 @@ use libra2_indexer_processor_sdk::{
     utils::errors::ProcessorError,
 };
 use async_trait::async_trait;
-use diesel::{upsert::excluded, ExpressionMethods};
+use diesel::{upsert::excluded, ExpressionMethods, QueryDsl};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
 
 /// A trait implementation of ProcessorStatusSaver for Parquet.
 pub struct ParquetProcessorStatusSaver {
@@ -42,15 +46,54 @@ impl ParquetProcessorStatusSaverTrait for ParquetProcessorStatusSaver {
         &self,
         last_success_batch: &TransactionContext<()>,
         table_name: &str,
+        files: &[ParquetFileManifest],
     ) -> Result<(), ProcessorError> {
         let processor_id = format_table_name(self.config.processor_config.name(), table_name);
-        save_processor_status(
-            &processor_id,
-            self.config.processor_mode.clone(),
-            last_success_batch,
-            self.db_pool.clone(),
-        )
+        if files.is_empty() {
+            return save_processor_status(
+                &processor_id,
+                self.config.processor_mode.clone(),
+                last_success_batch,
+                self.db_pool.clone(),
+            )
+            .await;
+        }
+
+        // Commit the files in the same transaction as the checkpoint that covers them, so a file
+        // is never committed without its checkpoint or the other way around
+        let file_paths: Vec<String> = files.iter().map(|file| file.file_path.clone()).collect();
+        let processor_mode = &self.config.processor_mode;
+        let mut conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to get connection from pool: {e:?}"),
+                query: None,
+            })?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                diesel::update(
+                    parquet_files::table.filter(parquet_files::file_path.eq_any(&file_paths)),
+                )
+                .set(parquet_files::is_committed.eq(true))
+                .execute(conn)
+                .await?;
+                save_processor_status_with_conn(
+                    &processor_id,
+                    processor_mode,
+                    last_success_batch,
+                    conn,
+                )
+                .await
+            }
+            .scope_boxed()
+        })
         .await
+        .map_err(|e| ProcessorError::DBStoreError {
+            message: format!("Failed to commit parquet files of {table_name}: {e:?}"),
+            query: None,
+        })
     }
 }
 
@@ -205,7 +248,8 @@ pub async fn get_parquet_end_version(
         })?;
 
     match &config.processor_mode {
-        ProcessorMode::Default(_) => Ok(None),
+        // Normally unset. The replay command sets it to stop after the last replayed transaction.
+        ProcessorMode::Default(_) => Ok(config.transaction_stream_config.request_ending_version),
         ProcessorMode::Backfill(BackfillConfig { ending_version, .. }) => {
             match ending_version {
                 Some(ending_version) => Ok(Some(*ending_version)),
@@ -352,11 +396,17 @@ mod tests {
     use super::*;
     use crate::{
         config::{
-            db_config::{DbConfig, ParquetConfig},
+            db_config::{
+                DbConfig, ParquetConfig, ParquetPartitionScheme, ParquetStorageBackendConfig,
+            },
             indexer_processor_config::IndexerProcessorConfig,
             processor_config::{ParquetDefaultProcessorConfig, ProcessorConfig},
         },
         db::backfill_processor_status::{BackfillProcessorStatus, BackfillStatus},
+        parquet_processors::parquet_utils::{
+            local_uploader::LocalUploader, parquet_file_ledger::ParquetFileLedger,
+            parquet_uploader::Uploadable,
+        },
         MIGRATIONS,
     };
     use libra2_indexer_processor_sdk::{
@@ -368,8 +418,10 @@ mod tests {
             utils::database::{new_db_pool, run_migrations},
         },
         testing_framework::database::{PostgresTestDatabase, TestDatabase},
+        types::transaction_context::TransactionMetadata,
     };
     use diesel_async::RunQueryDsl;
+    use std::collections::HashMap;
     use url::Url;
 
     fn create_indexer_config(
@@ -384,12 +436,15 @@ mod tests {
             google_application_credentials: None,
             bucket_name: "test".to_string(),
             bucket_root: "test".to_string(),
+            storage_backend: ParquetStorageBackendConfig::default(),
+            partition_scheme: ParquetPartitionScheme::default(),
         };
         let db_config = DbConfig::ParquetConfig(postgres_config);
         IndexerProcessorConfig {
             processor_config,
             db_config,
             processor_mode,
+            additional_processor_configs: vec![],
             transaction_stream_config: TransactionStreamConfig {
                 indexer_grpc_data_service_address: Url::parse("https://test.com").unwrap(),
                 starting_version: None,
@@ -746,4 +801,82 @@ mod tests {
         assert_eq!(starting_version, Some(0));
         assert_eq!(end_version, Some(0));
     }
+
+    #[tokio::test]
+    #[allow(clippy::needless_return)]
+    async fn test_files_are_committed_with_checkpoint() {
+        let mut db = PostgresTestDatabase::new();
+        db.setup().await.unwrap();
+        let conn_pool = new_db_pool(db.get_db_url().as_str(), Some(10))
+            .await
+            .expect("Failed to create connection pool");
+        run_migrations(db.get_db_url(), conn_pool.clone(), MIGRATIONS).await;
+
+        let indexer_processor_config = create_indexer_config(
+            db.get_db_url(),
+            ProcessorMode::Default(BootStrapConfig {
+                initial_starting_version: 0,
+            }),
+        );
+        let processor_name = indexer_processor_config.processor_config.name();
+        let root_dir = tempfile::tempdir().unwrap();
+        let uploader = LocalUploader::new(root_dir.path());
+        let file_ledger = ParquetFileLedger::new(conn_pool.clone(), processor_name.to_string());
+
+        let manifest = |start_version: i64, end_version: i64| ParquetFileManifest {
+            table_name: "move_resources".to_string(),
+            partition: None,
+            processor_name: processor_name.to_string(),
+            file_path: format!("move_resources/{start_version}_{end_version}.parquet"),
+            row_count: end_version - start_version + 1,
+            start_version,
+            end_version,
+            schema_hash: "schema_hash".to_string(),
+            schema_version: 1,
+            file_size_bytes: 4,
+            compacted_from: vec![],
+        };
+        let (committed, uncommitted) = (manifest(100, 105), manifest(106, 110));
+        for file in [&committed, &uncommitted] {
+            file_ledger.record_uncommitted(file).await.unwrap();
+            uploader
+                .upload_object(&file.file_path, b"PAR1".to_vec())
+                .await
+                .unwrap();
+        }
+
+        ParquetProcessorStatusSaver::new(indexer_processor_config, conn_pool.clone())
+            .save_parquet_processor_status(
+                &TransactionContext {
+                    data: (),
+                    metadata: TransactionMetadata {
+                        start_version: 100,
+                        end_version: 105,
+                        ..TransactionMetadata::default()
+                    },
+                },
+                "move_resources",
+                std::slice::from_ref(&committed),
+            )
+            .await
+            .unwrap();
+
+        // The processor stopped before the second file was committed
+        let committed_end_versions = file_ledger.recover(&uploader).await.unwrap();
+        assert_eq!(
+            committed_end_versions,
+            HashMap::from([("move_resources".to_string(), 105)])
+        );
+        assert!(root_dir.path().join(&committed.file_path).exists());
+        assert!(!root_dir.path().join(&uncommitted.file_path).exists());
+
+        let status = ProcessorStatusQuery::get_by_processor(
+            &format_table_name(processor_name, "move_resources"),
+            &mut conn_pool.get().await.unwrap(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(status.last_success_version, 105);
+    }
 }
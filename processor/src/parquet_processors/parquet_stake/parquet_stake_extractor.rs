@@ -7,7 +7,12 @@ use crate::{
         models::{
             delegator_activities::ParquetDelegatedStakingActivity,
             delegator_balances::{ParquetCurrentDelegatorBalance, ParquetDelegatorBalance},
+            delegator_pools::{
+                ParquetCurrentDelegatorPoolBalance, ParquetDelegatorPool,
+                ParquetDelegatorPoolBalance,
+            },
             proposal_votes::ParquetProposalVote,
+            staking_pool_voter::ParquetCurrentStakingPoolVoter,
         },
         parse_stake_data,
     },
@@ -44,14 +49,16 @@ impl Processable for ParquetStakeExtractor {
         transactions: TransactionContext<Self::Input>,
     ) -> anyhow::Result<Option<TransactionContext<ParquetTypeMap>>, ProcessorError> {
         let (
-            _,
+            raw_all_current_stake_pool_voters,
             raw_all_proposal_votes,
             raw_all_delegator_activities,
             raw_all_delegator_balances,
             raw_all_current_delegator_balances,
-            _,
-            _,
-            _,
+            raw_all_delegator_pools,
+            raw_all_delegator_pool_balances,
+            raw_all_current_delegator_pool_balances,
+            // Delegated voters are resolved from the Postgres stake tables, so they can't be
+            // written as parquet
             _,
         ) = match parse_stake_data(&transactions.data, None, 0, 0).await {
             Ok(data) => data,
@@ -85,6 +92,22 @@ impl Processable for ParquetStakeExtractor {
             .into_iter()
             .map(ParquetProposalVote::from)
             .collect::<Vec<_>>();
+        let all_current_stake_pool_voters = raw_all_current_stake_pool_voters
+            .into_iter()
+            .map(ParquetCurrentStakingPoolVoter::from)
+            .collect::<Vec<_>>();
+        let all_delegator_pools = raw_all_delegator_pools
+            .into_iter()
+            .map(ParquetDelegatorPool::from)
+            .collect::<Vec<_>>();
+        let all_delegator_pool_balances = raw_all_delegator_pool_balances
+            .into_iter()
+            .map(ParquetDelegatorPoolBalance::from)
+            .collect::<Vec<_>>();
+        let all_current_delegator_pool_balances = raw_all_current_delegator_pool_balances
+            .into_iter()
+            .map(ParquetCurrentDelegatorPoolBalance::from)
+            .collect::<Vec<_>>();
 
         // Print the size of each extracted data type
         debug!("Processed data sizes:");
@@ -98,6 +121,19 @@ impl Processable for ParquetStakeExtractor {
             " - CurrentDelegatorBalance: {}",
             all_current_delegator_balances.len()
         );
+        debug!(
+            " - CurrentStakingPoolVoter: {}",
+            all_current_stake_pool_voters.len()
+        );
+        debug!(" - DelegatorPool: {}", all_delegator_pools.len());
+        debug!(
+            " - DelegatorPoolBalance: {}",
+            all_delegator_pool_balances.len()
+        );
+        debug!(
+            " - CurrentDelegatorPoolBalance: {}",
+            all_current_delegator_pool_balances.len()
+        );
 
         let mut map: HashMap<ParquetTypeEnum, ParquetTypeStructs> = HashMap::new();
 
@@ -122,6 +158,28 @@ impl Processable for ParquetStakeExtractor {
                 ParquetTypeEnum::CurrentDelegatorBalances,
                 ParquetTypeStructs::CurrentDelegatorBalance(all_current_delegator_balances),
             ),
+            (
+                TableFlags::CURRENT_STAKING_POOL_VOTER,
+                ParquetTypeEnum::CurrentStakingPoolVoter,
+                ParquetTypeStructs::CurrentStakingPoolVoter(all_current_stake_pool_voters),
+            ),
+            (
+                TableFlags::DELEGATED_STAKING_POOLS,
+                ParquetTypeEnum::DelegatedStakingPools,
+                ParquetTypeStructs::DelegatorPool(all_delegator_pools),
+            ),
+            (
+                TableFlags::DELEGATED_STAKING_POOL_BALANCES,
+                ParquetTypeEnum::DelegatedStakingPoolBalances,
+                ParquetTypeStructs::DelegatorPoolBalance(all_delegator_pool_balances),
+            ),
+            (
+                TableFlags::CURRENT_DELEGATED_STAKING_POOL_BALANCES,
+                ParquetTypeEnum::CurrentDelegatedStakingPoolBalances,
+                ParquetTypeStructs::CurrentDelegatorPoolBalance(
+                    all_current_delegator_pool_balances,
+                ),
+            ),
         ];
 
         // Populate the map based on opt-in tables
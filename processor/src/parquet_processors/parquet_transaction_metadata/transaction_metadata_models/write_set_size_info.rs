@@ -3,7 +3,7 @@
 
 #![allow(clippy::extra_unused_lifetimes)]
 
-use crate::parquet_processors::parquet_utils::util::{HasVersion, NamedTable};
+use crate::parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable};
 use allocative_derive::Allocative;
 use libra2_indexer_processor_sdk::libra2_protos::transaction::v1::WriteOpSizeInfo;
 use field_count::FieldCount;
@@ -27,6 +27,10 @@ impl NamedTable for ParquetWriteSetSize {
     const TABLE_NAME: &'static str = "write_set_size";
 }
 
+impl HasSchemaVersion for ParquetWriteSetSize {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetWriteSetSize {
     fn version(&self) -> i64 {
         self.txn_version
@@ -0,0 +1,154 @@
+use crate::parquet_processors::parquet_utils::{
+    parquet_uploader::{StoredObject, Uploadable},
+    util::ParquetProcessorError,
+};
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+/// Writes parquet files to a directory on the local filesystem. Useful for development and CI,
+/// where no object store is available.
+pub struct LocalUploader {
+    pub root_dir: PathBuf,
+}
+
+impl LocalUploader {
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Uploadable for LocalUploader {
+    /// Files are written next to their final path first and then renamed, so readers never
+    /// see a partially written parquet file.
+    async fn upload_object(
+        &self,
+        object_path: &str,
+        buffer: Vec<u8>,
+    ) -> Result<(), ParquetProcessorError> {
+        let path = self.root_dir.join(object_path);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut tmp_path = path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        tokio::fs::write(&tmp_path, buffer).await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+
+    async fn delete_object(&self, object_path: &str) -> Result<(), ParquetProcessorError> {
+        match tokio::fs::remove_file(self.root_dir.join(object_path)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<StoredObject>, ParquetProcessorError> {
+        let mut objects = vec![];
+        let mut dirs = vec![self.root_dir.join(prefix)];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let metadata = entry.metadata().await?;
+                if metadata.is_dir() {
+                    dirs.push(entry.path());
+                    continue;
+                }
+                // Files still being written are skipped
+                if entry.path().extension().is_some_and(|ext| ext == "tmp") {
+                    continue;
+                }
+                let relative_path = entry
+                    .path()
+                    .strip_prefix(&self.root_dir)
+                    .map_err(|e| ParquetProcessorError::Other(e.to_string()))?
+                    .to_string_lossy()
+                    .replace(std::path::MAIN_SEPARATOR, "/");
+                objects.push(StoredObject {
+                    object_path: relative_path,
+                    size_bytes: metadata.len(),
+                });
+            }
+        }
+        Ok(objects)
+    }
+
+    async fn download_object(&self, object_path: &str) -> Result<Vec<u8>, ParquetProcessorError> {
+        Ok(tokio::fs::read(self.root_dir.join(object_path)).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::db_config::ParquetPartitionScheme,
+        parquet_processors::{
+            parquet_utils::{
+                parquet_uploader::{create_new_writer, default_writer_properties, ParquetUploader},
+                util::HasParquetSchema,
+            },
+            ParquetTypeEnum, ParquetTypeStructs,
+        },
+        processors::default::models::move_resources::ParquetMoveResource,
+    };
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use std::{collections::HashMap, sync::Arc};
+
+    fn collect_files(dir: &std::path::Path, files: &mut Vec<PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_files(&path, files);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_uploader_writes_readable_parquet_file() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let schema = ParquetMoveResource::schema();
+        let mut parquet_uploader = ParquetUploader::new(
+            Arc::new(LocalUploader::new(root_dir.path())),
+            HashMap::from([(ParquetTypeEnum::MoveResources, schema.clone())]),
+            HashMap::from([(
+                ParquetTypeEnum::MoveResources,
+                create_new_writer(schema, default_writer_properties()).unwrap(),
+            )]),
+            HashMap::new(),
+            "bucket_root".to_string(),
+            ParquetPartitionScheme::default(),
+            "processor_name".to_string(),
+            None,
+        )
+        .unwrap();
+
+        parquet_uploader
+            .upload_buffer(ParquetTypeStructs::MoveResource(vec![
+                ParquetMoveResource::default(),
+                ParquetMoveResource::default(),
+            ]))
+            .await
+            .unwrap();
+
+        let mut files = vec![];
+        collect_files(root_dir.path(), &mut files);
+        files.retain(|path| path.extension().unwrap() == "parquet");
+        assert_eq!(files.len(), 1);
+        assert!(files[0].starts_with(root_dir.path().join("bucket_root/move_resources")));
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&files[0]).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+    }
+}
@@ -1,4 +1,10 @@
 pub mod gcs_uploader;
+pub mod local_uploader;
 pub mod parquet_buffer_step;
+pub mod parquet_compactor;
+pub mod parquet_file_ledger;
+pub mod parquet_uploader;
 pub mod parquet_version_tracker_step;
+pub mod s3_uploader;
+pub mod schema_registry;
 pub mod util;
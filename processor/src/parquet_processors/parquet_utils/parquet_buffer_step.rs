@@ -1,7 +1,9 @@
 use crate::parquet_processors::ParquetTypeTrait;
 #[allow(unused_imports)]
 use crate::{
-    parquet_processors::parquet_utils::{gcs_uploader::GCSUploader, gcs_uploader::Uploadable},
+    parquet_processors::parquet_utils::{
+        parquet_uploader::ParquetUploader, parquet_version_tracker_step::TableBatchMetadata,
+    },
     parquet_processors::{ParquetTypeEnum, ParquetTypeStructs},
 };
 use anyhow::Result;
@@ -65,31 +67,37 @@ impl ParquetBuffer {
 
 /// `ParquetBufferStep` is a step that accumulates data in buffers until they reach a specified size limit.
 ///
-/// It then uploads the buffered data to Google Cloud Storage (GCS) through an uploader.
+/// It then uploads the buffered data through a `ParquetUploader`, which writes to whichever
+/// storage backend (GCS, S3 or the local filesystem) is configured in `ParquetConfig`.
+/// With a `partition_scheme`, a buffer is split into one file per partition, and the upload
+/// only counts as done once every partition is written.
+///
+/// Rows at or below a table's `committed_end_versions` entry are dropped, since they're already in
+/// a committed file. This happens after a restart, which resumes every table from the lowest
+/// checkpoint of the processor.
 /// This step is typically used to manage large data volumes efficiently by buffering and uploading
 /// only when necessary.
-///
-///
-/// # Type Parameters
-/// - `U`: A type that implements the `Uploadable` trait, providing the uploading functionality.
 pub struct ParquetBufferStep {
     internal_buffers: HashMap<ParquetTypeEnum, ParquetBuffer>,
     pub poll_interval: Duration,
-    pub buffer_uploader: GCSUploader,
+    pub buffer_uploader: ParquetUploader,
     pub buffer_max_size: usize,
+    committed_end_versions: HashMap<ParquetTypeEnum, i64>,
 }
 
 impl ParquetBufferStep {
     pub fn new(
         poll_interval: Duration,
-        buffer_uploader: GCSUploader,
+        buffer_uploader: ParquetUploader,
         buffer_max_size: usize,
+        committed_end_versions: HashMap<ParquetTypeEnum, i64>,
     ) -> Self {
         Self {
             internal_buffers: HashMap::new(),
             poll_interval,
             buffer_uploader,
             buffer_max_size,
+            committed_end_versions,
         }
     }
 
@@ -105,15 +113,23 @@ impl ParquetBufferStep {
     /// Handles the addition of `parquet_data` to the buffer for a specified `ParquetTypeEnum`.
     ///
     /// We check the size of the buffer + the size of the incoming data before appending it.
-    /// If the sum of the two exceeds the maximum limit size, it uploads the buffer content to GCS to avoid
+    /// If the sum of the two exceeds the maximum limit size, it uploads the buffer content to avoid
     /// spliting the batch data, allowing for more efficient and simpler version tracking.
     async fn upload_buffer_append(
         &mut self,
         parquet_type: ParquetTypeEnum,
-        parquet_data: ParquetTypeStructs,
+        mut parquet_data: ParquetTypeStructs,
         cur_batch_metadata: &TransactionMetadata,
-        upload_metadata_map: &mut HashMap<ParquetTypeEnum, TransactionMetadata>,
+        upload_metadata_map: &mut HashMap<ParquetTypeEnum, TableBatchMetadata>,
     ) -> Result<(), ProcessorError> {
+        if let Some(&committed_end_version) = self.committed_end_versions.get(&parquet_type) {
+            if cur_batch_metadata.start_version as i64 > committed_end_version {
+                self.committed_end_versions.remove(&parquet_type);
+            } else {
+                parquet_data.retain_after_version(committed_end_version);
+            }
+        }
+
         // Get or initialize the buffer for the specific ParquetTypeEnum
         let buffer = self
             .internal_buffers
@@ -145,11 +161,13 @@ impl ParquetBufferStep {
                 &mut buffer.buffer,
                 ParquetTypeStructs::default_for_type(&parquet_type),
             );
-            self.buffer_uploader.upload_buffer(struct_buffer).await?;
+            let files = self.buffer_uploader.upload_buffer(struct_buffer).await?;
 
             // update this metadata before insert
-            upload_metadata_map
-                .insert(parquet_type, buffer.current_batch_metadata.clone().unwrap());
+            upload_metadata_map.insert(parquet_type, TableBatchMetadata {
+                metadata: buffer.current_batch_metadata.clone().unwrap(),
+                files,
+            });
             buffer.buffer_size_bytes = 0;
             buffer.current_batch_metadata = None;
         }
@@ -169,7 +187,7 @@ impl ParquetBufferStep {
 #[async_trait]
 impl Processable for ParquetBufferStep {
     type Input = HashMap<ParquetTypeEnum, ParquetTypeStructs>;
-    type Output = HashMap<ParquetTypeEnum, TransactionMetadata>;
+    type Output = HashMap<ParquetTypeEnum, TableBatchMetadata>;
     type RunType = PollableAsyncRunType;
 
     /// Processes incoming `TransactionContext` data by appending it to the appropriate buffers.
@@ -214,11 +232,14 @@ impl Processable for ParquetBufferStep {
                     ParquetTypeStructs::default_for_type(&parquet_type),
                 );
 
-                self.buffer_uploader.upload_buffer(struct_buffer).await?;
+                let files = self.buffer_uploader.upload_buffer(struct_buffer).await?;
 
                 if let Some(buffer_metadata) = &mut buffer.current_batch_metadata {
                     buffer_metadata.total_size_in_bytes = buffer.buffer_size_bytes as u64;
-                    metadata_map.insert(parquet_type, buffer_metadata.clone());
+                    metadata_map.insert(parquet_type, TableBatchMetadata {
+                        metadata: buffer_metadata.clone(),
+                        files,
+                    });
                 } else {
                     // This should never happen
                     panic!("Buffer metadata is missing for ParquetTypeEnum: {parquet_type:?}");
@@ -259,10 +280,10 @@ impl PollableAsyncStep for ParquetBufferStep {
                     ParquetTypeStructs::default_for_type(&parquet_type),
                 );
 
-                self.buffer_uploader.upload_buffer(struct_buffer).await?;
+                let files = self.buffer_uploader.upload_buffer(struct_buffer).await?;
 
                 let metadata = buffer.current_batch_metadata.clone().unwrap();
-                metadata_map.insert(parquet_type, metadata);
+                metadata_map.insert(parquet_type, TableBatchMetadata { metadata, files });
 
                 buffer.buffer_size_bytes = 0;
                 buffer.current_batch_metadata = None;
@@ -287,10 +308,13 @@ impl NamedStep for ParquetBufferStep {
 #[cfg(test)]
 mod tests {
     use crate::{
-        config::db_config::ParquetConfig,
+        config::db_config::{
+            LocalStorageConfig, ParquetConfig, ParquetPartitionScheme, ParquetStorageBackendConfig,
+        },
         parquet_processors::parquet_utils::{
-            gcs_uploader::{create_new_writer, GCSUploader},
+            local_uploader::LocalUploader,
             parquet_buffer_step::{ParquetBufferStep, ParquetTypeEnum, ParquetTypeStructs},
+            parquet_uploader::{create_new_writer, default_writer_properties, ParquetUploader},
             util::HasParquetSchema,
         },
         processors::default::models::move_resources::ParquetMoveResource,
@@ -299,7 +323,6 @@ mod tests {
         traits::Processable,
         types::transaction_context::{TransactionContext, TransactionMetadata},
     };
-    use google_cloud_storage::client::{Client as GCSClient, ClientConfig as GcsClientConfig};
     use parquet::schema::types::Type;
     use std::{collections::HashMap, sync::Arc, time::Duration};
 
@@ -308,8 +331,12 @@ mod tests {
     async fn test_parquet_buffer_step_no_upload() -> anyhow::Result<()> {
         let db_config = create_parquet_db_config();
         let buffer_uploader = create_parquet_uploader(&db_config).await?;
-        let mut parquet_step =
-            ParquetBufferStep::new(Duration::from_secs(10), buffer_uploader, 100);
+        let mut parquet_step = ParquetBufferStep::new(
+            Duration::from_secs(10),
+            buffer_uploader,
+            100,
+            HashMap::new(),
+        );
 
         let data = HashMap::from([(
             ParquetTypeEnum::MoveResources,
@@ -337,8 +364,12 @@ mod tests {
         let db_config = create_parquet_db_config();
 
         let buffer_uploader = create_parquet_uploader(&db_config).await?;
-        let mut parquet_step =
-            ParquetBufferStep::new(Duration::from_secs(10), buffer_uploader, buffer_max_size);
+        let mut parquet_step = ParquetBufferStep::new(
+            Duration::from_secs(10),
+            buffer_uploader,
+            buffer_max_size,
+            HashMap::new(),
+        );
 
         // Test data below `buffer_max_size`
         let data = HashMap::from([(
@@ -376,12 +407,53 @@ mod tests {
         Ok(())
     }
 
-    async fn create_parquet_uploader(db_config: &ParquetConfig) -> anyhow::Result<GCSUploader> {
-        let gcs_config = GcsClientConfig::default()
-            .with_auth()
+    #[tokio::test]
+    async fn test_parquet_buffer_step_skips_committed_versions() -> anyhow::Result<()> {
+        let db_config = create_parquet_db_config();
+        let buffer_uploader = create_parquet_uploader(&db_config).await?;
+        let mut parquet_step = ParquetBufferStep::new(
+            Duration::from_secs(10),
+            buffer_uploader,
+            usize::MAX,
+            HashMap::from([(ParquetTypeEnum::MoveResources, 101)]),
+        );
+
+        let rows = (100..103)
+            .map(|txn_version| ParquetMoveResource {
+                txn_version,
+                ..Default::default()
+            })
+            .collect();
+        let data = HashMap::from([(
+            ParquetTypeEnum::MoveResources,
+            ParquetTypeStructs::MoveResource(rows),
+        )]);
+        let metadata = TransactionMetadata {
+            start_version: 100,
+            end_version: 102,
+            ..Default::default()
+        };
+        parquet_step
+            .process(TransactionContext { data, metadata })
             .await
-            .expect("Failed to create GCS client config");
-        let gcs_client = Arc::new(GCSClient::new(gcs_config));
+            .unwrap();
+
+        let result = parquet_step.cleanup().await.unwrap().unwrap();
+        let table_batch = &result[0].data[&ParquetTypeEnum::MoveResources];
+        assert_eq!(table_batch.metadata.start_version, 100);
+        assert_eq!(table_batch.metadata.end_version, 102);
+        assert_eq!(table_batch.files.len(), 1);
+        assert_eq!(table_batch.files[0].start_version, 102);
+        assert_eq!(table_batch.files[0].row_count, 1);
+
+        Ok(())
+    }
+
+    async fn create_parquet_uploader(db_config: &ParquetConfig) -> anyhow::Result<ParquetUploader> {
+        let root_dir = match &db_config.storage_backend {
+            ParquetStorageBackendConfig::Local(local_config) => local_config.root_dir.clone(),
+            _ => unreachable!("Tests only use the local storage backend"),
+        };
 
         let parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>> = [(
             ParquetTypeEnum::MoveResources,
@@ -393,18 +465,21 @@ mod tests {
         let parquet_type_to_writer = parquet_type_to_schemas
             .iter()
             .map(|(key, schema)| {
-                let writer = create_new_writer(schema.clone()).expect("Failed to create writer");
+                let writer = create_new_writer(schema.clone(), default_writer_properties())
+                    .expect("Failed to create writer");
                 (*key, writer)
             })
             .collect();
 
-        GCSUploader::new(
-            gcs_client,
+        ParquetUploader::new(
+            Arc::new(LocalUploader::new(root_dir)),
             parquet_type_to_schemas,
             parquet_type_to_writer,
-            db_config.bucket_name.clone(),
+            HashMap::new(),
             db_config.bucket_root.clone(),
+            db_config.partition_scheme.clone(),
             "processor_name".to_string(),
+            None,
         )
     }
 
@@ -415,6 +490,13 @@ mod tests {
             bucket_name: "bucket_name".to_string(),
             bucket_root: "bucket_root".to_string(),
             google_application_credentials: None,
+            storage_backend: ParquetStorageBackendConfig::Local(LocalStorageConfig {
+                root_dir: std::env::temp_dir()
+                    .join("parquet_buffer_step_test")
+                    .to_string_lossy()
+                    .to_string(),
+            }),
+            partition_scheme: ParquetPartitionScheme::default(),
         }
     }
 }
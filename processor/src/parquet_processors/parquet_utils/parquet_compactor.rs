@@ -0,0 +1,544 @@
+use crate::parquet_processors::{
+    parquet_utils::{
+        parquet_file_ledger::ParquetFileLedger,
+        parquet_uploader::{
+            create_new_writer, default_writer_properties, schema_version_metadata,
+            upload_parquet_file, ParquetFileManifest, Uploadable, MANIFEST_EXTENSION,
+        },
+        util::schema_hash,
+    },
+    ParquetTypeEnum, ParquetTypeStructs, ParquetTypeTrait,
+};
+use anyhow::Context;
+use bytes::Bytes;
+use parquet::{
+    column::writer::ColumnCloseResult,
+    file::{
+        properties::ReaderProperties,
+        reader::{FileReader, SerializedFileReader},
+        serialized_reader::ReadOptionsBuilder,
+    },
+    schema::types::Type,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+/// The files of a table that `ParquetCompactor::compact` merges or cleans up.
+#[derive(Clone, Debug)]
+pub struct CompactionPlan {
+    pub parquet_type: ParquetTypeEnum,
+    /// Compacted files whose sources weren't all deleted, because the compaction that wrote them
+    /// was interrupted. Each is listed with its remaining sources.
+    pub unfinished: Vec<(ParquetFileManifest, Vec<String>)>,
+    /// Files that are merged into one, in version order.
+    pub groups: Vec<Vec<ParquetFileManifest>>,
+}
+
+/// Merges the small files a parquet processor uploads into files of about
+/// `target_file_size_bytes`.
+///
+/// Files are merged by copying their row groups as is, so nothing is decoded or re-compressed.
+/// Column chunk statistics and bloom filters are copied with them, but page indexes aren't, so a
+/// merged file has no page level statistics even if the table is configured to write them.
+/// Only files in the same directory (partition) with the current schema of the table and
+/// non-overlapping version ranges are merged. Files that aren't committed in the ledger yet are
+/// left alone, the processor may still replace them.
+///
+/// A merged file is uploaded before its manifest, which lists the files it was merged from in
+/// `compacted_from`. Readers that only consider files with a manifest, and skip the files listed
+/// in `compacted_from` of another manifest, never see a version twice or a partial file. The
+/// sources are deleted last, so an interrupted compaction is finished by the next one.
+pub struct ParquetCompactor {
+    uploader: Arc<dyn Uploadable>,
+    bucket_root: PathBuf,
+    // `None` when the processor doesn't track its files in the ledger
+    file_ledger: Option<ParquetFileLedger>,
+    target_file_size_bytes: u64,
+}
+
+impl ParquetCompactor {
+    pub fn new(
+        uploader: Arc<dyn Uploadable>,
+        bucket_root: impl Into<PathBuf>,
+        file_ledger: Option<ParquetFileLedger>,
+        target_file_size_bytes: u64,
+    ) -> Self {
+        Self {
+            uploader,
+            bucket_root: bucket_root.into(),
+            file_ledger,
+            target_file_size_bytes,
+        }
+    }
+
+    /// Lists the files of the table and decides which ones to merge, without changing anything.
+    pub async fn plan(&self, parquet_type: ParquetTypeEnum) -> anyhow::Result<CompactionPlan> {
+        let table_name = parquet_type.to_string();
+        let expected_schema_hash = schema_hash(&table_schema(parquet_type));
+
+        // Unpartitioned and partitioned files of the table
+        let mut objects = vec![];
+        for table_dir in [table_name.clone(), format!("table={table_name}")] {
+            let prefix = self.bucket_root.join(table_dir);
+            objects.extend(self.uploader.list_objects(prefix.to_str().unwrap()).await?);
+        }
+        // Read the ledger after listing, so every listed file that isn't committed yet is in it
+        let uncommitted_file_paths = match &self.file_ledger {
+            Some(file_ledger) => file_ledger
+                .uncommitted_file_paths()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read the parquet file ledger: {e:?}"))?,
+            None => HashSet::new(),
+        };
+        let object_paths: HashSet<&str> = objects
+            .iter()
+            .map(|object| object.object_path.as_str())
+            .collect();
+
+        let mut files_by_directory: BTreeMap<String, Vec<ParquetFileManifest>> = BTreeMap::new();
+        for object in &objects {
+            if !object.object_path.ends_with(".parquet")
+                || uncommitted_file_paths.contains(&object.object_path)
+            {
+                continue;
+            }
+            let manifest_path = manifest_path(&object.object_path);
+            if !object_paths.contains(manifest_path.as_str()) {
+                warn!(
+                    file_path = object.object_path,
+                    "Skipping parquet file without a manifest"
+                );
+                continue;
+            }
+            let mut manifest: ParquetFileManifest =
+                serde_json::from_slice(&self.uploader.download_object(&manifest_path).await?)
+                    .with_context(|| format!("Failed to parse manifest {manifest_path}"))?;
+            manifest.file_path = object.object_path.clone();
+            manifest.file_size_bytes = object.size_bytes as i64;
+
+            let directory = Path::new(&object.object_path)
+                .parent()
+                .and_then(Path::to_str)
+                .unwrap_or_default()
+                .to_string();
+            files_by_directory
+                .entry(directory)
+                .or_default()
+                .push(manifest);
+        }
+
+        let mut plan = CompactionPlan {
+            parquet_type,
+            unfinished: vec![],
+            groups: vec![],
+        };
+        for (_, mut files) in files_by_directory {
+            let mut compacted_file_paths = HashSet::new();
+            for file in &files {
+                let leftover_sources: Vec<String> = file
+                    .compacted_from
+                    .iter()
+                    .filter(|source| object_paths.contains(source.as_str()))
+                    .cloned()
+                    .collect();
+                if !leftover_sources.is_empty() {
+                    plan.unfinished.push((file.clone(), leftover_sources));
+                }
+                compacted_file_paths.extend(file.compacted_from.iter().cloned());
+            }
+
+            files.retain(|file| {
+                if file.schema_hash != expected_schema_hash {
+                    warn!(
+                        file_path = file.file_path,
+                        "Skipping parquet file written with a different schema"
+                    );
+                    return false;
+                }
+                !compacted_file_paths.contains(&file.file_path)
+            });
+            files.sort_by_key(|file| (file.start_version, file.end_version));
+
+            let mut group: Vec<ParquetFileManifest> = vec![];
+            let mut group_size_bytes = 0;
+            for file in files {
+                let overlaps = group
+                    .last()
+                    .is_some_and(|last| file.start_version <= last.end_version);
+                let file_size_bytes = file.file_size_bytes as u64;
+                if overlaps || group_size_bytes + file_size_bytes > self.target_file_size_bytes {
+                    push_group(&mut plan.groups, std::mem::take(&mut group));
+                    group_size_bytes = 0;
+                }
+                group_size_bytes += file_size_bytes;
+                group.push(file);
+            }
+            push_group(&mut plan.groups, group);
+        }
+        Ok(plan)
+    }
+
+    /// Finishes the unfinished compactions of the plan and merges its groups. Returns the
+    /// manifests of the merged files.
+    pub async fn compact(&self, plan: &CompactionPlan) -> anyhow::Result<Vec<ParquetFileManifest>> {
+        for (compacted_file, leftover_sources) in &plan.unfinished {
+            self.replace_sources(compacted_file, leftover_sources)
+                .await?;
+        }
+
+        let schema = table_schema(plan.parquet_type);
+        // Every file in a group has the current schema of the table, including files written
+        // before schema versions were recorded
+        let schema_version =
+            ParquetTypeStructs::default_for_type(&plan.parquet_type).schema_version();
+        let mut compacted_files = vec![];
+        for group in &plan.groups {
+            let (first, last) = (&group[0], &group[group.len() - 1]);
+            let buffer = self.merge(group, schema.clone(), schema_version).await?;
+            let manifest = ParquetFileManifest {
+                table_name: first.table_name.clone(),
+                partition: first.partition.clone(),
+                processor_name: first.processor_name.clone(),
+                file_path: String::new(),
+                row_count: group.iter().map(|file| file.row_count).sum(),
+                start_version: first.start_version,
+                end_version: last.end_version,
+                schema_hash: first.schema_hash.clone(),
+                schema_version,
+                file_size_bytes: buffer.len() as i64,
+                compacted_from: group.iter().map(|file| file.file_path.clone()).collect(),
+            };
+            let manifest =
+                upload_parquet_file(self.uploader.as_ref(), buffer, &self.bucket_root, manifest)
+                    .await?;
+            self.replace_sources(&manifest, &manifest.compacted_from)
+                .await?;
+            compacted_files.push(manifest);
+        }
+        Ok(compacted_files)
+    }
+
+    /// Concatenates the row groups of `files` into a new parquet file, with their bloom filters.
+    async fn merge(
+        &self,
+        files: &[ParquetFileManifest],
+        schema: Arc<Type>,
+        schema_version: u32,
+    ) -> anyhow::Result<Vec<u8>> {
+        let expected_schema_hash = schema_hash(&schema);
+        let mut writer = create_new_writer(schema, default_writer_properties())?;
+        for file in files {
+            let buffer = Bytes::from(self.uploader.download_object(&file.file_path).await?);
+            let options = ReadOptionsBuilder::new()
+                .with_reader_properties(
+                    ReaderProperties::builder()
+                        .set_read_bloom_filter(true)
+                        .build(),
+                )
+                .build();
+            let reader = SerializedFileReader::new_with_options(buffer.clone(), options)
+                .with_context(|| format!("Failed to read {}", file.file_path))?;
+            let metadata = reader.metadata();
+            // The manifest could be out of date, check the schema the file was written with
+            anyhow::ensure!(
+                schema_hash(metadata.file_metadata().schema()) == expected_schema_hash,
+                "{} doesn't have the schema of its manifest",
+                file.file_path
+            );
+
+            for (i, row_group) in metadata.row_groups().iter().enumerate() {
+                let row_group_reader = reader
+                    .get_row_group(i)
+                    .with_context(|| format!("Failed to read a row group of {}", file.file_path))?;
+                let mut row_group_writer =
+                    writer.next_row_group().context("Failed to get row group")?;
+                for (j, column) in row_group.columns().iter().enumerate() {
+                    row_group_writer
+                        .append_column(&buffer, ColumnCloseResult {
+                            bytes_written: column.compressed_size() as _,
+                            rows_written: row_group.num_rows() as _,
+                            metadata: column.clone(),
+                            bloom_filter: row_group_reader.get_column_bloom_filter(j).cloned(),
+                            // Page indexes can't be copied, the reader doesn't expose them in
+                            // the form the writer takes
+                            column_index: None,
+                            offset_index: None,
+                        })
+                        .with_context(|| {
+                            format!("Failed to copy a column of {}", file.file_path)
+                        })?;
+                }
+                row_group_writer
+                    .close()
+                    .context("Failed to close row group")?;
+            }
+        }
+        writer.append_key_value_metadata(schema_version_metadata(schema_version));
+        writer.into_inner().context("Failed to get inner buffer")
+    }
+
+    /// Moves the ledger over to the compacted file and deletes its sources from the bucket.
+    async fn replace_sources(
+        &self,
+        compacted_file: &ParquetFileManifest,
+        source_file_paths: &[String],
+    ) -> anyhow::Result<()> {
+        if let Some(file_ledger) = &self.file_ledger {
+            file_ledger
+                .replace_files(compacted_file, source_file_paths)
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to record {}: {e:?}", compacted_file.file_path)
+                })?;
+        }
+        // The manifest goes first, so a source is never visible without its parquet file
+        for source_file_path in source_file_paths {
+            self.uploader
+                .delete_object(&manifest_path(source_file_path))
+                .await?;
+            self.uploader.delete_object(source_file_path).await?;
+        }
+        info!(
+            table_name = compacted_file.table_name,
+            file_path = compacted_file.file_path,
+            start_version = compacted_file.start_version,
+            end_version = compacted_file.end_version,
+            source_files = source_file_paths.len(),
+            "Compacted parquet files",
+        );
+        Ok(())
+    }
+}
+
+fn table_schema(parquet_type: ParquetTypeEnum) -> Arc<Type> {
+    ParquetTypeStructs::default_for_type(&parquet_type).schema()
+}
+
+fn manifest_path(file_path: &str) -> String {
+    Path::new(file_path)
+        .with_extension(MANIFEST_EXTENSION)
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+/// Only groups of several files are worth merging.
+fn push_group(groups: &mut Vec<Vec<ParquetFileManifest>>, group: Vec<ParquetFileManifest>) {
+    if group.len() > 1 {
+        groups.push(group);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{db_config::ParquetPartitionScheme, processor_config::ParquetWriterConfig},
+        parquet_processors::parquet_utils::{
+            local_uploader::LocalUploader,
+            parquet_uploader::{writer_properties, ParquetUploader},
+            util::HasParquetSchema,
+        },
+        processors::default::models::move_resources::ParquetMoveResource,
+    };
+    use parquet::{file::properties::WriterProperties, record::Field};
+    use std::collections::HashMap;
+
+    async fn upload_move_resources(uploader: Arc<dyn Uploadable>, versions: &[(i64, i64)]) {
+        upload_move_resources_with(uploader, versions, default_writer_properties()).await
+    }
+
+    async fn upload_move_resources_with(
+        uploader: Arc<dyn Uploadable>,
+        versions: &[(i64, i64)],
+        props: Arc<WriterProperties>,
+    ) {
+        let schema = ParquetMoveResource::schema();
+        let mut parquet_uploader = ParquetUploader::new(
+            uploader,
+            HashMap::from([(ParquetTypeEnum::MoveResources, schema.clone())]),
+            HashMap::from([(
+                ParquetTypeEnum::MoveResources,
+                create_new_writer(schema, props.clone()).unwrap(),
+            )]),
+            HashMap::from([(ParquetTypeEnum::MoveResources, props)]),
+            "bucket_root".to_string(),
+            ParquetPartitionScheme::default(),
+            "processor_name".to_string(),
+            None,
+        )
+        .unwrap();
+        for (start_version, end_version) in versions {
+            parquet_uploader
+                .upload_buffer(ParquetTypeStructs::MoveResource(
+                    (*start_version..=*end_version)
+                        .map(|txn_version| ParquetMoveResource {
+                            txn_version,
+                            ..Default::default()
+                        })
+                        .collect(),
+                ))
+                .await
+                .unwrap();
+        }
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut file_names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        file_names.sort();
+        file_names
+    }
+
+    #[tokio::test]
+    async fn test_small_files_are_merged_in_version_order() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let uploader: Arc<dyn Uploadable> = Arc::new(LocalUploader::new(root_dir.path()));
+        upload_move_resources(uploader.clone(), &[(104, 105), (100, 101), (102, 103)]).await;
+
+        let compactor = ParquetCompactor::new(uploader, "bucket_root", None, 1024 * 1024);
+        let plan = compactor
+            .plan(ParquetTypeEnum::MoveResources)
+            .await
+            .unwrap();
+        assert!(plan.unfinished.is_empty());
+        assert_eq!(plan.groups.len(), 1);
+        let compacted_files = compactor.compact(&plan).await.unwrap();
+
+        let table_dir = root_dir.path().join("bucket_root/move_resources/v1");
+        assert_eq!(file_names(&table_dir), vec![
+            "100_105.manifest.json".to_string(),
+            "100_105.parquet".to_string(),
+        ]);
+        assert_eq!(compacted_files.len(), 1);
+        assert_eq!(compacted_files[0].row_count, 6);
+        assert_eq!(compacted_files[0].compacted_from, vec![
+            "bucket_root/move_resources/v1/100_101.parquet".to_string(),
+            "bucket_root/move_resources/v1/102_103.parquet".to_string(),
+            "bucket_root/move_resources/v1/104_105.parquet".to_string(),
+        ]);
+
+        let reader = SerializedFileReader::new(
+            std::fs::File::open(table_dir.join("100_105.parquet")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 3);
+        let versions: Vec<i64> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| {
+                row.unwrap()
+                    .get_column_iter()
+                    .find_map(|(name, field)| match (name.as_str(), field) {
+                        ("txn_version", Field::Long(version)) => Some(*version),
+                        _ => None,
+                    })
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(versions, (100..=105).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filters_are_kept() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let uploader: Arc<dyn Uploadable> = Arc::new(LocalUploader::new(root_dir.path()));
+        let props = Arc::new(
+            writer_properties(&ParquetWriterConfig {
+                bloom_filter_columns: vec!["resource_address".to_string()],
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        upload_move_resources_with(uploader.clone(), &[(100, 101), (102, 103)], props).await;
+
+        let compactor = ParquetCompactor::new(uploader, "bucket_root", None, 1024 * 1024);
+        let plan = compactor
+            .plan(ParquetTypeEnum::MoveResources)
+            .await
+            .unwrap();
+        compactor.compact(&plan).await.unwrap();
+
+        let file = std::fs::File::open(
+            root_dir
+                .path()
+                .join("bucket_root/move_resources/v1/100_103.parquet"),
+        )
+        .unwrap();
+        let options = ReadOptionsBuilder::new()
+            .with_reader_properties(
+                ReaderProperties::builder()
+                    .set_read_bloom_filter(true)
+                    .build(),
+            )
+            .build();
+        let reader = SerializedFileReader::new_with_options(file, options).unwrap();
+        let column = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .position(|column| column.name() == "resource_address")
+            .unwrap();
+        assert_eq!(reader.num_row_groups(), 2);
+        for i in 0..reader.num_row_groups() {
+            let row_group = reader.get_row_group(i).unwrap();
+            assert!(row_group.get_column_bloom_filter(column).is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_compaction_is_finished() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let uploader: Arc<dyn Uploadable> = Arc::new(LocalUploader::new(root_dir.path()));
+        upload_move_resources(uploader.clone(), &[(100, 101), (102, 103)]).await;
+        let compactor = ParquetCompactor::new(uploader.clone(), "bucket_root", None, 1024 * 1024);
+        let plan = compactor
+            .plan(ParquetTypeEnum::MoveResources)
+            .await
+            .unwrap();
+        compactor.compact(&plan).await.unwrap();
+
+        // A source that wasn't deleted before the previous run stopped
+        upload_move_resources(uploader, &[(100, 101)]).await;
+        let plan = compactor
+            .plan(ParquetTypeEnum::MoveResources)
+            .await
+            .unwrap();
+        assert!(plan.groups.is_empty());
+        assert_eq!(plan.unfinished.len(), 1);
+        assert_eq!(plan.unfinished[0].1, vec![
+            "bucket_root/move_resources/v1/100_101.parquet".to_string()
+        ]);
+        compactor.compact(&plan).await.unwrap();
+
+        assert_eq!(
+            file_names(&root_dir.path().join("bucket_root/move_resources/v1")),
+            vec![
+                "100_103.manifest.json".to_string(),
+                "100_103.parquet".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_files_over_target_size_are_not_merged() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let uploader: Arc<dyn Uploadable> = Arc::new(LocalUploader::new(root_dir.path()));
+        upload_move_resources(uploader.clone(), &[(100, 101), (102, 103)]).await;
+
+        let compactor = ParquetCompactor::new(uploader, "bucket_root", None, 1);
+        let plan = compactor
+            .plan(ParquetTypeEnum::MoveResources)
+            .await
+            .unwrap();
+        assert!(plan.groups.is_empty());
+    }
+}
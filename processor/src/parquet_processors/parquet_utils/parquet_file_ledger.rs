@@ -0,0 +1,168 @@
+use crate::{
+    db::parquet_files::{ParquetFile, ParquetFileQuery},
+    parquet_processors::parquet_utils::parquet_uploader::{
+        ParquetFileManifest, Uploadable, MANIFEST_EXTENSION,
+    },
+    schema::parquet_files,
+};
+use libra2_indexer_processor_sdk::{
+    postgres::utils::database::{execute_with_better_error, ArcDbPool, DbPoolConnection},
+    utils::errors::ProcessorError,
+};
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+use tracing::info;
+
+/// Tracks the files a parquet processor uploads in the `parquet_files` table.
+///
+/// A file is recorded uncommitted right before it's uploaded, and `ParquetProcessorStatusSaver`
+/// commits it in the same transaction that moves the table's checkpoint past it. So every file
+/// in the bucket is either committed and covered by the checkpoint, or uncommitted and about to
+/// be processed again.
+#[derive(Clone)]
+pub struct ParquetFileLedger {
+    db_pool: ArcDbPool,
+    processor_name: String,
+}
+
+impl ParquetFileLedger {
+    pub fn new(db_pool: ArcDbPool, processor_name: String) -> Self {
+        Self {
+            db_pool,
+            processor_name,
+        }
+    }
+
+    pub async fn record_uncommitted(
+        &self,
+        manifest: &ParquetFileManifest,
+    ) -> Result<(), ProcessorError> {
+        // A committed file at the same path keeps its row, the upload replaces it with the same
+        // version range
+        execute_with_better_error(
+            self.db_pool.clone(),
+            diesel::insert_into(parquet_files::table)
+                .values(ParquetFile::from_manifest(manifest, false))
+                .on_conflict(parquet_files::file_path)
+                .do_nothing(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Deletes the uncommitted files of the previous run from the bucket, since their versions are
+    /// processed again from the checkpoint, and returns the last committed version of each table.
+    pub async fn recover(
+        &self,
+        uploader: &dyn Uploadable,
+    ) -> Result<HashMap<String, i64>, ProcessorError> {
+        let mut conn = self.get_conn().await?;
+
+        let uncommitted_files = ParquetFileQuery::get_uncommitted(&self.processor_name, &mut conn)
+            .await
+            .map_err(|e| ProcessorError::ProcessError {
+                message: format!("Failed to query parquet_files table. {e:?}"),
+            })?;
+        for file in &uncommitted_files {
+            let manifest_path = Path::new(&file.file_path).with_extension(MANIFEST_EXTENSION);
+            for object_path in [manifest_path.to_str().unwrap(), file.file_path.as_str()] {
+                uploader.delete_object(object_path).await.map_err(|e| {
+                    ProcessorError::ProcessError {
+                        message: format!("Failed to delete uncommitted file {object_path}: {e}"),
+                    }
+                })?;
+            }
+            info!(
+                table_name = file.table_name,
+                file_path = file.file_path,
+                start_version = file.start_version,
+                end_version = file.end_version,
+                "Deleted uncommitted parquet file",
+            );
+        }
+        let file_paths: Vec<String> = uncommitted_files
+            .into_iter()
+            .map(|file| file.file_path)
+            .collect();
+        ParquetFileQuery::delete_by_file_paths(&file_paths, &mut conn)
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to delete uncommitted parquet files: {e:?}"),
+                query: None,
+            })?;
+
+        let end_versions =
+            ParquetFileQuery::get_committed_end_versions(&self.processor_name, &mut conn)
+                .await
+                .map_err(|e| ProcessorError::ProcessError {
+                    message: format!("Failed to query parquet_files table. {e:?}"),
+                })?;
+        Ok(end_versions
+            .into_iter()
+            .filter_map(|(table_name, end_version)| Some((table_name, end_version?)))
+            .collect())
+    }
+
+    /// Paths of the files that are uploaded but not covered by a checkpoint yet.
+    pub async fn uncommitted_file_paths(&self) -> Result<HashSet<String>, ProcessorError> {
+        let mut conn = self.get_conn().await?;
+        let uncommitted_files = ParquetFileQuery::get_uncommitted(&self.processor_name, &mut conn)
+            .await
+            .map_err(|e| ProcessorError::ProcessError {
+                message: format!("Failed to query parquet_files table. {e:?}"),
+            })?;
+        Ok(uncommitted_files
+            .into_iter()
+            .map(|file| file.file_path)
+            .collect())
+    }
+
+    /// Replaces the rows of `source_file_paths` with a committed row for the file they were
+    /// compacted into. Safe to repeat when a compaction is resumed.
+    pub async fn replace_files(
+        &self,
+        compacted_file: &ParquetFileManifest,
+        source_file_paths: &[String],
+    ) -> Result<(), ProcessorError> {
+        let compacted_file = ParquetFile::from_manifest(compacted_file, true);
+        let mut conn = self.get_conn().await?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                diesel::insert_into(parquet_files::table)
+                    .values(&compacted_file)
+                    .on_conflict(parquet_files::file_path)
+                    .do_nothing()
+                    .execute(conn)
+                    .await?;
+                diesel::delete(
+                    parquet_files::table
+                        .filter(parquet_files::file_path.eq_any(source_file_paths))
+                        .filter(parquet_files::file_path.ne(&compacted_file.file_path)),
+                )
+                .execute(conn)
+                .await?;
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+        .map_err(|e| ProcessorError::DBStoreError {
+            message: format!("Failed to replace compacted parquet files: {e:?}"),
+            query: None,
+        })
+    }
+
+    async fn get_conn(&self) -> Result<DbPoolConnection<'_>, ProcessorError> {
+        self.db_pool
+            .get()
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to get connection from pool: {e:?}"),
+                query: None,
+            })
+    }
+}
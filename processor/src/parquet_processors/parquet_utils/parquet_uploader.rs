@@ -0,0 +1,720 @@
+use crate::{
+    config::{
+        db_config::ParquetPartitionScheme,
+        processor_config::{ParquetCompression, ParquetStatisticsLevel, ParquetWriterConfig},
+    },
+    parquet_processors::{
+        parquet_utils::{
+            parquet_file_ledger::ParquetFileLedger,
+            util::{schema_hash, HasParquetSchema, HasVersion, ParquetProcessorError},
+        },
+        ParquetTypeEnum, ParquetTypeStructs, ParquetTypeTrait,
+    },
+    utils::counters::PARQUET_BUFFER_SIZE,
+};
+use anyhow::Context;
+use libra2_indexer_processor_sdk::utils::errors::ProcessorError;
+use async_trait::async_trait;
+use parquet::{
+    basic::{Compression, ZstdLevel},
+    file::{
+        properties::{EnabledStatistics, WriterProperties},
+        writer::SerializedFileWriter,
+    },
+    format::KeyValue,
+    record::RecordWriter,
+    schema::types::{ColumnPath, Type},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::time::{sleep, timeout, Duration};
+use tracing::{debug, error, info};
+
+const MAX_RETRIES: usize = 3;
+const INITIAL_DELAY_MS: u64 = 500;
+const TIMEOUT_SECONDS: u64 = 300;
+/// Extension of the sidecar manifest uploaded next to every parquet file.
+pub const MANIFEST_EXTENSION: &str = "manifest.json";
+/// Partition value Hive uses for rows that don't have one.
+pub const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+/// Key of the schema version in the key-value metadata of every parquet file.
+pub const SCHEMA_VERSION_METADATA_KEY: &str = "schema_version";
+
+/// Sidecar metadata uploaded next to every parquet file, so downstream loaders can dedupe files
+/// that cover the same version range without opening them.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ParquetFileManifest {
+    pub table_name: String,
+    // e.g. `date=2025-03-02`, `None` when files aren't partitioned
+    #[serde(default)]
+    pub partition: Option<String>,
+    pub processor_name: String,
+    pub file_path: String,
+    pub row_count: i64,
+    pub start_version: i64,
+    pub end_version: i64,
+    pub schema_hash: String,
+    // `HasSchemaVersion::SCHEMA_VERSION` of the rows, 0 for files written before it existed
+    #[serde(default)]
+    pub schema_version: u32,
+    pub file_size_bytes: i64,
+    // Files merged into this one by compaction. Readers ignore these files once this manifest
+    // is visible, even if they haven't been deleted yet.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compacted_from: Vec<String>,
+}
+
+/// A storage backend that finished parquet files are uploaded to.
+///
+/// Backends only move bytes to an object path. Serializing the buffers, retries and timeouts
+/// are handled by `ParquetUploader`, so every backend behaves the same way.
+#[async_trait]
+pub trait Uploadable: Send + Sync {
+    async fn upload_object(
+        &self,
+        object_path: &str,
+        buffer: Vec<u8>,
+    ) -> Result<(), ParquetProcessorError>;
+
+    /// Deleting an object that doesn't exist is not an error.
+    async fn delete_object(&self, object_path: &str) -> Result<(), ParquetProcessorError>;
+
+    /// Lists every object below the `prefix` directory, at any depth.
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<StoredObject>, ParquetProcessorError>;
+
+    async fn download_object(&self, object_path: &str) -> Result<Vec<u8>, ParquetProcessorError>;
+}
+
+/// An object listed by `Uploadable::list_objects`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredObject {
+    pub object_path: String,
+    pub size_bytes: u64,
+}
+
+/// Serializes `ParquetTypeStructs` buffers into parquet files and uploads them through an
+/// `Uploadable` backend.
+pub struct ParquetUploader {
+    uploader: Arc<dyn Uploadable>,
+    parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>>,
+    parquet_type_to_writer: HashMap<ParquetTypeEnum, SerializedFileWriter<Vec<u8>>>,
+    // Types without an entry use `default_writer_properties`
+    parquet_type_to_properties: HashMap<ParquetTypeEnum, Arc<WriterProperties>>,
+    pub bucket_root: String,
+    pub partition_scheme: ParquetPartitionScheme,
+    pub processor_name: String,
+    // Records every file before it's uploaded, `None` when checkpoints aren't persisted
+    file_ledger: Option<ParquetFileLedger>,
+}
+
+pub fn create_new_writer(
+    schema: Arc<Type>,
+    props: Arc<WriterProperties>,
+) -> anyhow::Result<SerializedFileWriter<Vec<u8>>> {
+    SerializedFileWriter::new(Vec::new(), schema, props).context("Failed to create new writer")
+}
+
+/// LZ4 compression and the parquet defaults for everything else.
+pub fn default_writer_properties() -> Arc<WriterProperties> {
+    Arc::new(
+        writer_properties(&ParquetWriterConfig::default())
+            .expect("Default writer properties are valid"),
+    )
+}
+
+pub fn writer_properties(config: &ParquetWriterConfig) -> anyhow::Result<WriterProperties> {
+    anyhow::ensure!(
+        config.max_row_group_rows > 0,
+        "max_row_group_rows must be positive"
+    );
+    let compression = match config.compression {
+        ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+        ParquetCompression::Lz4 => Compression::LZ4,
+        ParquetCompression::Lz4Raw => Compression::LZ4_RAW,
+        ParquetCompression::Zstd => Compression::ZSTD(match config.compression_level {
+            Some(level) => ZstdLevel::try_new(level).context("Invalid zstd compression level")?,
+            None => ZstdLevel::default(),
+        }),
+    };
+    let statistics = match config.statistics {
+        ParquetStatisticsLevel::None => EnabledStatistics::None,
+        ParquetStatisticsLevel::Chunk => EnabledStatistics::Chunk,
+        ParquetStatisticsLevel::Page => EnabledStatistics::Page,
+    };
+
+    let mut builder = WriterProperties::builder()
+        .set_compression(compression)
+        .set_max_row_group_size(config.max_row_group_rows)
+        .set_dictionary_enabled(config.dictionary_enabled)
+        .set_statistics_enabled(statistics);
+    for column in &config.bloom_filter_columns {
+        builder = builder.set_column_bloom_filter_enabled(ColumnPath::from(column.as_str()), true);
+    }
+    Ok(builder.build())
+}
+
+impl ParquetUploader {
+    pub fn new(
+        uploader: Arc<dyn Uploadable>,
+        parquet_type_to_schemas: HashMap<ParquetTypeEnum, Arc<Type>>,
+        parquet_type_to_writer: HashMap<ParquetTypeEnum, SerializedFileWriter<Vec<u8>>>,
+        parquet_type_to_properties: HashMap<ParquetTypeEnum, Arc<WriterProperties>>,
+        bucket_root: String,
+        partition_scheme: ParquetPartitionScheme,
+        processor_name: String,
+        file_ledger: Option<ParquetFileLedger>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            uploader,
+            parquet_type_to_schemas,
+            parquet_type_to_writer,
+            parquet_type_to_properties,
+            bucket_root,
+            partition_scheme,
+            processor_name,
+            file_ledger,
+        })
+    }
+
+    /// Uploads the buffer as one file per partition. Every file is uploaded before this returns,
+    /// so the version tracker only checkpoints a buffer once all of its partitions are written.
+    ///
+    /// Returns the manifests of the uploaded files, which the version tracker commits together
+    /// with the checkpoint.
+    pub async fn upload_buffer(
+        &mut self,
+        mut buffer: ParquetTypeStructs,
+    ) -> Result<Vec<ParquetFileManifest>, ProcessorError> {
+        let parquet_type = buffer.parquet_type();
+        let table_name = parquet_type.to_string();
+
+        let mut manifests = vec![];
+        for (partition, partition_buffer) in buffer.split_by_partition(&self.partition_scheme) {
+            let result = partition_buffer
+                .upload_to_storage(self, parquet_type, &table_name, partition.as_deref())
+                .await;
+            match result {
+                Ok(manifest) => manifests.extend(manifest),
+                Err(e) => {
+                    error!("Failed to upload buffer: {}", e);
+                    return Err(ProcessorError::ProcessError {
+                        message: format!("Failed to upload buffer: {e}"),
+                    });
+                },
+            }
+        }
+        Ok(manifests)
+    }
+
+    /// The backend files are uploaded to.
+    pub fn uploader(&self) -> &dyn Uploadable {
+        self.uploader.as_ref()
+    }
+
+    fn create_new_writer(
+        &self,
+        parquet_type: ParquetTypeEnum,
+    ) -> anyhow::Result<SerializedFileWriter<Vec<u8>>> {
+        let schema = self
+            .parquet_type_to_schemas
+            .get(&parquet_type)
+            .context("Parquet type not found in schemas")?
+            .clone();
+
+        create_new_writer(schema, self.properties(parquet_type))
+    }
+
+    fn properties(&self, parquet_type: ParquetTypeEnum) -> Arc<WriterProperties> {
+        self.parquet_type_to_properties
+            .get(&parquet_type)
+            .cloned()
+            .unwrap_or_else(default_writer_properties)
+    }
+
+    /// # Context: Why we replace our writer
+    ///
+    /// Once we’re ready to upload (either because the buffer is full or enough time has passed),
+    /// we don’t want to keep adding new data to that same writer. we want a clean slate for the next batch.
+    /// So, we replace the old writer with a new one to empty the writer buffer without losing any data.
+    fn get_and_replace_writer(
+        &mut self,
+        parquet_type: ParquetTypeEnum,
+    ) -> anyhow::Result<SerializedFileWriter<Vec<u8>>> {
+        let old_writer = self
+            .parquet_type_to_writer
+            .remove(&parquet_type)
+            .context("Writer for specified Parquet type not found")?;
+
+        // Create a new writer and replace the old writer with it
+        let new_writer = self.create_new_writer(parquet_type)?;
+        self.parquet_type_to_writer.insert(parquet_type, new_writer);
+
+        // Return the old writer so its contents can be used
+        Ok(old_writer)
+    }
+
+    // Generic upload function to handle any data type
+    pub async fn upload_generic<ParquetType>(
+        &mut self,
+        data: &[ParquetType],
+        parquet_type: ParquetTypeEnum,
+        table_name: &str,
+        partition: Option<&str>,
+    ) -> anyhow::Result<Option<ParquetFileManifest>>
+    where
+        ParquetType: HasVersion + HasParquetSchema,
+        for<'a> &'a [ParquetType]: RecordWriter<ParquetType>,
+    {
+        if data.is_empty() {
+            debug!("Buffer is empty, skipping upload.");
+            return Ok(None);
+        }
+
+        let max_row_group_rows = self.properties(parquet_type).max_row_group_size();
+        let writer = self
+            .parquet_type_to_writer
+            .get_mut(&parquet_type)
+            .context("Writer not found for specified parquet type")?;
+
+        // Large buffers are written as several row groups
+        for rows in data.chunks(max_row_group_rows) {
+            let mut row_group_writer =
+                writer.next_row_group().context("Failed to get row group")?;
+
+            rows.write_to_row_group(&mut row_group_writer)
+                .context("Failed to write to row group")?;
+
+            row_group_writer
+                .close()
+                .context("Failed to close row group")?;
+        }
+
+        let mut old_writer = self
+            .get_and_replace_writer(parquet_type)
+            .context("Failed to close writer")?;
+        old_writer
+            .append_key_value_metadata(schema_version_metadata(ParquetType::schema_version()));
+        let upload_buffer = old_writer
+            .into_inner()
+            .context("Failed to get inner buffer")?;
+
+        let start_version = data.iter().map(|row| row.version()).min().unwrap();
+        let end_version = data.iter().map(|row| row.version()).max().unwrap();
+        let schema = self
+            .parquet_type_to_schemas
+            .get(&parquet_type)
+            .context("Parquet type not found in schemas")?;
+        let bucket_root = PathBuf::from(&self.bucket_root);
+        let mut manifest = ParquetFileManifest {
+            table_name: table_name.to_string(),
+            partition: partition.map(str::to_string),
+            processor_name: self.processor_name.clone(),
+            file_path: String::new(),
+            row_count: data.len() as i64,
+            start_version,
+            end_version,
+            schema_hash: schema_hash(schema),
+            schema_version: ParquetType::schema_version(),
+            file_size_bytes: upload_buffer.len() as i64,
+            compacted_from: vec![],
+        };
+        manifest.file_path = parquet_file_path(&bucket_root, &manifest);
+
+        if let Some(file_ledger) = &self.file_ledger {
+            file_ledger
+                .record_uncommitted(&manifest)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to record {}: {e:?}", manifest.file_path))?;
+        }
+
+        let manifest = upload_parquet_file(
+            self.uploader.as_ref(),
+            upload_buffer,
+            &bucket_root,
+            manifest,
+        )
+        .await?;
+
+        debug!(
+            "Uploaded parquet file for table: {}, start_version: {}, end_version: {}",
+            table_name, start_version, end_version,
+        );
+
+        Ok(Some(manifest))
+    }
+}
+
+/// Uploads a serialized parquet file through the given backend, followed by its manifest.
+///
+/// The object name is derived from the table and the version range of the rows in the file,
+/// so re-uploading the same range (e.g. after a crash before the checkpoint was saved) replaces
+/// the existing object instead of writing a duplicate. The manifest is uploaded after the
+/// parquet file, so a manifest is only ever visible for a complete file.
+///
+/// Returns the manifest, with `file_path` set to the object path of the parquet file.
+pub async fn upload_parquet_file(
+    uploader: &dyn Uploadable,
+    buffer: Vec<u8>,
+    bucket_root: &Path,
+    mut manifest: ParquetFileManifest,
+) -> Result<ParquetFileManifest, ParquetProcessorError> {
+    if buffer.is_empty() {
+        error!("The file is empty and has no data to upload.",);
+        return Err(ParquetProcessorError::Other(
+            "The file is empty and has no data to upload.".to_string(),
+        ));
+    }
+
+    let file_name = parquet_file_path(bucket_root, &manifest);
+    let manifest_name = Path::new(&file_name)
+        .with_extension(MANIFEST_EXTENSION)
+        .to_str()
+        .unwrap()
+        .to_owned();
+
+    PARQUET_BUFFER_SIZE
+        .with_label_values(&[&manifest.processor_name, &manifest.table_name])
+        .set(buffer.len() as i64);
+
+    upload_object_with_retry(uploader, &file_name, buffer).await?;
+    info!(
+        table_name = manifest.table_name,
+        file_name = file_name,
+        start_version = manifest.start_version,
+        end_version = manifest.end_version,
+        "File uploaded successfully",
+    );
+
+    manifest.file_path = file_name;
+    let manifest_buffer = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| ParquetProcessorError::Other(format!("Failed to serialize manifest: {e}")))?;
+    upload_object_with_retry(uploader, &manifest_name, manifest_buffer).await?;
+
+    Ok(manifest)
+}
+
+/// Uploads a single object, retrying with exponential backoff on failures and timeouts.
+async fn upload_object_with_retry(
+    uploader: &dyn Uploadable,
+    object_path: &str,
+    buffer: Vec<u8>,
+) -> Result<(), ParquetProcessorError> {
+    let mut retry_count = 0;
+    let mut delay = INITIAL_DELAY_MS;
+
+    loop {
+        let upload_result = timeout(
+            Duration::from_secs(TIMEOUT_SECONDS),
+            uploader.upload_object(object_path, buffer.clone()),
+        )
+        .await;
+
+        match upload_result {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(e)) => {
+                error!("Failed to upload {}: {}", object_path, e);
+                if retry_count >= MAX_RETRIES {
+                    return Err(e);
+                }
+            },
+            Err(e) => {
+                error!("Upload of {} timed out: {}", object_path, e);
+                if retry_count >= MAX_RETRIES {
+                    return Err(ParquetProcessorError::TimeoutError(e));
+                }
+            },
+        }
+
+        retry_count += 1;
+        sleep(Duration::from_millis(delay)).await;
+        delay *= 2;
+        debug!("Retrying upload operation. Retry count: {}", retry_count);
+    }
+}
+
+/// The object path of the parquet file described by `manifest`.
+pub fn parquet_file_path(bucket_root: &Path, manifest: &ParquetFileManifest) -> String {
+    generate_parquet_file_path(
+        bucket_root,
+        &manifest.table_name,
+        manifest.schema_version,
+        manifest.partition.as_deref(),
+        manifest.start_version,
+        manifest.end_version,
+    )
+    .to_str()
+    .unwrap()
+    .to_owned()
+}
+
+/// `{bucket_root}/{table}/v{schema_version}/{start_version}_{end_version}.parquet`, or
+/// `{bucket_root}/table={table}/schema_version={schema_version}/{partition}/...` when the files
+/// are partitioned, so Hive readers see the schema version as a partition column.
+fn generate_parquet_file_path(
+    bucket_root: &Path,
+    table: &str,
+    schema_version: u32,
+    partition: Option<&str>,
+    start_version: i64,
+    end_version: i64,
+) -> PathBuf {
+    match partition {
+        Some(partition) => bucket_root
+            .join(format!(
+                "table={table}/schema_version={schema_version}/{partition}"
+            ))
+            .join(format!("{start_version}_{end_version}.parquet")),
+        None => bucket_root.join(format!(
+            "{table}/v{schema_version}/{start_version}_{end_version}.parquet"
+        )),
+    }
+}
+
+/// The key-value metadata entry that records `schema_version` in a parquet file.
+pub fn schema_version_metadata(schema_version: u32) -> KeyValue {
+    KeyValue::new(
+        SCHEMA_VERSION_METADATA_KEY.to_string(),
+        schema_version.to_string(),
+    )
+}
+
+/// The Hive-style partition a row belongs to, e.g. `date=2025-03-02` or `version_bucket=1000000`.
+pub fn partition_for_row(
+    partition_scheme: &ParquetPartitionScheme,
+    version: i64,
+    timestamp: Option<chrono::NaiveDateTime>,
+) -> Option<String> {
+    match partition_scheme {
+        ParquetPartitionScheme::None => None,
+        ParquetPartitionScheme::Date => Some(match timestamp {
+            Some(timestamp) => format!("date={}", timestamp.format("%Y-%m-%d")),
+            None => format!("date={HIVE_DEFAULT_PARTITION}"),
+        }),
+        ParquetPartitionScheme::VersionBucket { bucket_size } => Some(format!(
+            "version_bucket={}",
+            version - version.rem_euclid(*bucket_size)
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parquet_processors::parquet_utils::local_uploader::LocalUploader,
+        processors::default::models::move_resources::ParquetMoveResource,
+    };
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    fn move_resource(txn_version: i64) -> ParquetMoveResource {
+        ParquetMoveResource {
+            txn_version,
+            ..Default::default()
+        }
+    }
+
+    fn move_resource_on(txn_version: i64, day: u32) -> ParquetMoveResource {
+        ParquetMoveResource {
+            txn_version,
+            block_timestamp: chrono::NaiveDate::from_ymd_opt(2025, 3, day)
+                .unwrap()
+                .and_hms_opt(23, 59, 59)
+                .unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reupload_of_same_version_range_replaces_object() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let schema = ParquetMoveResource::schema();
+        let mut parquet_uploader = ParquetUploader::new(
+            Arc::new(LocalUploader::new(root_dir.path())),
+            HashMap::from([(ParquetTypeEnum::MoveResources, schema.clone())]),
+            HashMap::from([(
+                ParquetTypeEnum::MoveResources,
+                create_new_writer(schema.clone(), default_writer_properties()).unwrap(),
+            )]),
+            HashMap::new(),
+            "bucket_root".to_string(),
+            ParquetPartitionScheme::default(),
+            "processor_name".to_string(),
+            None,
+        )
+        .unwrap();
+
+        for _ in 0..2 {
+            parquet_uploader
+                .upload_buffer(ParquetTypeStructs::MoveResource(vec![
+                    move_resource(100),
+                    move_resource(101),
+                    move_resource(105),
+                ]))
+                .await
+                .unwrap();
+        }
+
+        let table_dir = root_dir.path().join("bucket_root/move_resources/v1");
+        let mut file_names: Vec<String> = std::fs::read_dir(&table_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        file_names.sort();
+        assert_eq!(file_names, vec![
+            "100_105.manifest.json".to_string(),
+            "100_105.parquet".to_string(),
+        ]);
+
+        let manifest: ParquetFileManifest = serde_json::from_slice(
+            &std::fs::read(table_dir.join("100_105.manifest.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest.table_name, "move_resources");
+        assert_eq!(manifest.processor_name, "processor_name");
+        assert_eq!(
+            manifest.file_path,
+            "bucket_root/move_resources/v1/100_105.parquet"
+        );
+        assert_eq!(manifest.row_count, 3);
+        assert_eq!(manifest.start_version, 100);
+        assert_eq!(manifest.end_version, 105);
+        assert_eq!(manifest.schema_hash, schema_hash(&schema));
+        assert_eq!(manifest.schema_version, 1);
+        assert_eq!(
+            manifest.file_size_bytes,
+            std::fs::metadata(table_dir.join("100_105.parquet"))
+                .unwrap()
+                .len() as i64
+        );
+    }
+
+    #[tokio::test]
+    async fn test_date_partitioned_buffer_is_split_per_day() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let schema = ParquetMoveResource::schema();
+        let mut parquet_uploader = ParquetUploader::new(
+            Arc::new(LocalUploader::new(root_dir.path())),
+            HashMap::from([(ParquetTypeEnum::MoveResources, schema.clone())]),
+            HashMap::from([(
+                ParquetTypeEnum::MoveResources,
+                create_new_writer(schema, default_writer_properties()).unwrap(),
+            )]),
+            HashMap::new(),
+            "bucket_root".to_string(),
+            ParquetPartitionScheme::Date,
+            "processor_name".to_string(),
+            None,
+        )
+        .unwrap();
+
+        parquet_uploader
+            .upload_buffer(ParquetTypeStructs::MoveResource(vec![
+                move_resource_on(100, 1),
+                move_resource_on(101, 2),
+                move_resource_on(105, 2),
+            ]))
+            .await
+            .unwrap();
+
+        let table_dir = root_dir
+            .path()
+            .join("bucket_root/table=move_resources/schema_version=1");
+        assert!(table_dir.join("date=2025-03-01/100_100.parquet").exists());
+        assert!(table_dir.join("date=2025-03-02/101_105.parquet").exists());
+
+        let manifest: ParquetFileManifest = serde_json::from_slice(
+            &std::fs::read(table_dir.join("date=2025-03-02/101_105.manifest.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest.partition, Some("date=2025-03-02".to_string()));
+        assert_eq!(manifest.row_count, 2);
+    }
+
+    #[test]
+    fn test_partition_for_row() {
+        let timestamp = chrono::NaiveDate::from_ymd_opt(2025, 3, 2)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(
+            partition_for_row(&ParquetPartitionScheme::None, 5, Some(timestamp)),
+            None
+        );
+        assert_eq!(
+            partition_for_row(&ParquetPartitionScheme::Date, 5, Some(timestamp)),
+            Some("date=2025-03-02".to_string())
+        );
+        assert_eq!(
+            partition_for_row(&ParquetPartitionScheme::Date, 5, None),
+            Some(format!("date={HIVE_DEFAULT_PARTITION}"))
+        );
+        assert_eq!(
+            partition_for_row(
+                &ParquetPartitionScheme::VersionBucket { bucket_size: 1000 },
+                2999,
+                None
+            ),
+            Some("version_bucket=2000".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_large_buffer_is_split_into_row_groups() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let schema = ParquetMoveResource::schema();
+        let props = Arc::new(
+            writer_properties(&ParquetWriterConfig {
+                compression: ParquetCompression::Zstd,
+                compression_level: Some(3),
+                max_row_group_rows: 2,
+                bloom_filter_columns: vec!["resource_address".to_string()],
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        let mut parquet_uploader = ParquetUploader::new(
+            Arc::new(LocalUploader::new(root_dir.path())),
+            HashMap::from([(ParquetTypeEnum::MoveResources, schema.clone())]),
+            HashMap::from([(
+                ParquetTypeEnum::MoveResources,
+                create_new_writer(schema, props.clone()).unwrap(),
+            )]),
+            HashMap::from([(ParquetTypeEnum::MoveResources, props)]),
+            "bucket_root".to_string(),
+            ParquetPartitionScheme::default(),
+            "processor_name".to_string(),
+            None,
+        )
+        .unwrap();
+
+        parquet_uploader
+            .upload_buffer(ParquetTypeStructs::MoveResource(
+                (100..105).map(move_resource).collect(),
+            ))
+            .await
+            .unwrap();
+
+        let file = std::fs::File::open(
+            root_dir
+                .path()
+                .join("bucket_root/move_resources/v1/100_104.parquet"),
+        )
+        .unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 3);
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 5);
+        // The level isn't stored in the file
+        assert!(matches!(
+            reader.metadata().row_group(0).column(0).compression(),
+            Compression::ZSTD(_)
+        ));
+        assert_eq!(
+            reader.metadata().file_metadata().key_value_metadata(),
+            Some(&vec![schema_version_metadata(1)])
+        );
+    }
+}
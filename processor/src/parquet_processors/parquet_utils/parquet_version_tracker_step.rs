@@ -1,4 +1,6 @@
-use crate::parquet_processors::ParquetTypeEnum;
+use crate::parquet_processors::{
+    parquet_utils::parquet_uploader::ParquetFileManifest, ParquetTypeEnum,
+};
 use anyhow::Result;
 use libra2_indexer_processor_sdk::{
     traits::{
@@ -14,18 +16,42 @@ use tracing::debug;
 /// The ParquetProcessorStatusSaverTrait intended to save
 /// the latest successfully processed transaction version to storage,
 /// ensuring that the processor_status is persistently stored.
+///
+/// `files` are the parquet files uploaded since the last save, which are recorded in the same
+/// transaction as the status.
 #[async_trait]
 pub trait ParquetProcessorStatusSaverTrait {
     async fn save_parquet_processor_status(
         &self,
         last_success_batch: &TransactionContext<()>,
         table_name: &str,
+        files: &[ParquetFileManifest],
     ) -> Result<(), ProcessorError>;
 }
 
+/// The versions a writer step finished writing to one table, along with the parquet files they
+/// were uploaded to. `files` is empty for sinks that don't write files.
+#[derive(Clone, Debug)]
+pub struct TableBatchMetadata {
+    pub metadata: TransactionMetadata,
+    pub files: Vec<ParquetFileManifest>,
+}
+
+impl From<TransactionMetadata> for TableBatchMetadata {
+    fn from(metadata: TransactionMetadata) -> Self {
+        Self {
+            metadata,
+            files: vec![],
+        }
+    }
+}
+
 /// Tracks the versioned processing of sequential transactions, ensuring no gaps
 /// occur between them.
 ///
+/// Checkpoints are saved on a polling interval, except for batches that uploaded files. Those are
+/// saved right away, so a file is committed as soon as possible after it lands.
+///
 /// Important: this step assumes ordered transactions. Please use the `OrederByVersionStep` before this step
 /// if the transactions are not ordered.
 pub struct ParquetVersionTrackerStep<S>
@@ -56,7 +82,7 @@ where
         for (parquet_type, last_success_batch) in &self.last_success_batch {
             let table_name = parquet_type.to_string();
             self.processor_status_saver
-                .save_parquet_processor_status(last_success_batch, &table_name)
+                .save_parquet_processor_status(last_success_batch, &table_name, &[])
                 .await?;
         }
         Ok(())
@@ -69,7 +95,7 @@ where
     Self: Sized + Send + 'static,
     S: ParquetProcessorStatusSaverTrait + Send + 'static,
 {
-    type Input = HashMap<ParquetTypeEnum, TransactionMetadata>;
+    type Input = HashMap<ParquetTypeEnum, TableBatchMetadata>;
     type Output = ();
     type RunType = PollableAsyncRunType;
 
@@ -80,7 +106,8 @@ where
         let mut processed_data = HashMap::new();
 
         // Check for version gap before processing each key-value pair
-        for (parquet_type, current_metadata) in &current_batch.data {
+        for (parquet_type, table_batch) in &current_batch.data {
+            let current_metadata = &table_batch.metadata;
             // we need to have a map of last_success_bath for parquet-Type as well.
             // if there is a last_success_batch for the current parquet-Type then we need to check the version gap
             debug!(
@@ -114,6 +141,16 @@ where
                     data: (),
                     metadata: current_metadata.clone(),
                 });
+
+            if !table_batch.files.is_empty() {
+                self.processor_status_saver
+                    .save_parquet_processor_status(
+                        &self.last_success_batch[parquet_type],
+                        &parquet_type.to_string(),
+                        &table_batch.files,
+                    )
+                    .await?;
+            }
         }
 
         // Pass through the current batch with updated metadata
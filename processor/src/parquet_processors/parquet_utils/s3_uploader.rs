@@ -0,0 +1,94 @@
+use crate::{
+    config::db_config::S3StorageConfig,
+    parquet_processors::parquet_utils::{
+        parquet_uploader::{StoredObject, Uploadable},
+        util::ParquetProcessorError,
+    },
+};
+use anyhow::Context;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use object_store::{
+    aws::{AmazonS3, AmazonS3Builder},
+    path::Path as ObjectPath,
+    ObjectStore, PutPayload,
+};
+
+/// Uploads parquet files to an S3 bucket, or any store that speaks the S3 API (MinIO, R2, ...).
+pub struct S3Uploader {
+    store: AmazonS3,
+    pub bucket_name: String,
+}
+
+impl S3Uploader {
+    /// Credentials that are not set in the config fall back to the standard `AWS_*`
+    /// environment variables.
+    pub fn new(bucket_name: String, config: &S3StorageConfig) -> anyhow::Result<Self> {
+        let mut builder = AmazonS3Builder::from_env()
+            .with_bucket_name(&bucket_name)
+            .with_region(&config.region)
+            .with_allow_http(config.allow_http);
+        if let Some(endpoint) = &config.endpoint {
+            builder = builder.with_endpoint(endpoint);
+        }
+        if let Some(access_key_id) = &config.access_key_id {
+            builder = builder.with_access_key_id(access_key_id);
+        }
+        if let Some(secret_access_key) = &config.secret_access_key {
+            builder = builder.with_secret_access_key(secret_access_key);
+        }
+        let store = builder.build().context("Failed to create S3 client")?;
+
+        Ok(Self { store, bucket_name })
+    }
+}
+
+#[async_trait]
+impl Uploadable for S3Uploader {
+    async fn upload_object(
+        &self,
+        object_path: &str,
+        buffer: Vec<u8>,
+    ) -> Result<(), ParquetProcessorError> {
+        self.store
+            .put(&ObjectPath::from(object_path), PutPayload::from(buffer))
+            .await
+            .map_err(ParquetProcessorError::ObjectStoreError)?;
+        Ok(())
+    }
+
+    async fn delete_object(&self, object_path: &str) -> Result<(), ParquetProcessorError> {
+        match self.store.delete(&ObjectPath::from(object_path)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(ParquetProcessorError::ObjectStoreError(e)),
+        }
+    }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<StoredObject>, ParquetProcessorError> {
+        let objects: Vec<_> = self
+            .store
+            .list(Some(&ObjectPath::from(prefix)))
+            .try_collect()
+            .await
+            .map_err(ParquetProcessorError::ObjectStoreError)?;
+        Ok(objects
+            .into_iter()
+            .map(|object| StoredObject {
+                object_path: object.location.to_string(),
+                size_bytes: object.size as u64,
+            })
+            .collect())
+    }
+
+    async fn download_object(&self, object_path: &str) -> Result<Vec<u8>, ParquetProcessorError> {
+        let bytes = self
+            .store
+            .get(&ObjectPath::from(object_path))
+            .await
+            .map_err(ParquetProcessorError::ObjectStoreError)?
+            .bytes()
+            .await
+            .map_err(ParquetProcessorError::ObjectStoreError)?;
+        Ok(bytes.to_vec())
+    }
+}
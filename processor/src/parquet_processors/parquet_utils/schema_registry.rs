@@ -0,0 +1,653 @@
+use crate::parquet_processors::{
+    parquet_utils::util::schema_hash, ParquetTypeEnum, ParquetTypeStructs, ParquetTypeTrait,
+};
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+/// The current parquet schema of a table, as dumped by the `schemas` command.
+#[derive(Clone, Debug, Serialize)]
+pub struct ParquetTableSchema {
+    pub table_name: String,
+    pub schema_version: u32,
+    pub schema_hash: String,
+    pub columns: Vec<ParquetColumnSchema>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ParquetColumnSchema {
+    pub name: String,
+    pub physical_type: String,
+    // e.g. `String` or `Integer { .. }` for unsigned numbers, `None` for signed numbers, booleans
+    // and timestamps
+    pub logical_type: Option<String>,
+    pub repetition: String,
+}
+
+/// The schemas and schema versions of every parquet table, in the order of `ParquetTypeEnum`.
+pub fn parquet_table_schemas() -> Vec<ParquetTableSchema> {
+    ParquetTypeEnum::iter()
+        .map(|parquet_type| {
+            let buffer = ParquetTypeStructs::default_for_type(&parquet_type);
+            let schema = buffer.schema();
+            let columns = schema
+                .get_fields()
+                .iter()
+                .map(|field| {
+                    let info = field.get_basic_info();
+                    ParquetColumnSchema {
+                        name: field.name().to_string(),
+                        physical_type: field.get_physical_type().to_string(),
+                        logical_type: info
+                            .logical_type()
+                            .map(|logical_type| format!("{logical_type:?}")),
+                        repetition: info.repetition().to_string(),
+                    }
+                })
+                .collect();
+            ParquetTableSchema {
+                table_name: parquet_type.to_string(),
+                schema_version: buffer.schema_version(),
+                schema_hash: schema_hash(&schema),
+                columns,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The columns of every schema version of every table, as `{name} {physical type}
+    /// {repetition}`, followed by the logical type if there is one. When a `Parquet*` struct
+    /// changes, bump its `SCHEMA_VERSION` and add the new columns here as the next version. Older
+    /// versions stay, they describe files in the bucket.
+    const SCHEMA_SNAPSHOTS: &[(ParquetTypeEnum, u32, &[&str])] = &[
+        (ParquetTypeEnum::MoveResources, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "block_height INT64 REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+            "resource_address BYTE_ARRAY REQUIRED String",
+            "resource_type BYTE_ARRAY REQUIRED String",
+            "module BYTE_ARRAY REQUIRED String",
+            "fun BYTE_ARRAY REQUIRED String",
+            "is_deleted BOOLEAN REQUIRED",
+            "generic_type_params BYTE_ARRAY OPTIONAL String",
+            "data BYTE_ARRAY OPTIONAL String",
+            "state_key_hash BYTE_ARRAY REQUIRED String",
+        ]),
+        (ParquetTypeEnum::WriteSetChanges, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "state_key_hash BYTE_ARRAY REQUIRED String",
+            "change_type BYTE_ARRAY REQUIRED String",
+            "resource_address BYTE_ARRAY REQUIRED String",
+            "block_height INT64 REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::Transactions, 1, &[
+            "txn_version INT64 REQUIRED",
+            "block_height INT64 REQUIRED",
+            "epoch INT64 REQUIRED",
+            "txn_type BYTE_ARRAY REQUIRED String",
+            "payload BYTE_ARRAY OPTIONAL String",
+            "payload_type BYTE_ARRAY OPTIONAL String",
+            "gas_used INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "success BOOLEAN REQUIRED",
+            "vm_status BYTE_ARRAY REQUIRED String",
+            "num_events INT64 REQUIRED",
+            "num_write_set_changes INT64 REQUIRED",
+            "txn_hash BYTE_ARRAY REQUIRED String",
+            "state_change_hash BYTE_ARRAY REQUIRED String",
+            "event_root_hash BYTE_ARRAY REQUIRED String",
+            "state_checkpoint_hash BYTE_ARRAY OPTIONAL String",
+            "accumulator_root_hash BYTE_ARRAY REQUIRED String",
+            "txn_total_bytes INT64 REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::TableItems, 1, &[
+            "txn_version INT64 REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "transaction_block_height INT64 REQUIRED",
+            "table_key BYTE_ARRAY REQUIRED String",
+            "table_handle BYTE_ARRAY REQUIRED String",
+            "decoded_key BYTE_ARRAY REQUIRED String",
+            "decoded_value BYTE_ARRAY OPTIONAL String",
+            "is_deleted BOOLEAN REQUIRED",
+        ]),
+        (ParquetTypeEnum::MoveModules, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "block_height INT64 REQUIRED",
+            "name BYTE_ARRAY REQUIRED String",
+            "address BYTE_ARRAY REQUIRED String",
+            "bytecode BYTE_ARRAY REQUIRED",
+            "exposed_functions BYTE_ARRAY OPTIONAL String",
+            "friends BYTE_ARRAY OPTIONAL String",
+            "structs BYTE_ARRAY OPTIONAL String",
+            "is_deleted BOOLEAN REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CurrentTableItems, 1, &[
+            "table_handle BYTE_ARRAY REQUIRED String",
+            "key_hash BYTE_ARRAY REQUIRED String",
+            "key BYTE_ARRAY REQUIRED String",
+            "decoded_key BYTE_ARRAY REQUIRED String",
+            "decoded_value BYTE_ARRAY OPTIONAL String",
+            "last_transaction_version INT64 REQUIRED",
+            "is_deleted BOOLEAN REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::BlockMetadataTransactions, 1, &[
+            "txn_version INT64 REQUIRED",
+            "block_height INT64 REQUIRED",
+            "block_id BYTE_ARRAY REQUIRED String",
+            "round INT64 REQUIRED",
+            "epoch INT64 REQUIRED",
+            "previous_block_votes_bitvec BYTE_ARRAY REQUIRED String",
+            "proposer BYTE_ARRAY REQUIRED String",
+            "failed_proposer_indices BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+            "since_unix_epoch INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+        ]),
+        (ParquetTypeEnum::TableMetadata, 1, &[
+            "handle BYTE_ARRAY REQUIRED String",
+            "key_type BYTE_ARRAY REQUIRED String",
+            "value_type BYTE_ARRAY REQUIRED String",
+        ]),
+        (ParquetTypeEnum::Events, 1, &[
+            "txn_version INT64 REQUIRED",
+            "account_address BYTE_ARRAY REQUIRED String",
+            "sequence_number INT64 REQUIRED",
+            "creation_number INT64 REQUIRED",
+            "block_height INT64 REQUIRED",
+            "event_type BYTE_ARRAY REQUIRED String",
+            "data BYTE_ARRAY REQUIRED String",
+            "event_index INT64 REQUIRED",
+            "indexed_type BYTE_ARRAY REQUIRED String",
+            "type_tag_bytes INT64 REQUIRED",
+            "total_bytes INT64 REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::UserTransactions, 1, &[
+            "txn_version INT64 REQUIRED",
+            "block_height INT64 REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+            "epoch INT64 REQUIRED",
+            "sender BYTE_ARRAY REQUIRED String",
+            "sequence_number INT64 OPTIONAL",
+            "replay_protection_nonce BYTE_ARRAY OPTIONAL String",
+            "entry_function_id_str BYTE_ARRAY REQUIRED String",
+            "expiration_timestamp_secs INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "parent_signature_type BYTE_ARRAY REQUIRED String",
+            "gas_fee_payer_address BYTE_ARRAY OPTIONAL String",
+            "gas_used_unit INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "gas_unit_price INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "max_gas_octa INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "storage_refund_octa INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "is_transaction_success BOOLEAN REQUIRED",
+            "num_signatures INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::Signatures, 1, &[
+            "txn_version INT64 REQUIRED",
+            "multi_agent_index INT64 REQUIRED",
+            "multi_sig_index INT64 REQUIRED",
+            "is_sender_primary BOOLEAN REQUIRED",
+            "block_height INT64 REQUIRED",
+            "signer BYTE_ARRAY REQUIRED String",
+            "account_signature_type BYTE_ARRAY REQUIRED String",
+            "any_signature_type BYTE_ARRAY OPTIONAL String",
+            "public_key_type BYTE_ARRAY OPTIONAL String",
+            "public_key BYTE_ARRAY REQUIRED String",
+            "signature BYTE_ARRAY REQUIRED String",
+            "threshold INT64 OPTIONAL",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::AnsPrimaryNameV2, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "registered_address BYTE_ARRAY REQUIRED String",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "domain BYTE_ARRAY OPTIONAL String",
+            "subdomain BYTE_ARRAY OPTIONAL String",
+            "token_name BYTE_ARRAY OPTIONAL String",
+            "is_deleted BOOLEAN REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CurrentAnsPrimaryNameV2, 1, &[
+            "registered_address BYTE_ARRAY REQUIRED String",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "domain BYTE_ARRAY OPTIONAL String",
+            "subdomain BYTE_ARRAY OPTIONAL String",
+            "token_name BYTE_ARRAY OPTIONAL String",
+            "is_deleted BOOLEAN REQUIRED",
+            "last_transaction_version INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::AnsLookupV2, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "domain BYTE_ARRAY REQUIRED String",
+            "subdomain BYTE_ARRAY REQUIRED String",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "registered_address BYTE_ARRAY OPTIONAL String",
+            "expiration_timestamp INT64 REQUIRED",
+            "token_name BYTE_ARRAY REQUIRED String",
+            "is_deleted BOOLEAN REQUIRED",
+            "subdomain_expiration_policy INT64 OPTIONAL",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CurrentAnsLookupV2, 1, &[
+            "domain BYTE_ARRAY REQUIRED String",
+            "subdomain BYTE_ARRAY REQUIRED String",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "registered_address BYTE_ARRAY OPTIONAL String",
+            "last_transaction_version INT64 REQUIRED",
+            "expiration_timestamp INT64 REQUIRED",
+            "token_name BYTE_ARRAY REQUIRED String",
+            "is_deleted BOOLEAN REQUIRED",
+            "subdomain_expiration_policy INT64 OPTIONAL",
+        ]),
+        (ParquetTypeEnum::FungibleAssetActivities, 1, &[
+            "txn_version INT64 REQUIRED",
+            "event_index INT64 REQUIRED",
+            "owner_address BYTE_ARRAY OPTIONAL String",
+            "storage_id BYTE_ARRAY REQUIRED String",
+            "asset_type BYTE_ARRAY OPTIONAL String",
+            "is_frozen BOOLEAN OPTIONAL",
+            "amount BYTE_ARRAY OPTIONAL String",
+            "event_type BYTE_ARRAY REQUIRED String",
+            "is_gas_fee BOOLEAN REQUIRED",
+            "gas_fee_payer_address BYTE_ARRAY OPTIONAL String",
+            "is_transaction_success BOOLEAN REQUIRED",
+            "entry_function_id_str BYTE_ARRAY OPTIONAL String",
+            "block_height INT64 REQUIRED",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+            "storage_refund_octa INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+        ]),
+        (ParquetTypeEnum::FungibleAssetMetadata, 1, &[
+            "asset_type BYTE_ARRAY REQUIRED String",
+            "creator_address BYTE_ARRAY REQUIRED String",
+            "name BYTE_ARRAY REQUIRED String",
+            "symbol BYTE_ARRAY REQUIRED String",
+            "decimals INT32 REQUIRED",
+            "icon_uri BYTE_ARRAY OPTIONAL String",
+            "project_uri BYTE_ARRAY OPTIONAL String",
+            "last_transaction_version INT64 REQUIRED",
+            "last_transaction_timestamp INT64 REQUIRED",
+            "supply_aggregator_table_handle_v1 BYTE_ARRAY OPTIONAL String",
+            "supply_aggregator_table_key_v1 BYTE_ARRAY OPTIONAL String",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "is_token_v2 BOOLEAN OPTIONAL",
+            "supply_v2 BYTE_ARRAY OPTIONAL String",
+            "maximum_v2 BYTE_ARRAY OPTIONAL String",
+        ]),
+        (ParquetTypeEnum::FungibleAssetBalances, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "storage_id BYTE_ARRAY REQUIRED String",
+            "owner_address BYTE_ARRAY REQUIRED String",
+            "asset_type BYTE_ARRAY REQUIRED String",
+            "is_primary BOOLEAN REQUIRED",
+            "is_frozen BOOLEAN REQUIRED",
+            "amount BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+            "token_standard BYTE_ARRAY REQUIRED String",
+        ]),
+        (ParquetTypeEnum::FungibleAssetToCoinMappings, 1, &[
+            "fungible_asset_metadata_address BYTE_ARRAY REQUIRED String",
+            "coin_type BYTE_ARRAY REQUIRED String",
+            "last_transaction_version INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CoinSupply, 1, &[
+            "txn_version INT64 REQUIRED",
+            "coin_type_hash BYTE_ARRAY REQUIRED String",
+            "coin_type BYTE_ARRAY REQUIRED String",
+            "supply BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+            "transaction_epoch INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CoinInfos, 1, &[
+            "coin_type_hash BYTE_ARRAY REQUIRED String",
+            "coin_type BYTE_ARRAY REQUIRED String",
+            "transaction_version_created INT64 REQUIRED",
+            "creator_address BYTE_ARRAY REQUIRED String",
+            "name BYTE_ARRAY REQUIRED String",
+            "symbol BYTE_ARRAY REQUIRED String",
+            "decimals INT32 REQUIRED",
+            "transaction_created_timestamp INT64 REQUIRED",
+            "supply_aggregator_table_handle BYTE_ARRAY OPTIONAL String",
+            "supply_aggregator_table_key BYTE_ARRAY OPTIONAL String",
+        ]),
+        (ParquetTypeEnum::CoinBalances, 1, &[
+            "txn_version INT64 REQUIRED",
+            "owner_address BYTE_ARRAY REQUIRED String",
+            "coin_type_hash BYTE_ARRAY REQUIRED String",
+            "coin_type BYTE_ARRAY REQUIRED String",
+            "amount BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::WriteSetSize, 1, &[
+            "txn_version INT64 REQUIRED",
+            "change_index INT64 REQUIRED",
+            "key_bytes INT64 REQUIRED",
+            "value_bytes INT64 REQUIRED",
+            "total_bytes INT64 REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::AccountTransactions, 1, &[
+            "txn_version INT64 REQUIRED",
+            "account_address BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CurrentTokenPendingClaims, 1, &[
+            "token_data_id_hash BYTE_ARRAY REQUIRED String",
+            "property_version INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "from_address BYTE_ARRAY REQUIRED String",
+            "to_address BYTE_ARRAY REQUIRED String",
+            "collection_data_id_hash BYTE_ARRAY REQUIRED String",
+            "creator_address BYTE_ARRAY REQUIRED String",
+            "collection_name BYTE_ARRAY REQUIRED String",
+            "name BYTE_ARRAY REQUIRED String",
+            "amount BYTE_ARRAY REQUIRED String",
+            "table_handle BYTE_ARRAY REQUIRED String",
+            "last_transaction_version INT64 REQUIRED",
+            "last_transaction_timestamp INT64 REQUIRED",
+            "token_data_id BYTE_ARRAY REQUIRED String",
+            "collection_id BYTE_ARRAY REQUIRED String",
+        ]),
+        (ParquetTypeEnum::CurrentTokenRoyaltiesV1, 1, &[
+            "token_data_id BYTE_ARRAY REQUIRED String",
+            "payee_address BYTE_ARRAY REQUIRED String",
+            "royalty_points_numerator BYTE_ARRAY REQUIRED String",
+            "royalty_points_denominator BYTE_ARRAY REQUIRED String",
+            "last_transaction_version INT64 REQUIRED",
+            "last_transaction_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CurrentTokenV2Metadata, 1, &[
+            "object_address BYTE_ARRAY REQUIRED String",
+            "resource_type BYTE_ARRAY REQUIRED String",
+            "data BYTE_ARRAY REQUIRED String",
+            "state_key_hash BYTE_ARRAY REQUIRED String",
+            "last_transaction_version INT64 REQUIRED",
+            "last_transaction_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::TokenActivitiesV2, 1, &[
+            "txn_version INT64 REQUIRED",
+            "event_index INT64 REQUIRED",
+            "event_account_address BYTE_ARRAY REQUIRED String",
+            "token_data_id BYTE_ARRAY REQUIRED String",
+            "property_version_v1 INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "event_type BYTE_ARRAY REQUIRED String",
+            "from_address BYTE_ARRAY OPTIONAL String",
+            "to_address BYTE_ARRAY OPTIONAL String",
+            "token_amount BYTE_ARRAY REQUIRED String",
+            "before_value BYTE_ARRAY OPTIONAL String",
+            "after_value BYTE_ARRAY OPTIONAL String",
+            "entry_function_id_str BYTE_ARRAY OPTIONAL String",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "is_fungible_v2 BOOLEAN OPTIONAL",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::TokenDatasV2, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "token_data_id BYTE_ARRAY REQUIRED String",
+            "collection_id BYTE_ARRAY REQUIRED String",
+            "token_name BYTE_ARRAY REQUIRED String",
+            "largest_property_version_v1 BYTE_ARRAY OPTIONAL String",
+            "token_uri BYTE_ARRAY REQUIRED String",
+            "token_properties BYTE_ARRAY REQUIRED String",
+            "description BYTE_ARRAY REQUIRED String",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "is_fungible_v2 BOOLEAN OPTIONAL",
+            "block_timestamp INT64 REQUIRED",
+            "is_deleted_v2 BOOLEAN OPTIONAL",
+        ]),
+        (ParquetTypeEnum::CurrentTokenDatasV2, 1, &[
+            "token_data_id BYTE_ARRAY REQUIRED String",
+            "collection_id BYTE_ARRAY REQUIRED String",
+            "token_name BYTE_ARRAY REQUIRED String",
+            "maximum BYTE_ARRAY OPTIONAL String",
+            "supply BYTE_ARRAY OPTIONAL String",
+            "largest_property_version_v1 BYTE_ARRAY OPTIONAL String",
+            "token_uri BYTE_ARRAY REQUIRED String",
+            "token_properties BYTE_ARRAY REQUIRED String",
+            "description BYTE_ARRAY REQUIRED String",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "is_fungible_v2 BOOLEAN OPTIONAL",
+            "last_transaction_version INT64 REQUIRED",
+            "last_transaction_timestamp INT64 REQUIRED",
+            "decimals INT64 OPTIONAL",
+            "is_deleted_v2 BOOLEAN OPTIONAL",
+        ]),
+        (ParquetTypeEnum::TokenOwnershipsV2, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "token_data_id BYTE_ARRAY REQUIRED String",
+            "property_version_v1 INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "owner_address BYTE_ARRAY OPTIONAL String",
+            "storage_id BYTE_ARRAY REQUIRED String",
+            "amount BYTE_ARRAY REQUIRED String",
+            "table_type_v1 BYTE_ARRAY OPTIONAL String",
+            "token_properties_mutated_v1 BYTE_ARRAY OPTIONAL String",
+            "is_soulbound_v2 BOOLEAN OPTIONAL",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+            "non_transferrable_by_owner BOOLEAN OPTIONAL",
+        ]),
+        (ParquetTypeEnum::CurrentTokenOwnershipsV2, 1, &[
+            "token_data_id BYTE_ARRAY REQUIRED String",
+            "property_version_v1 INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "owner_address BYTE_ARRAY REQUIRED String",
+            "storage_id BYTE_ARRAY REQUIRED String",
+            "amount BYTE_ARRAY REQUIRED String",
+            "table_type_v1 BYTE_ARRAY OPTIONAL String",
+            "token_properties_mutated_v1 BYTE_ARRAY OPTIONAL String",
+            "is_soulbound_v2 BOOLEAN OPTIONAL",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "is_fungible_v2 BOOLEAN OPTIONAL",
+            "last_transaction_version INT64 REQUIRED",
+            "last_transaction_timestamp INT64 REQUIRED",
+            "non_transferrable_by_owner BOOLEAN OPTIONAL",
+        ]),
+        (ParquetTypeEnum::CollectionsV2, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "collection_id BYTE_ARRAY REQUIRED String",
+            "creator_address BYTE_ARRAY REQUIRED String",
+            "collection_name BYTE_ARRAY REQUIRED String",
+            "description BYTE_ARRAY REQUIRED String",
+            "uri BYTE_ARRAY REQUIRED String",
+            "current_supply BYTE_ARRAY REQUIRED String",
+            "max_supply BYTE_ARRAY OPTIONAL String",
+            "total_minted_v2 BYTE_ARRAY OPTIONAL String",
+            "mutable_description BOOLEAN OPTIONAL",
+            "mutable_uri BOOLEAN OPTIONAL",
+            "table_handle_v1 BYTE_ARRAY OPTIONAL String",
+            "collection_properties BYTE_ARRAY OPTIONAL String",
+            "token_standard BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::DelegatedStakingActivities, 1, &[
+            "transaction_version INT64 REQUIRED",
+            "event_index INT64 REQUIRED",
+            "delegator_address BYTE_ARRAY REQUIRED String",
+            "pool_address BYTE_ARRAY REQUIRED String",
+            "event_type BYTE_ARRAY REQUIRED String",
+            "amount BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CurrentDelegatorBalances, 1, &[
+            "delegator_address BYTE_ARRAY REQUIRED String",
+            "pool_address BYTE_ARRAY REQUIRED String",
+            "pool_type BYTE_ARRAY REQUIRED String",
+            "table_handle BYTE_ARRAY REQUIRED String",
+            "last_transaction_version INT64 REQUIRED",
+            "shares BYTE_ARRAY REQUIRED String",
+            "parent_table_handle BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::DelegatorBalances, 1, &[
+            "transaction_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "delegator_address BYTE_ARRAY REQUIRED String",
+            "pool_address BYTE_ARRAY REQUIRED String",
+            "pool_type BYTE_ARRAY REQUIRED String",
+            "table_handle BYTE_ARRAY REQUIRED String",
+            "shares BYTE_ARRAY REQUIRED String",
+            "parent_table_handle BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::ProposalVotes, 1, &[
+            "transaction_version INT64 REQUIRED",
+            "proposal_id INT64 REQUIRED",
+            "voter_address BYTE_ARRAY REQUIRED String",
+            "staking_pool_address BYTE_ARRAY REQUIRED String",
+            "num_votes BYTE_ARRAY REQUIRED String",
+            "should_pass BOOLEAN REQUIRED",
+            "transaction_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CurrentStakingPoolVoter, 1, &[
+            "staking_pool_address BYTE_ARRAY REQUIRED String",
+            "voter_address BYTE_ARRAY REQUIRED String",
+            "last_transaction_version INT64 REQUIRED",
+            "operator_address BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::DelegatedStakingPools, 1, &[
+            "staking_pool_address BYTE_ARRAY REQUIRED String",
+            "first_transaction_version INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::DelegatedStakingPoolBalances, 1, &[
+            "transaction_version INT64 REQUIRED",
+            "staking_pool_address BYTE_ARRAY REQUIRED String",
+            "total_coins BYTE_ARRAY REQUIRED String",
+            "total_shares BYTE_ARRAY REQUIRED String",
+            "operator_commission_percentage BYTE_ARRAY REQUIRED String",
+            "inactive_table_handle BYTE_ARRAY REQUIRED String",
+            "active_table_handle BYTE_ARRAY REQUIRED String",
+        ]),
+        (ParquetTypeEnum::CurrentDelegatedStakingPoolBalances, 1, &[
+            "staking_pool_address BYTE_ARRAY REQUIRED String",
+            "total_coins BYTE_ARRAY REQUIRED String",
+            "total_shares BYTE_ARRAY REQUIRED String",
+            "last_transaction_version INT64 REQUIRED",
+            "operator_commission_percentage BYTE_ARRAY REQUIRED String",
+            "inactive_table_handle BYTE_ARRAY REQUIRED String",
+            "active_table_handle BYTE_ARRAY REQUIRED String",
+        ]),
+        (ParquetTypeEnum::Objects, 1, &[
+            "txn_version INT64 REQUIRED",
+            "write_set_change_index INT64 REQUIRED",
+            "object_address BYTE_ARRAY REQUIRED String",
+            "owner_address BYTE_ARRAY REQUIRED String",
+            "state_key_hash BYTE_ARRAY REQUIRED String",
+            "guid_creation_num INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "allow_ungated_transfer BOOLEAN REQUIRED",
+            "is_deleted BOOLEAN REQUIRED",
+            "untransferrable BOOLEAN REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::CurrentObjects, 1, &[
+            "object_address BYTE_ARRAY REQUIRED String",
+            "owner_address BYTE_ARRAY REQUIRED String",
+            "state_key_hash BYTE_ARRAY REQUIRED String",
+            "allow_ungated_transfer BOOLEAN REQUIRED",
+            "last_guid_creation_num INT64 REQUIRED Integer { bit_width: 64, is_signed: false }",
+            "last_transaction_version INT64 REQUIRED",
+            "is_deleted BOOLEAN REQUIRED",
+            "untransferrable BOOLEAN REQUIRED",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::NftPoints, 1, &[
+            "txn_version INT64 REQUIRED",
+            "event_index INT64 REQUIRED",
+            "owner_address BYTE_ARRAY REQUIRED String",
+            "token_name BYTE_ARRAY REQUIRED String",
+            "point_type BYTE_ARRAY REQUIRED String",
+            "amount BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+        (ParquetTypeEnum::AuthKeyAccountAddresses, 1, &[
+            "auth_key BYTE_ARRAY REQUIRED String",
+            "account_address BYTE_ARRAY REQUIRED String",
+            "last_transaction_version INT64 REQUIRED",
+            "is_auth_key_used BOOLEAN REQUIRED",
+        ]),
+        (ParquetTypeEnum::PublicKeyAuthKeys, 1, &[
+            "public_key BYTE_ARRAY REQUIRED String",
+            "public_key_type BYTE_ARRAY REQUIRED String",
+            "auth_key BYTE_ARRAY REQUIRED String",
+            "account_public_key BYTE_ARRAY REQUIRED String",
+            "is_public_key_used BOOLEAN REQUIRED",
+            "last_transaction_version INT64 REQUIRED",
+            "signature_type BYTE_ARRAY REQUIRED String",
+        ]),
+        (ParquetTypeEnum::GasFees, 1, &[
+            "txn_version INT64 REQUIRED",
+            "owner_address BYTE_ARRAY OPTIONAL String",
+            "amount BYTE_ARRAY OPTIONAL String",
+            "gas_fee_payer_address BYTE_ARRAY OPTIONAL String",
+            "is_transaction_success BOOLEAN REQUIRED",
+            "entry_function_id_str BYTE_ARRAY OPTIONAL String",
+            "block_height INT64 REQUIRED",
+            "storage_refund_amount BYTE_ARRAY REQUIRED String",
+            "block_timestamp INT64 REQUIRED",
+        ]),
+    ];
+
+    #[test]
+    fn test_schema_changes_bump_the_schema_version() {
+        for (parquet_type, table) in ParquetTypeEnum::iter().zip(parquet_table_schemas()) {
+            let mut versions: Vec<(u32, &[&str])> = SCHEMA_SNAPSHOTS
+                .iter()
+                .filter(|(snapshot_type, ..)| *snapshot_type == parquet_type)
+                .map(|(_, version, columns)| (*version, *columns))
+                .collect();
+            versions.sort_by_key(|(version, _)| *version);
+            assert_eq!(
+                versions
+                    .iter()
+                    .map(|(version, _)| *version)
+                    .collect::<Vec<_>>(),
+                (1..=versions.len() as u32).collect::<Vec<_>>(),
+                "Schema versions of {} must be numbered from 1 without gaps",
+                table.table_name
+            );
+
+            let (latest_version, latest_columns) = versions
+                .last()
+                .unwrap_or_else(|| panic!("{} has no schema snapshot", table.table_name));
+            let columns: Vec<String> = table
+                .columns
+                .iter()
+                .map(|column| {
+                    let column_schema = format!(
+                        "{} {} {}",
+                        column.name, column.physical_type, column.repetition
+                    );
+                    match &column.logical_type {
+                        Some(logical_type) => format!("{column_schema} {logical_type}"),
+                        None => column_schema,
+                    }
+                })
+                .collect();
+            assert_eq!(
+                columns,
+                *latest_columns,
+                "The schema of {} changed. Bump SCHEMA_VERSION of its model and add the new \
+                 columns to SCHEMA_SNAPSHOTS as version {}",
+                table.table_name,
+                latest_version + 1
+            );
+            assert_eq!(
+                table.schema_version, *latest_version,
+                "SCHEMA_VERSION of {} doesn't match its latest snapshot",
+                table.table_name
+            );
+        }
+    }
+}
@@ -6,7 +6,11 @@ use crate::{
 use ahash::AHashMap;
 use google_cloud_storage::http::Error as StorageError;
 use lazy_static::lazy_static;
-use parquet::{record::RecordWriter, schema::types::Type};
+use libra2_indexer_processor_sdk::utils::extract::hash_str;
+use parquet::{
+    record::RecordWriter,
+    schema::{printer::print_schema, types::Type},
+};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
@@ -46,6 +50,7 @@ pub struct ParquetProcessingResult {
 pub enum ParquetProcessorError {
     ParquetError(parquet::errors::ParquetError),
     StorageError(StorageError),
+    ObjectStoreError(object_store::Error),
     TimeoutError(tokio::time::error::Elapsed),
     IoError(io::Error),
     Other(String),
@@ -56,6 +61,7 @@ impl std::error::Error for ParquetProcessorError {
         match *self {
             ParquetProcessorError::ParquetError(ref err) => Some(err),
             ParquetProcessorError::StorageError(ref err) => Some(err),
+            ParquetProcessorError::ObjectStoreError(ref err) => Some(err),
             ParquetProcessorError::TimeoutError(ref err) => Some(err),
             ParquetProcessorError::IoError(ref err) => Some(err),
             ParquetProcessorError::Other(_) => None,
@@ -68,6 +74,9 @@ impl Display for ParquetProcessorError {
         match *self {
             ParquetProcessorError::ParquetError(ref err) => write!(f, "Parquet error: {err}"),
             ParquetProcessorError::StorageError(ref err) => write!(f, "Storage error: {err}"),
+            ParquetProcessorError::ObjectStoreError(ref err) => {
+                write!(f, "Object store error: {err}")
+            },
             ParquetProcessorError::TimeoutError(ref err) => write!(f, "Timeout error: {err}"),
             ParquetProcessorError::IoError(ref err) => write!(f, "IO error: {err}"),
             ParquetProcessorError::Other(ref desc) => write!(f, "Error: {desc}"),
@@ -102,20 +111,42 @@ pub trait HasVersion {
     fn version(&self) -> i64;
 }
 
+/// Version of the parquet schema of a model. Bump it whenever a column is added, removed,
+/// renamed or changes type, and add the new schema to the snapshots in `schema_registry`.
+///
+/// The version is part of the upload path and the key-value metadata of every file, so files
+/// with different schemas never share a directory.
+pub trait HasSchemaVersion {
+    const SCHEMA_VERSION: u32;
+}
+
 pub trait HasParquetSchema {
     fn schema() -> Arc<parquet::schema::types::Type>;
+    fn schema_version() -> u32;
 }
 
-/// Auto-implement this for all types that implement `Default` and `RecordWriter`
+/// Auto-implement this for all types that implement `Default`, `RecordWriter` and
+/// `HasSchemaVersion`
 impl<ParquetType> HasParquetSchema for ParquetType
 where
-    ParquetType: std::fmt::Debug + Default + Sync + Send,
+    ParquetType: std::fmt::Debug + Default + Sync + Send + HasSchemaVersion,
     for<'a> &'a [ParquetType]: RecordWriter<ParquetType>,
 {
     fn schema() -> Arc<Type> {
         let example: Self = Default::default();
         [example].as_slice().schema().unwrap()
     }
+
+    fn schema_version() -> u32 {
+        Self::SCHEMA_VERSION
+    }
+}
+
+/// Stable hash of a parquet schema, used to tell files written with different schemas apart.
+pub fn schema_hash(schema: &Type) -> String {
+    let mut printed = Vec::new();
+    print_schema(&mut printed, schema);
+    hash_str(&String::from_utf8_lossy(&printed))
 }
 
 /// Fill the map with data if the table is opted in for backfill-purpose
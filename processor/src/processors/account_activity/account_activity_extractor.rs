@@ -0,0 +1,120 @@
+use crate::processors::{
+    account_activity::account_activity_model::AccountActivity,
+    ans::ans_extractor::parse_ans,
+    fungible_asset::fungible_asset_processor_helpers::parse_v2_coin,
+    token_v2::{
+        token_models::tokens::TableMetadataForToken, token_v2_processor_helpers::parse_v2_token,
+    },
+};
+use async_trait::async_trait;
+use libra2_indexer_processor_sdk::{
+    libra2_protos::transaction::v1::Transaction,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use tracing::error;
+
+/// Turns the fungible asset, token, delegated staking and ANS activities of transactions into
+/// rows of the accounts they involve.
+pub struct AccountActivityExtractor
+where
+    Self: Sized + Send + 'static,
+{
+    ans_v1_primary_names_table_handle: String,
+    ans_v1_name_records_table_handle: String,
+    ans_v2_contract_address: String,
+}
+
+impl AccountActivityExtractor {
+    pub fn new(
+        ans_v1_primary_names_table_handle: String,
+        ans_v1_name_records_table_handle: String,
+        ans_v2_contract_address: String,
+    ) -> Self {
+        Self {
+            ans_v1_primary_names_table_handle,
+            ans_v1_name_records_table_handle,
+            ans_v2_contract_address,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for AccountActivityExtractor {
+    type Input = Vec<Transaction>;
+    type Output = Vec<AccountActivity>;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        transactions: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<Vec<AccountActivity>>>, ProcessorError> {
+        let (fungible_asset_activities, _, _, _, _, _) =
+            parse_v2_coin(&transactions.data, None).await;
+
+        let table_handle_to_owner =
+            TableMetadataForToken::get_table_handle_to_owner_from_transactions(&transactions.data);
+        let (_, _, _, _, _, _, _, _, token_activities_v2, _, _, _) =
+            parse_v2_token(&transactions.data, &table_handle_to_owner, &mut None).await;
+
+        let (_, ans_lookups_v2, _, ans_primary_names_v2, ans_renewal_activities) = parse_ans(
+            &transactions.data,
+            self.ans_v1_primary_names_table_handle.clone(),
+            self.ans_v1_name_records_table_handle.clone(),
+            self.ans_v2_contract_address.clone(),
+        );
+
+        let mut account_activities =
+            AccountActivity::from_fungible_asset_activities(&fungible_asset_activities);
+        account_activities.extend(
+            token_activities_v2
+                .iter()
+                .flat_map(AccountActivity::from_token_activity),
+        );
+        for transaction in &transactions.data {
+            match AccountActivity::from_stake_events(transaction) {
+                Ok(stake_activities) => account_activities.extend(stake_activities),
+                Err(e) => {
+                    error!(
+                        transaction_version = transaction.version,
+                        processor_name = self.name(),
+                        error = ?e,
+                        "[Parser] Error parsing stake events",
+                    );
+                    return Err(ProcessorError::ProcessError {
+                        message: format!("Error parsing stake events: {e:?}"),
+                    });
+                },
+            }
+        }
+        account_activities.extend(
+            ans_lookups_v2
+                .iter()
+                .filter_map(AccountActivity::from_ans_lookup),
+        );
+        account_activities.extend(
+            ans_primary_names_v2
+                .iter()
+                .map(AccountActivity::from_ans_primary_name),
+        );
+        account_activities.extend(
+            ans_renewal_activities
+                .iter()
+                .filter_map(AccountActivity::from_ans_renewal),
+        );
+
+        Ok(Some(TransactionContext {
+            data: account_activities,
+            metadata: transactions.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for AccountActivityExtractor {}
+
+impl NamedStep for AccountActivityExtractor {
+    fn name(&self) -> String {
+        "AccountActivityExtractor".to_string()
+    }
+}
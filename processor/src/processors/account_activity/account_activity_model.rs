@@ -0,0 +1,434 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::{
+    processors::{
+        ans::models::{
+            ans_lookup_v2::AnsLookupV2, ans_primary_name_v2::AnsPrimaryNameV2,
+            ans_renewal_activities::AnsRenewalActivity,
+        },
+        fungible_asset::fungible_asset_models::v2_fungible_asset_activities::FungibleAssetActivity,
+        stake::models::stake_utils::StakeEvent,
+        token_v2::token_v2_models::v2_token_activities::TokenActivityV2,
+    },
+    schema::account_activities,
+};
+use ahash::AHashMap;
+use bigdecimal::BigDecimal;
+use field_count::FieldCount;
+use libra2_indexer_processor_sdk::{
+    libra2_indexer_transaction_stream::utils::time::parse_timestamp,
+    libra2_protos::transaction::v1::{transaction::TxnData, Transaction},
+    utils::convert::{standardize_address, u64_to_bigdecimal},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Formatter};
+
+/// Delegated stake is always in the native coin
+pub const STAKE_ASSET_TYPE: &str = "0x1::libra2_coin::Libra2Coin";
+
+/// What an activity was, from the point of view of the account in the row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountActivityKind {
+    FungibleAssetDeposit,
+    FungibleAssetWithdraw,
+    GasFee,
+    TokenMint,
+    TokenBurn,
+    TokenReceive,
+    TokenSend,
+    TokenOther,
+    StakeAdd,
+    StakeUnlock,
+    StakeWithdraw,
+    StakeReactivate,
+    AnsNameUpdate,
+    AnsNameRenewal,
+    AnsPrimaryNameSet,
+    AnsPrimaryNameClear,
+}
+
+impl fmt::Display for AccountActivityKind {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let res = match self {
+            AccountActivityKind::FungibleAssetDeposit => "fungible_asset_deposit",
+            AccountActivityKind::FungibleAssetWithdraw => "fungible_asset_withdraw",
+            AccountActivityKind::GasFee => "gas_fee",
+            AccountActivityKind::TokenMint => "token_mint",
+            AccountActivityKind::TokenBurn => "token_burn",
+            AccountActivityKind::TokenReceive => "token_receive",
+            AccountActivityKind::TokenSend => "token_send",
+            AccountActivityKind::TokenOther => "token_other",
+            AccountActivityKind::StakeAdd => "stake_add",
+            AccountActivityKind::StakeUnlock => "stake_unlock",
+            AccountActivityKind::StakeWithdraw => "stake_withdraw",
+            AccountActivityKind::StakeReactivate => "stake_reactivate",
+            AccountActivityKind::AnsNameUpdate => "ans_name_update",
+            AccountActivityKind::AnsNameRenewal => "ans_name_renewal",
+            AccountActivityKind::AnsPrimaryNameSet => "ans_primary_name_set",
+            AccountActivityKind::AnsPrimaryNameClear => "ans_primary_name_clear",
+        };
+        write!(f, "{res}")
+    }
+}
+
+/// One row of an account's activity feed. `activity_index` is the event index of the activity, or
+/// the write set change index for ANS name changes, so rows are unique per
+/// (account, version, kind, index).
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(account_address, transaction_version, activity_kind, activity_index))]
+#[diesel(table_name = account_activities)]
+pub struct AccountActivity {
+    pub transaction_version: i64,
+    pub account_address: String,
+    pub activity_kind: String,
+    pub activity_index: i64,
+    pub counterparty_address: Option<String>,
+    /// Asset type of a fungible asset, token data id of a token or the name of an ANS name
+    pub asset: Option<String>,
+    pub amount: Option<BigDecimal>,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+}
+
+impl AccountActivity {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        transaction_version: i64,
+        account_address: &str,
+        kind: AccountActivityKind,
+        activity_index: i64,
+        counterparty_address: Option<String>,
+        asset: Option<String>,
+        amount: Option<BigDecimal>,
+        transaction_timestamp: chrono::NaiveDateTime,
+    ) -> Self {
+        Self {
+            transaction_version,
+            account_address: standardize_address(account_address),
+            activity_kind: kind.to_string(),
+            activity_index,
+            counterparty_address: counterparty_address.map(|a| standardize_address(&a)),
+            asset,
+            amount,
+            transaction_timestamp,
+        }
+    }
+
+    /// Deposits, withdrawals and gas fees. Frozen events and activities without an owner are
+    /// skipped. When a transaction moves an asset with exactly one withdrawal and one deposit of
+    /// the same amount, the two owners are each other's counterparty.
+    pub fn from_fungible_asset_activities(activities: &[FungibleAssetActivity]) -> Vec<Self> {
+        let mut rows = vec![];
+        for activity in activities {
+            let owner_address = match activity.owner_address.as_ref() {
+                Some(owner_address) => owner_address,
+                None => continue,
+            };
+            let kind = if activity.is_gas_fee {
+                AccountActivityKind::GasFee
+            } else if activity.event_type.contains("Deposit") {
+                AccountActivityKind::FungibleAssetDeposit
+            } else if activity.event_type.contains("Withdraw") {
+                AccountActivityKind::FungibleAssetWithdraw
+            } else {
+                continue;
+            };
+            rows.push(Self::new(
+                activity.transaction_version,
+                owner_address,
+                kind,
+                activity.event_index,
+                None,
+                activity.asset_type.clone(),
+                activity.amount.clone(),
+                activity.transaction_timestamp,
+            ));
+        }
+        pair_fungible_asset_counterparties(&mut rows);
+        rows
+    }
+
+    /// A row for the sender and a row for the receiver of a token activity, when they're known.
+    pub fn from_token_activity(activity: &TokenActivityV2) -> Vec<Self> {
+        // e.g. 0x3::token::MintTokenEvent or 0x4::collection::Mint
+        let event_name = activity.type_.rsplit("::").next().unwrap_or_default();
+        let from = activity.from_address.as_deref();
+        let to = activity.to_address.as_deref();
+        let entries = if event_name.contains("Mint") {
+            vec![(to.or(from), AccountActivityKind::TokenMint, None)]
+        } else if event_name.contains("Burn") {
+            vec![(from, AccountActivityKind::TokenBurn, None)]
+        } else if event_name.contains("Deposit") {
+            vec![(to, AccountActivityKind::TokenReceive, from)]
+        } else if event_name.contains("Withdraw") {
+            vec![(from, AccountActivityKind::TokenSend, to)]
+        } else if event_name.contains("Transfer") {
+            vec![
+                (from, AccountActivityKind::TokenSend, to),
+                (to, AccountActivityKind::TokenReceive, from),
+            ]
+        } else {
+            vec![
+                (from, AccountActivityKind::TokenOther, to),
+                (
+                    to.filter(|to| Some(*to) != from),
+                    AccountActivityKind::TokenOther,
+                    from,
+                ),
+            ]
+        };
+        entries
+            .into_iter()
+            .filter_map(|(account_address, kind, counterparty_address)| {
+                account_address.map(|account_address| {
+                    Self::new(
+                        activity.transaction_version,
+                        account_address,
+                        kind,
+                        activity.event_index,
+                        counterparty_address.map(str::to_string),
+                        Some(activity.token_data_id.clone()),
+                        Some(activity.token_amount.clone()),
+                        activity.transaction_timestamp,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Delegated staking activities of delegators. The pool is the counterparty.
+    pub fn from_stake_events(transaction: &Transaction) -> anyhow::Result<Vec<Self>> {
+        let events = match transaction.txn_data.as_ref() {
+            Some(TxnData::User(txn)) => &txn.events,
+            _ => return Ok(vec![]),
+        };
+        let txn_version = transaction.version as i64;
+        let txn_timestamp =
+            parse_timestamp(transaction.timestamp.as_ref().unwrap(), txn_version).naive_utc();
+
+        let mut rows = vec![];
+        for (index, event) in events.iter().enumerate() {
+            let (kind, delegator_address, pool_address, amount) =
+                match StakeEvent::from_event(event.type_str.as_str(), &event.data, txn_version)? {
+                    Some(StakeEvent::AddStakeEvent(inner)) => (
+                        AccountActivityKind::StakeAdd,
+                        inner.delegator_address,
+                        inner.pool_address,
+                        inner.amount_added,
+                    ),
+                    Some(StakeEvent::UnlockStakeEvent(inner)) => (
+                        AccountActivityKind::StakeUnlock,
+                        inner.delegator_address,
+                        inner.pool_address,
+                        inner.amount_unlocked,
+                    ),
+                    Some(StakeEvent::WithdrawStakeEvent(inner)) => (
+                        AccountActivityKind::StakeWithdraw,
+                        inner.delegator_address,
+                        inner.pool_address,
+                        inner.amount_withdrawn,
+                    ),
+                    Some(StakeEvent::ReactivateStakeEvent(inner)) => (
+                        AccountActivityKind::StakeReactivate,
+                        inner.delegator_address,
+                        inner.pool_address,
+                        inner.amount_reactivated,
+                    ),
+                    _ => continue,
+                };
+            rows.push(Self::new(
+                txn_version,
+                &delegator_address,
+                kind,
+                index as i64,
+                Some(pool_address),
+                Some(STAKE_ASSET_TYPE.to_string()),
+                Some(u64_to_bigdecimal(amount)),
+                txn_timestamp,
+            ));
+        }
+        Ok(rows)
+    }
+
+    /// Registrations and changes of a name, for the address the name points to.
+    pub fn from_ans_lookup(lookup: &AnsLookupV2) -> Option<Self> {
+        let registered_address = lookup.registered_address.as_ref()?;
+        Some(Self::new(
+            lookup.transaction_version,
+            registered_address,
+            AccountActivityKind::AnsNameUpdate,
+            lookup.write_set_change_index,
+            None,
+            Some(lookup.token_name.clone()),
+            None,
+            lookup.block_timestamp,
+        ))
+    }
+
+    pub fn from_ans_primary_name(primary_name: &AnsPrimaryNameV2) -> Self {
+        let kind = if primary_name.is_deleted {
+            AccountActivityKind::AnsPrimaryNameClear
+        } else {
+            AccountActivityKind::AnsPrimaryNameSet
+        };
+        Self::new(
+            primary_name.transaction_version,
+            &primary_name.registered_address,
+            kind,
+            primary_name.write_set_change_index,
+            None,
+            primary_name.token_name.clone(),
+            None,
+            primary_name.transaction_timestamp,
+        )
+    }
+
+    pub fn from_ans_renewal(renewal: &AnsRenewalActivity) -> Option<Self> {
+        let registered_address = renewal.registered_address.as_ref()?;
+        Some(Self::new(
+            renewal.transaction_version,
+            registered_address,
+            AccountActivityKind::AnsNameRenewal,
+            renewal.event_index,
+            None,
+            Some(renewal.token_name.clone()),
+            None,
+            renewal.block_timestamp,
+        ))
+    }
+}
+
+/// Sets the counterparty of simple transfers, where a transaction withdraws an asset once and
+/// deposits the same amount once. Anything else, like swaps or airdrops, is left without one.
+fn pair_fungible_asset_counterparties(rows: &mut [AccountActivity]) {
+    let mut transfers: AHashMap<(i64, Option<String>), (Vec<usize>, Vec<usize>)> = AHashMap::new();
+    for (index, row) in rows.iter().enumerate() {
+        let entry = transfers
+            .entry((row.transaction_version, row.asset.clone()))
+            .or_default();
+        if row.activity_kind == AccountActivityKind::FungibleAssetWithdraw.to_string() {
+            entry.0.push(index);
+        } else if row.activity_kind == AccountActivityKind::FungibleAssetDeposit.to_string() {
+            entry.1.push(index);
+        }
+    }
+    for (withdrawals, deposits) in transfers.into_values() {
+        if let ([withdrawal], [deposit]) = (withdrawals.as_slice(), deposits.as_slice()) {
+            if rows[*withdrawal].amount == rows[*deposit].amount {
+                rows[*withdrawal].counterparty_address =
+                    Some(rows[*deposit].account_address.clone());
+                rows[*deposit].counterparty_address =
+                    Some(rows[*withdrawal].account_address.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "0x000000000000000000000000000000000000000000000000000000000000a11c";
+    const BOB: &str = "0x0000000000000000000000000000000000000000000000000000000000000b0b";
+
+    fn fa_activity(
+        event_index: i64,
+        owner_address: &str,
+        event_type: &str,
+        amount: u64,
+    ) -> FungibleAssetActivity {
+        FungibleAssetActivity {
+            transaction_version: 1,
+            event_index,
+            owner_address: Some(owner_address.to_string()),
+            storage_id: "0x1".to_string(),
+            asset_type: Some("0xa".to_string()),
+            is_frozen: None,
+            amount: Some(BigDecimal::from(amount)),
+            event_type: event_type.to_string(),
+            is_gas_fee: false,
+            gas_fee_payer_address: None,
+            is_transaction_success: true,
+            entry_function_id_str: None,
+            block_height: 1,
+            token_standard: "v2".to_string(),
+            transaction_timestamp: chrono::NaiveDateTime::default(),
+            storage_refund_amount: BigDecimal::from(0),
+        }
+    }
+
+    fn token_activity(type_: &str, from: Option<&str>, to: Option<&str>) -> TokenActivityV2 {
+        TokenActivityV2 {
+            transaction_version: 1,
+            event_index: 0,
+            event_account_address: ALICE.to_string(),
+            token_data_id: "0x70c3".to_string(),
+            property_version_v1: BigDecimal::from(0),
+            type_: type_.to_string(),
+            from_address: from.map(str::to_string),
+            to_address: to.map(str::to_string),
+            token_amount: BigDecimal::from(1),
+            before_value: None,
+            after_value: None,
+            entry_function_id_str: None,
+            token_standard: "v2".to_string(),
+            is_fungible_v2: None,
+            transaction_timestamp: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_transfer_pairs_counterparties() {
+        let rows = AccountActivity::from_fungible_asset_activities(&[
+            fa_activity(0, ALICE, "0x1::fungible_asset::Withdraw", 10),
+            fa_activity(1, BOB, "0x1::fungible_asset::Deposit", 10),
+        ]);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].activity_kind, "fungible_asset_withdraw");
+        assert_eq!(rows[0].counterparty_address.as_deref(), Some(BOB));
+        assert_eq!(rows[1].activity_kind, "fungible_asset_deposit");
+        assert_eq!(rows[1].counterparty_address.as_deref(), Some(ALICE));
+    }
+
+    #[test]
+    fn test_ambiguous_transfer_has_no_counterparty() {
+        let rows = AccountActivity::from_fungible_asset_activities(&[
+            fa_activity(0, ALICE, "0x1::fungible_asset::Withdraw", 10),
+            fa_activity(1, BOB, "0x1::fungible_asset::Deposit", 4),
+            fa_activity(2, ALICE, "0x1::fungible_asset::Deposit", 6),
+            fa_activity(3, ALICE, "0x1::fungible_asset::Frozen", 0),
+        ]);
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().all(|row| row.counterparty_address.is_none()));
+    }
+
+    #[test]
+    fn test_token_transfer_has_a_row_per_side() {
+        let rows = AccountActivity::from_token_activity(&token_activity(
+            "0x1::object::TransferEvent",
+            Some(ALICE),
+            Some(BOB),
+        ));
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].account_address, ALICE);
+        assert_eq!(rows[0].activity_kind, "token_send");
+        assert_eq!(rows[0].counterparty_address.as_deref(), Some(BOB));
+        assert_eq!(rows[1].account_address, BOB);
+        assert_eq!(rows[1].activity_kind, "token_receive");
+    }
+
+    #[test]
+    fn test_token_mint_goes_to_the_receiver() {
+        let rows = AccountActivity::from_token_activity(&token_activity(
+            "0x3::token::MintTokenEvent",
+            Some(ALICE),
+            Some(BOB),
+        ));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].account_address, BOB);
+        assert_eq!(rows[0].activity_kind, "token_mint");
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::IndexerProcessorConfig,
+        processor_config::{DefaultProcessorConfig, ProcessorConfig},
+    },
+    processors::{
+        account_activity::{
+            account_activity_extractor::AccountActivityExtractor,
+            account_activity_storer::AccountActivityStorer,
+        },
+        processor_status_saver::{
+            get_end_version, get_starting_version, PostgresProcessorStatusSaver,
+        },
+    },
+    utils::table_flags::TableFlags,
+    MIGRATIONS,
+};
+use anyhow::Result;
+use libra2_indexer_processor_sdk::{
+    builder::ProcessorBuilder,
+    common_steps::{
+        TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+    },
+    libra2_indexer_transaction_stream::TransactionStreamConfig,
+    postgres::utils::{
+        checkpoint::PostgresChainIdChecker,
+        database::{new_db_pool, run_migrations, ArcDbPool},
+    },
+    traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    utils::chain_id_check::check_or_update_chain_id,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountActivityProcessorConfig {
+    #[serde(flatten)]
+    pub default_config: DefaultProcessorConfig,
+    // Same as the ANS processor, to parse ANS activity
+    pub ans_v1_primary_names_table_handle: String,
+    pub ans_v1_name_records_table_handle: String,
+    pub ans_v2_contract_address: String,
+}
+
+pub struct AccountActivityProcessor {
+    pub config: IndexerProcessorConfig,
+    pub db_pool: ArcDbPool,
+}
+
+impl AccountActivityProcessor {
+    pub async fn new(config: IndexerProcessorConfig) -> Result<Self> {
+        match config.db_config {
+            DbConfig::PostgresConfig(ref postgres_config) => {
+                let conn_pool = new_db_pool(
+                    &postgres_config.connection_string,
+                    Some(postgres_config.db_pool_size),
+                )
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to create connection pool for PostgresConfig: {:?}",
+                        e
+                    )
+                })?;
+
+                Ok(Self {
+                    config,
+                    db_pool: conn_pool,
+                })
+            },
+            _ => Err(anyhow::anyhow!(
+                "Invalid db config for AccountActivityProcessor {:?}",
+                config.db_config
+            )),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for AccountActivityProcessor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    /// Writes the fungible asset, token, delegated staking and ANS activity of each account to
+    /// `account_activities`.
+    async fn run_processor(&self) -> Result<()> {
+        let processor_config = match &self.config.processor_config {
+            ProcessorConfig::AccountActivityProcessor(processor_config) => processor_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor config for AccountActivityProcessor: {:?}",
+                    self.config.processor_config
+                ))
+            },
+        };
+
+        // Run migrations
+        if let DbConfig::PostgresConfig(ref postgres_config) = self.config.db_config {
+            run_migrations(
+                postgres_config.connection_string.clone(),
+                self.db_pool.clone(),
+                MIGRATIONS,
+            )
+            .await;
+        }
+
+        // Merge the starting version from config and the latest processed version from the DB
+        let (starting_version, ending_version) = (
+            get_starting_version(&self.config, self.db_pool.clone()).await?,
+            get_end_version(&self.config, self.db_pool.clone()).await?,
+        );
+
+        // Check and update the ledger chain id to ensure we're indexing the correct chain
+        check_or_update_chain_id(
+            &self.config.transaction_stream_config,
+            &PostgresChainIdChecker::new(self.db_pool.clone()),
+        )
+        .await?;
+
+        let channel_size = processor_config.default_config.channel_size;
+
+        // Define processor steps
+        let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+            starting_version,
+            request_ending_version: ending_version,
+            ..self.config.transaction_stream_config.clone()
+        })
+        .await?;
+        let opt_in_tables = TableFlags::from_set(&processor_config.default_config.tables_to_write);
+        let account_activity_extractor = AccountActivityExtractor::new(
+            processor_config.ans_v1_primary_names_table_handle.clone(),
+            processor_config.ans_v1_name_records_table_handle.clone(),
+            processor_config.ans_v2_contract_address.clone(),
+        );
+        let account_activity_storer = AccountActivityStorer::new(
+            self.db_pool.clone(),
+            processor_config.default_config.clone(),
+            opt_in_tables,
+        );
+        let version_tracker = VersionTrackerStep::new(
+            PostgresProcessorStatusSaver::new(self.config.clone(), self.db_pool.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        // Connect processor steps together
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+            transaction_stream.into_runnable_step(),
+        )
+        .connect_to(
+            account_activity_extractor.into_runnable_step(),
+            channel_size,
+        )
+        .connect_to(account_activity_storer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+
+        // (Optional) Parse the results
+        loop {
+            match buffer_receiver.recv().await {
+                Ok(txn_context) => {
+                    debug!(
+                        "Finished processing account activities from versions [{:?}, {:?}]",
+                        txn_context.metadata.start_version, txn_context.metadata.end_version,
+                    );
+                },
+                Err(e) => {
+                    info!("No more transactions in channel: {:?}", e);
+                    break Ok(());
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{
+    config::processor_config::DefaultProcessorConfig,
+    processors::account_activity::account_activity_model::AccountActivity,
+    schema,
+    utils::table_flags::{filter_data, TableFlags},
+};
+use ahash::AHashMap;
+use anyhow::Result;
+use async_trait::async_trait;
+use diesel::{pg::Pg, query_builder::QueryFragment};
+use libra2_indexer_processor_sdk::{
+    postgres::utils::database::{execute_in_chunks, get_config_table_chunk_size, ArcDbPool},
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+
+pub struct AccountActivityStorer
+where
+    Self: Sized + Send + 'static,
+{
+    conn_pool: ArcDbPool,
+    processor_config: DefaultProcessorConfig,
+    tables_to_write: TableFlags,
+}
+
+impl AccountActivityStorer {
+    pub fn new(
+        conn_pool: ArcDbPool,
+        processor_config: DefaultProcessorConfig,
+        tables_to_write: TableFlags,
+    ) -> Self {
+        Self {
+            conn_pool,
+            processor_config,
+            tables_to_write,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for AccountActivityStorer {
+    type Input = Vec<AccountActivity>;
+    type Output = ();
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        input: TransactionContext<Vec<AccountActivity>>,
+    ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
+        let per_table_chunk_sizes: AHashMap<String, usize> =
+            self.processor_config.per_table_chunk_sizes.clone();
+
+        let account_activities = filter_data(
+            &self.tables_to_write,
+            TableFlags::ACCOUNT_ACTIVITIES,
+            input.data,
+        );
+
+        let aa = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_account_activities_query,
+            &account_activities,
+            get_config_table_chunk_size::<AccountActivity>(
+                "account_activities",
+                &per_table_chunk_sizes,
+            ),
+        );
+
+        match aa.await {
+            Ok(_) => {},
+            Err(e) => {
+                return Err(ProcessorError::DBStoreError {
+                    message: format!(
+                        "Failed to store versions {} to {}: {:?}",
+                        input.metadata.start_version, input.metadata.end_version, e,
+                    ),
+                    query: None,
+                })
+            },
+        }
+
+        Ok(Some(TransactionContext {
+            data: (),
+            metadata: input.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for AccountActivityStorer {}
+
+impl NamedStep for AccountActivityStorer {
+    fn name(&self) -> String {
+        "AccountActivityStorer".to_string()
+    }
+}
+
+fn insert_account_activities_query(
+    items_to_insert: Vec<AccountActivity>,
+) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
+    use schema::account_activities::dsl::*;
+
+    diesel::insert_into(schema::account_activities::table)
+        .values(items_to_insert)
+        .on_conflict((
+            account_address,
+            transaction_version,
+            activity_kind,
+            activity_index,
+        ))
+        .do_nothing()
+}
@@ -0,0 +1,4 @@
+pub mod account_activity_extractor;
+pub mod account_activity_model;
+pub mod account_activity_processor;
+pub mod account_activity_storer;
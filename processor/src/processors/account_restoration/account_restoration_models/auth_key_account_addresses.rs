@@ -1,8 +1,13 @@
 // Copyright © A-p-t-o-s Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::schema::auth_key_account_addresses;
+use crate::{
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
+    schema::auth_key_account_addresses,
+};
+use allocative_derive::Allocative;
 use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
 use serde::{Deserialize, Serialize};
 
 #[derive(
@@ -43,3 +48,39 @@ impl PartialOrd for AuthKeyAccountAddress {
         Some(self.cmp(other))
     }
 }
+
+/// This is a parquet version of AuthKeyAccountAddress
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetAuthKeyAccountAddress {
+    pub auth_key: String,
+    pub account_address: String,
+    pub last_transaction_version: i64,
+    pub is_auth_key_used: bool,
+}
+
+impl NamedTable for ParquetAuthKeyAccountAddress {
+    const TABLE_NAME: &'static str = "auth_key_account_addresses";
+}
+
+impl HasSchemaVersion for ParquetAuthKeyAccountAddress {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetAuthKeyAccountAddress {
+    fn version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
+impl From<AuthKeyAccountAddress> for ParquetAuthKeyAccountAddress {
+    fn from(raw: AuthKeyAccountAddress) -> Self {
+        Self {
+            auth_key: raw.auth_key,
+            account_address: raw.account_address,
+            last_transaction_version: raw.last_transaction_version,
+            is_auth_key_used: raw.is_auth_key_used,
+        }
+    }
+}
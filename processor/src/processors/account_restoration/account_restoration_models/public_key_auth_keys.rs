@@ -3,6 +3,7 @@
 
 use super::account_restoration_utils::KeyRotationToPublicKeyEvent;
 use crate::{
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::user_transaction::models::signature_utils::{
         account_signature_utils::{
             get_account_signature_type_from_enum, get_public_key_indices_from_multi_key_signature,
@@ -13,6 +14,7 @@ use crate::{
     schema::public_key_auth_keys,
 };
 use ahash::AHashMap;
+use allocative_derive::Allocative;
 use libra2_indexer_processor_sdk::libra2_protos::transaction::v1::{
     account_signature::{Signature as AccountSignature, Type as AccountSignatureTypeEnum},
     any_public_key::Type as AnyPublicKeyEnum,
@@ -20,6 +22,7 @@ use libra2_indexer_processor_sdk::libra2_protos::transaction::v1::{
     AnyPublicKey, MultiEd25519Signature, MultiKeySignature, Signature,
 };
 use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
 use serde::{Deserialize, Serialize};
 
 pub type PublicKeyAuthKeyMapping = AHashMap<(String, String, String, bool), PublicKeyAuthKey>;
@@ -277,6 +280,48 @@ impl PartialOrd for PublicKeyAuthKey {
     }
 }
 
+/// This is a parquet version of PublicKeyAuthKey
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetPublicKeyAuthKey {
+    pub public_key: String,
+    pub public_key_type: String,
+    pub auth_key: String,
+    pub account_public_key: String,
+    pub is_public_key_used: bool,
+    pub last_transaction_version: i64,
+    pub signature_type: String,
+}
+
+impl NamedTable for ParquetPublicKeyAuthKey {
+    const TABLE_NAME: &'static str = "public_key_auth_keys";
+}
+
+impl HasSchemaVersion for ParquetPublicKeyAuthKey {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetPublicKeyAuthKey {
+    fn version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
+impl From<PublicKeyAuthKey> for ParquetPublicKeyAuthKey {
+    fn from(raw: PublicKeyAuthKey) -> Self {
+        Self {
+            public_key: raw.public_key,
+            public_key_type: raw.public_key_type,
+            auth_key: raw.auth_key,
+            account_public_key: raw.account_public_key,
+            is_public_key_used: raw.is_public_key_used,
+            last_transaction_version: raw.last_transaction_version,
+            signature_type: raw.signature_type,
+        }
+    }
+}
+
 // Below are just types and convenience functions for the multi key deserialization.
 // Ideally we would use aptos-crypto or aptos-types to deserialize these types, but
 // there is a blocking incompatible dependency.
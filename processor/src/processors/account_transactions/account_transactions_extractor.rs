@@ -1,8 +1,10 @@
 use crate::processors::account_transactions::{
-    account_transactions_model::PostgresAccountTransaction, parse_account_transactions,
+    account_transactions_model::{AccountOwners, PostgresAccountTransaction},
+    parse_account_transactions,
 };
 use libra2_indexer_processor_sdk::{
    libra2_protos::transaction::v1::Transaction,
+    postgres::utils::database::ArcDbPool,
     traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
     types::transaction_context::TransactionContext,
     utils::errors::ProcessorError,
@@ -11,7 +13,16 @@ use async_trait::async_trait;
 
 pub struct AccountTransactionsExtractor
 where
-    Self: Sized + Send + 'static, {}
+    Self: Sized + Send + 'static,
+{
+    conn_pool: ArcDbPool,
+}
+
+impl AccountTransactionsExtractor {
+    pub fn new(conn_pool: ArcDbPool) -> Self {
+        Self { conn_pool }
+    }
+}
 
 #[async_trait]
 impl Processable for AccountTransactionsExtractor {
@@ -23,10 +34,27 @@ impl Processable for AccountTransactionsExtractor {
         &mut self,
         input: TransactionContext<Vec<Transaction>>,
     ) -> Result<Option<TransactionContext<Vec<PostgresAccountTransaction>>>, ProcessorError> {
-        let acc_txns: Vec<PostgresAccountTransaction> = parse_account_transactions(input.data)
-            .into_iter()
-            .map(PostgresAccountTransaction::from)
-            .collect();
+        let mut conn = self
+            .conn_pool
+            .get()
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to get connection from pool: {e:?}"),
+                query: None,
+            })?;
+        let mut owners = AccountOwners::from_transactions(&input.data);
+        owners
+            .load_from_db(&input.data, &mut conn)
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to load object and table owners: {e:?}"),
+                query: None,
+            })?;
+        let acc_txns: Vec<PostgresAccountTransaction> =
+            parse_account_transactions(input.data, &owners)
+                .into_iter()
+                .map(PostgresAccountTransaction::from)
+                .collect();
         Ok(Some(TransactionContext {
             data: acc_txns,
             metadata: input.metadata,
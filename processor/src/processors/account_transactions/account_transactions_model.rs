@@ -7,19 +7,21 @@
 
 use crate::{
     db::resources::FromWriteResource,
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
-        objects::v2_object_utils::ObjectWithMetadata,
+        default::models::table_items::{PostgresTableMetadata, TableMetadata},
+        objects::{v2_object_utils::ObjectWithMetadata, v2_objects_models::CurrentObjectQuery},
         user_transaction::models::user_transactions::UserTransaction,
     },
     schema::account_transactions,
-    utils::counters::PROCESSOR_UNKNOWN_TYPE_COUNT,
+    utils::counters::{ACCOUNT_TRANSACTIONS_UNRESOLVED_OWNER_COUNT, PROCESSOR_UNKNOWN_TYPE_COUNT},
 };
-use ahash::AHashSet;
+use ahash::{AHashMap, AHashSet};
 use allocative_derive::Allocative;
 use libra2_indexer_processor_sdk::{
     libra2_indexer_transaction_stream::utils::time::parse_timestamp,
    libra2_protos::transaction::v1::{transaction::TxnData, write_set_change::Change, Transaction},
+    postgres::utils::database::DbPoolConnection,
     utils::convert::standardize_address,
 };
 use field_count::FieldCount;
@@ -28,6 +30,175 @@ use serde::{Deserialize, Serialize};
 
 pub type AccountTransactionPK = (String, i64);
 
+/// Maximum number of objects we walk up before giving up on an ownership chain.
+const MAX_OBJECT_OWNER_DEPTH: usize = 8;
+
+/// Owners used to attribute object resources and table items to accounts.
+#[derive(Clone, Debug, Default)]
+pub struct AccountOwners {
+    /// Object address -> owner address, from `ObjectCore`
+    object_owners: AHashMap<String, String>,
+    /// Table handle -> address of the resource holding the table
+    table_owners: AHashMap<String, String>,
+}
+
+impl AccountOwners {
+    /// Owners from the batch alone. This is the state at the end of the batch, so an object
+    /// transferred within the batch is attributed to its latest owner.
+    pub fn from_transactions(transactions: &[Transaction]) -> Self {
+        let mut object_owners = AHashMap::new();
+        for transaction in transactions {
+            let Some(info) = transaction.info.as_ref() else {
+                continue;
+            };
+            for wsc in &info.changes {
+                if let Some(Change::WriteResource(res)) = wsc.change.as_ref() {
+                    if let Some(inner) = ObjectWithMetadata::from_write_resource(res).unwrap() {
+                        object_owners.insert(
+                            standardize_address(res.address.as_str()),
+                            inner.object_core.get_owner_address(),
+                        );
+                    }
+                }
+            }
+        }
+        Self {
+            object_owners,
+            table_owners: TableMetadata::get_table_handle_to_owner_from_transactions(transactions),
+        }
+    }
+
+    /// Fills in what the batch doesn't know from `table_metadatas` and `current_objects`.
+    /// Object owners are loaded one level per query, up to `MAX_OBJECT_OWNER_DEPTH` levels.
+    pub async fn load_from_db(
+        &mut self,
+        transactions: &[Transaction],
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<()> {
+        let mut addresses = AHashSet::new();
+        let mut missing_handles = AHashSet::new();
+        for transaction in transactions {
+            let Some(info) = transaction.info.as_ref() else {
+                continue;
+            };
+            for wsc in &info.changes {
+                match wsc.change.as_ref() {
+                    Some(Change::WriteResource(res)) => {
+                        addresses.insert(standardize_address(res.address.as_str()));
+                    },
+                    Some(Change::DeleteResource(res)) => {
+                        addresses.insert(standardize_address(res.address.as_str()));
+                    },
+                    // table_metadatas keeps the handle as it comes from the transaction
+                    Some(Change::WriteTableItem(item)) => {
+                        if !self
+                            .table_owners
+                            .contains_key(&standardize_address(item.handle.as_str()))
+                        {
+                            missing_handles.insert(item.handle.clone());
+                        }
+                    },
+                    Some(Change::DeleteTableItem(item)) => {
+                        if !self
+                            .table_owners
+                            .contains_key(&standardize_address(item.handle.as_str()))
+                        {
+                            missing_handles.insert(item.handle.clone());
+                        }
+                    },
+                    _ => {},
+                }
+            }
+        }
+
+        if !missing_handles.is_empty() {
+            let missing_handles = missing_handles.into_iter().collect::<Vec<_>>();
+            for (handle, owner_address) in
+                PostgresTableMetadata::get_owners_by_handles(&missing_handles, conn).await?
+            {
+                self.table_owners
+                    .insert(standardize_address(&handle), owner_address);
+            }
+        }
+
+        addresses.extend(self.table_owners.values().cloned());
+        addresses.extend(self.object_owners.values().cloned());
+        let mut queried = AHashSet::new();
+        let mut candidates = addresses
+            .into_iter()
+            .filter(|address| !self.object_owners.contains_key(address))
+            .collect::<Vec<_>>();
+        for _ in 0..MAX_OBJECT_OWNER_DEPTH {
+            if candidates.is_empty() {
+                break;
+            }
+            queried.extend(candidates.iter().cloned());
+            let rows = CurrentObjectQuery::get_owners_by_addresses(&candidates, conn).await?;
+            candidates = rows
+                .iter()
+                .map(|(_, owner_address)| owner_address)
+                .filter(|owner_address| {
+                    !self.object_owners.contains_key(*owner_address)
+                        && !queried.contains(*owner_address)
+                })
+                .cloned()
+                .collect::<AHashSet<_>>()
+                .into_iter()
+                .collect();
+            for (object_address, owner_address) in rows {
+                self.object_owners
+                    .entry(object_address)
+                    .or_insert(owner_address);
+            }
+        }
+        Ok(())
+    }
+
+    /// Records every owner above `address`, stopping after `MAX_OBJECT_OWNER_DEPTH` objects.
+    fn insert_owners(&self, address: &str, accounts: &mut AHashSet<String>) {
+        let mut current = address;
+        for _ in 0..MAX_OBJECT_OWNER_DEPTH {
+            match self.object_owners.get(current) {
+                Some(owner_address) if owner_address != current => {
+                    accounts.insert(owner_address.clone());
+                    current = owner_address;
+                },
+                _ => return,
+            }
+        }
+        if self.object_owners.contains_key(current) {
+            ACCOUNT_TRANSACTIONS_UNRESOLVED_OWNER_COUNT
+                .with_label_values(&["max_depth"])
+                .inc();
+        }
+    }
+
+    /// Records the holder of the table and every owner above it. Tables held by framework
+    /// addresses, like coin supply aggregators, are written for every account, so they're skipped.
+    fn insert_table_owners(&self, handle: &str, accounts: &mut AHashSet<String>) {
+        match self.table_owners.get(&standardize_address(handle)) {
+            Some(owner_address) if is_reserved_address(owner_address) => {},
+            Some(owner_address) => {
+                accounts.insert(owner_address.clone());
+                self.insert_owners(owner_address, accounts);
+            },
+            None => {
+                ACCOUNT_TRANSACTIONS_UNRESOLVED_OWNER_COUNT
+                    .with_label_values(&["table_handle"])
+                    .inc();
+            },
+        }
+    }
+}
+
+/// Whether the address is one of the framework addresses, 0x0 to 0xf.
+fn is_reserved_address(address: &str) -> bool {
+    let address = standardize_address(address);
+    address[2..address.len() - 1]
+        .bytes()
+        .all(|byte| byte == b'0')
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AccountTransaction {
     pub transaction_version: i64,
@@ -39,11 +210,10 @@ impl AccountTransaction {
     /// This table will record every transaction that touch an account which could be
     /// a user account, an object, or a resource account.
     /// We will consider all transactions that modify a resource or event associated with a particular account.
-    /// We will do 1 level of redirection for now (e.g. if it's an object, we will record the owner as account address).
+    /// If it's an object, we will record every owner up the chain (see `MAX_OBJECT_OWNER_DEPTH`),
+    /// and table items are recorded against the account holding the table, using `owners`.
     /// We will also consider transactions that the account signed or is part of a multi sig / multi agent.
-    /// TODO: recursively find the parent account of an object
-    /// TODO: include table items in the detection path
-    pub fn get_accounts(transaction: &Transaction) -> AHashSet<String> {
+    pub fn get_accounts(transaction: &Transaction, owners: &AccountOwners) -> AHashSet<String> {
         let txn_version = transaction.version as i64;
         let txn_data = match transaction.txn_data.as_ref() {
             Some(data) => data,
@@ -97,20 +267,31 @@ impl AccountTransaction {
         for wsc in wscs {
             match wsc.change.as_ref().unwrap() {
                 Change::DeleteResource(res) => {
-                    // Record resource account.
-                    // TODO: If the resource is an object, then we need to look for the latest
-                    // owner. This isn't really possible right now given we have parallel threads
-                    // so it'll be very difficult to ensure that we have the correct latest owner.
-                    accounts.insert(standardize_address(res.address.as_str()));
+                    // Record resource account. If the resource is under an object, then we
+                    // record the owners as of the batch, since the object may be gone now.
+                    let address = standardize_address(res.address.as_str());
+                    owners.insert_owners(&address, &mut accounts);
+                    accounts.insert(address);
                 },
                 Change::WriteResource(res) => {
                     // Record resource account. If the resource is an object, then we record the
-                    // owner as well.
+                    // owner from this write and the owners above it.
                     // This handles partial deletes as well.
-                    accounts.insert(standardize_address(res.address.as_str()));
+                    let address = standardize_address(res.address.as_str());
                     if let Some(inner) = &ObjectWithMetadata::from_write_resource(res).unwrap() {
-                        accounts.insert(inner.object_core.get_owner_address());
+                        let owner_address = inner.object_core.get_owner_address();
+                        owners.insert_owners(&owner_address, &mut accounts);
+                        accounts.insert(owner_address);
+                    } else {
+                        owners.insert_owners(&address, &mut accounts);
                     }
+                    accounts.insert(address);
+                },
+                Change::WriteTableItem(item) => {
+                    owners.insert_table_owners(item.handle.as_str(), &mut accounts);
+                },
+                Change::DeleteTableItem(item) => {
+                    owners.insert_table_owners(item.handle.as_str(), &mut accounts);
                 },
                 _ => {},
             }
@@ -134,6 +315,10 @@ impl NamedTable for ParquetAccountTransaction {
     const TABLE_NAME: &'static str = "account_transactions";
 }
 
+impl HasSchemaVersion for ParquetAccountTransaction {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetAccountTransaction {
     fn version(&self) -> i64 {
         self.txn_version
@@ -167,3 +352,194 @@ impl From<AccountTransaction> for PostgresAccountTransaction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libra2_indexer_processor_sdk::libra2_protos::transaction::v1::{
+        BlockMetadataTransaction, MoveStructTag, TransactionInfo, WriteResource, WriteSetChange,
+        WriteTableItem,
+    };
+
+    fn address(n: u64) -> String {
+        format!("0x{n:064x}")
+    }
+
+    fn write_resource(address: &str, module: &str, name: &str, data: serde_json::Value) -> Change {
+        Change::WriteResource(WriteResource {
+            address: address.to_string(),
+            type_str: format!("0x1::{module}::{name}"),
+            r#type: Some(MoveStructTag {
+                address: "0x1".to_string(),
+                module: module.to_string(),
+                name: name.to_string(),
+                generic_type_params: vec![],
+            }),
+            data: data.to_string(),
+            ..WriteResource::default()
+        })
+    }
+
+    fn object_core(address: &str, owner: &str) -> Change {
+        write_resource(
+            address,
+            "object",
+            "ObjectCore",
+            serde_json::json!({
+                "allow_ungated_transfer": true,
+                "guid_creation_num": "1125899906842625",
+                "owner": owner,
+            }),
+        )
+    }
+
+    fn write_table_item(handle: &str) -> Change {
+        Change::WriteTableItem(WriteTableItem {
+            handle: handle.to_string(),
+            ..WriteTableItem::default()
+        })
+    }
+
+    fn transaction(changes: Vec<Change>) -> Transaction {
+        Transaction {
+            info: Some(TransactionInfo {
+                changes: changes
+                    .into_iter()
+                    .map(|change| WriteSetChange {
+                        change: Some(change),
+                        ..WriteSetChange::default()
+                    })
+                    .collect(),
+                ..TransactionInfo::default()
+            }),
+            txn_data: Some(TxnData::BlockMetadata(BlockMetadataTransaction::default())),
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn test_table_handle_to_owner_from_transactions() {
+        let store = write_resource(
+            &address(0xa),
+            "token",
+            "TokenStore",
+            serde_json::json!({
+                "tokens": { "handle": "0xbeef" },
+                "nested": [{ "inner": { "handle": "0xcafe" } }],
+                // Not a table, it has other fields
+                "event": { "handle": "0xf00d", "counter": "1" },
+            }),
+        );
+        let owners = TableMetadata::get_table_handle_to_owner_from_transactions(&[
+            transaction(vec![store]),
+            // Later writes win
+            transaction(vec![write_resource(
+                &address(0xb),
+                "token",
+                "TokenStore",
+                serde_json::json!({ "tokens": { "handle": "0xcafe" } }),
+            )]),
+        ]);
+
+        assert_eq!(
+            owners,
+            AHashMap::from_iter([
+                (address(0xbeef), address(0xa)),
+                (address(0xcafe), address(0xb)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_get_accounts_walks_object_owners() {
+        // object 0 -> object 1 -> ... -> object N, each owned by the next
+        let objects: Vec<String> = (0..=MAX_OBJECT_OWNER_DEPTH as u64 + 1)
+            .map(|n| address(0x100 + n))
+            .collect();
+        let owners = AccountOwners {
+            object_owners: objects
+                .windows(2)
+                .map(|pair| (pair[0].clone(), pair[1].clone()))
+                .collect(),
+            ..AccountOwners::default()
+        };
+        let txn = transaction(vec![write_resource(
+            &objects[0],
+            "fungible_asset",
+            "FungibleStore",
+            serde_json::json!({}),
+        )]);
+
+        // Only MAX_OBJECT_OWNER_DEPTH owners above the written object are recorded
+        let accounts = AccountTransaction::get_accounts(&txn, &owners);
+        assert_eq!(
+            accounts,
+            AHashSet::from_iter(objects[..=MAX_OBJECT_OWNER_DEPTH].iter().cloned())
+        );
+
+        // A short chain is recorded up to the account at the top
+        let accounts = AccountTransaction::get_accounts(
+            &transaction(vec![write_resource(
+                &objects[MAX_OBJECT_OWNER_DEPTH - 1],
+                "fungible_asset",
+                "FungibleStore",
+                serde_json::json!({}),
+            )]),
+            &owners,
+        );
+        assert_eq!(
+            accounts,
+            AHashSet::from_iter(objects[MAX_OBJECT_OWNER_DEPTH - 1..].iter().cloned())
+        );
+    }
+
+    #[test]
+    fn test_get_accounts_from_batch_owners() {
+        let (object, user, new_user) = (address(0x100), address(0xa11ce), address(0xb0b));
+        // The object is transferred in the same batch
+        let txns = [
+            transaction(vec![object_core(&object, &user)]),
+            transaction(vec![object_core(&object, &new_user)]),
+        ];
+        let owners = AccountOwners::from_transactions(&txns);
+
+        // The ObjectCore write records the owner of that write
+        assert_eq!(
+            AccountTransaction::get_accounts(&txns[0], &owners),
+            AHashSet::from_iter([object.clone(), user])
+        );
+        // Other resources of the object are recorded against the latest owner of the batch
+        let txn = transaction(vec![write_resource(
+            &object,
+            "fungible_asset",
+            "FungibleStore",
+            serde_json::json!({}),
+        )]);
+        assert_eq!(
+            AccountTransaction::get_accounts(&txn, &owners),
+            AHashSet::from_iter([object, new_user])
+        );
+    }
+
+    #[test]
+    fn test_get_accounts_attributes_table_items() {
+        let (object, user) = (address(0x100), address(0xa11ce));
+        let owners = AccountOwners {
+            object_owners: AHashMap::from_iter([(object.clone(), user.clone())]),
+            table_owners: AHashMap::from_iter([
+                (address(0xbeef), object.clone()),
+                (address(0xcafe), address(0x1)),
+            ]),
+        };
+
+        // A table held by an object is recorded against the object and its owner
+        let txn = transaction(vec![write_table_item("0xbeef")]);
+        assert_eq!(
+            AccountTransaction::get_accounts(&txn, &owners),
+            AHashSet::from_iter([object, user])
+        );
+        // Tables held by framework addresses and unknown tables aren't attributed
+        let txn = transaction(vec![write_table_item("0xcafe"), write_table_item("0xf00d")]);
+        assert!(AccountTransaction::get_accounts(&txn, &owners).is_empty());
+    }
+}
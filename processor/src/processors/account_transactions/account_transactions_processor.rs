@@ -113,7 +113,7 @@ impl ProcessorTrait for AccountTransactionsProcessor {
             ..self.config.transaction_stream_config.clone()
         })
         .await?;
-        let acc_txns_extractor = AccountTransactionsExtractor {};
+        let acc_txns_extractor = AccountTransactionsExtractor::new(self.db_pool.clone());
         let opt_in_tables = TableFlags::from_set(&processor_config.tables_to_write);
 
         let acc_txns_storer =
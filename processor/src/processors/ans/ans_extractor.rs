@@ -7,11 +7,15 @@ use crate::{
         ans_processor::AnsProcessorConfig,
         models::{
             ans_lookup::{CurrentAnsLookup, CurrentAnsPrimaryName},
-            ans_lookup_v2::{AnsLookupV2, CurrentAnsLookupV2, PostgresCurrentAnsLookupV2},
+            ans_lookup_v2::{
+                AnsLookupV2, CurrentAnsLookupV2, PostgresAnsLookupV2, PostgresCurrentAnsLookupV2,
+            },
             ans_primary_name_v2::{
-                AnsPrimaryNameV2, CurrentAnsPrimaryNameV2, PostgresCurrentAnsPrimaryNameV2,
+                AnsPrimaryNameV2, CurrentAnsPrimaryNameV2, PostgresAnsPrimaryNameV2,
+                PostgresCurrentAnsPrimaryNameV2,
             },
-            ans_utils::{RenewNameEvent, SubdomainExtV2},
+            ans_renewal_activities::{AnsRenewalActivity, PostgresAnsRenewalActivity},
+            ans_utils::SubdomainExtV2,
         },
     },
     utils::counters::PROCESSOR_UNKNOWN_TYPE_COUNT,
@@ -57,8 +61,11 @@ impl AnsExtractor {
 impl Processable for AnsExtractor {
     type Input = Vec<Transaction>;
     type Output = (
+        Vec<PostgresAnsLookupV2>,
         Vec<PostgresCurrentAnsLookupV2>,
+        Vec<PostgresAnsPrimaryNameV2>,
         Vec<PostgresCurrentAnsPrimaryNameV2>,
+        Vec<PostgresAnsRenewalActivity>,
     );
     type RunType = AsyncRunType;
 
@@ -68,17 +75,21 @@ impl Processable for AnsExtractor {
     ) -> Result<
         Option<
             TransactionContext<(
+                Vec<PostgresAnsLookupV2>,
                 Vec<PostgresCurrentAnsLookupV2>,
+                Vec<PostgresAnsPrimaryNameV2>,
                 Vec<PostgresCurrentAnsPrimaryNameV2>,
+                Vec<PostgresAnsRenewalActivity>,
             )>,
         >,
         ProcessorError,
     > {
         let (
             raw_current_ans_lookups_v2,
-            _,
+            raw_ans_lookups_v2,
             raw_current_ans_primary_names_v2,
-            _, // AnsPrimaryNameV2 is deprecated.
+            raw_ans_primary_names_v2,
+            raw_ans_renewal_activities,
         ) = parse_ans(
             &input.data,
             self.config.ans_v1_primary_names_table_handle.clone(),
@@ -86,22 +97,41 @@ impl Processable for AnsExtractor {
             self.config.ans_v2_contract_address.clone(),
         );
 
+        let postgres_ans_lookups_v2: Vec<PostgresAnsLookupV2> = raw_ans_lookups_v2
+            .into_iter()
+            .map(PostgresAnsLookupV2::from)
+            .collect();
+
         let postgres_current_ans_lookups_v2: Vec<PostgresCurrentAnsLookupV2> =
             raw_current_ans_lookups_v2
                 .into_iter()
                 .map(PostgresCurrentAnsLookupV2::from)
                 .collect();
 
+        let postgres_ans_primary_names_v2: Vec<PostgresAnsPrimaryNameV2> = raw_ans_primary_names_v2
+            .into_iter()
+            .map(PostgresAnsPrimaryNameV2::from)
+            .collect();
+
         let postgres_current_ans_primary_names_v2: Vec<PostgresCurrentAnsPrimaryNameV2> =
             raw_current_ans_primary_names_v2
                 .into_iter()
                 .map(PostgresCurrentAnsPrimaryNameV2::from)
                 .collect();
 
+        let postgres_ans_renewal_activities: Vec<PostgresAnsRenewalActivity> =
+            raw_ans_renewal_activities
+                .into_iter()
+                .map(PostgresAnsRenewalActivity::from)
+                .collect();
+
         Ok(Some(TransactionContext {
             data: (
+                postgres_ans_lookups_v2,
                 postgres_current_ans_lookups_v2,
+                postgres_ans_primary_names_v2,
                 postgres_current_ans_primary_names_v2,
+                postgres_ans_renewal_activities,
             ),
             metadata: input.metadata,
         }))
@@ -126,6 +156,7 @@ pub fn parse_ans(
     Vec<AnsLookupV2>,
     Vec<CurrentAnsPrimaryNameV2>,
     Vec<AnsPrimaryNameV2>,
+    Vec<AnsRenewalActivity>,
 ) {
     let mut all_current_ans_lookups = AHashMap::new();
     let mut all_ans_lookups = vec![];
@@ -135,6 +166,7 @@ pub fn parse_ans(
     let mut all_ans_lookups_v2 = vec![];
     let mut all_current_ans_primary_names_v2 = AHashMap::new();
     let mut all_ans_primary_names_v2 = vec![];
+    let mut all_ans_renewal_activities = vec![];
 
     for transaction in transactions {
         let txn_version = transaction.version as i64;
@@ -166,19 +198,22 @@ pub fn parse_ans(
         // Extracts from user transactions. Other transactions won't have any ANS changes
 
         if let TxnData::User(user_txn) = txn_data {
-            // TODO: Use the v2_renew_name_events to preserve metadata once we switch to a single ANS table to store everything
-            let mut v2_renew_name_events = vec![];
             let mut v2_address_to_subdomain_ext = AHashMap::new();
 
             // Parse V2 ANS Events. We only care about the following events:
-            // 1. RenewNameEvents: helps to fill in metadata for name records with updated expiration time
+            // 1. RenewNameEvents: parse to get ans_renewal_activities
             // 2. SetReverseLookupEvents: parse to get current_ans_primary_names
             for (event_index, event) in user_txn.events.iter().enumerate() {
-                if let Some(renew_name_event) =
-                    RenewNameEvent::from_event(event, &ans_v2_contract_address, txn_version)
-                        .unwrap()
+                if let Some(renewal_activity) = AnsRenewalActivity::from_event(
+                    event,
+                    &ans_v2_contract_address,
+                    txn_version,
+                    event_index as i64,
+                    block_timestamp,
+                )
+                .unwrap()
                 {
-                    v2_renew_name_events.push(renew_name_event);
+                    all_ans_renewal_activities.push(renewal_activity);
                 }
                 if let Some((current_ans_lookup_v2, ans_lookup_v2)) =
                     CurrentAnsPrimaryNameV2::parse_v2_primary_name_record_from_event(
@@ -395,5 +430,6 @@ pub fn parse_ans(
         all_ans_lookups_v2,
         all_current_ans_primary_names_v2,
         all_ans_primary_names_v2,
+        all_ans_renewal_activities,
     )
 }
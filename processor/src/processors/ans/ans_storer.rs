@@ -6,12 +6,13 @@ use crate::{
     processors::ans::{
         ans_processor::AnsProcessorConfig,
         models::{
-            ans_lookup_v2::PostgresCurrentAnsLookupV2,
-            ans_primary_name_v2::PostgresCurrentAnsPrimaryNameV2,
+            ans_lookup_v2::{PostgresAnsLookupV2, PostgresCurrentAnsLookupV2},
+            ans_primary_name_v2::{PostgresAnsPrimaryNameV2, PostgresCurrentAnsPrimaryNameV2},
+            ans_renewal_activities::PostgresAnsRenewalActivity,
         },
     },
     schema,
-    utils::table_flags::{filter_data, TableFlags},
+    utils::table_flags::{filter_data, filter_opt_in_data, TableFlags},
 };
 use ahash::AHashMap;
 use anyhow::Result;
@@ -55,8 +56,11 @@ impl AnsStorer {
 #[async_trait]
 impl Processable for AnsStorer {
     type Input = (
+        Vec<PostgresAnsLookupV2>,
         Vec<PostgresCurrentAnsLookupV2>,
+        Vec<PostgresAnsPrimaryNameV2>,
         Vec<PostgresCurrentAnsPrimaryNameV2>,
+        Vec<PostgresAnsRenewalActivity>,
     );
     type Output = ();
     type RunType = AsyncRunType;
@@ -64,20 +68,56 @@ impl Processable for AnsStorer {
     async fn process(
         &mut self,
         input: TransactionContext<(
+            Vec<PostgresAnsLookupV2>,
             Vec<PostgresCurrentAnsLookupV2>,
+            Vec<PostgresAnsPrimaryNameV2>,
             Vec<PostgresCurrentAnsPrimaryNameV2>,
+            Vec<PostgresAnsRenewalActivity>,
         )>,
     ) -> Result<Option<TransactionContext<()>>, ProcessorError> {
-        let (current_ans_lookups_v2, current_ans_primary_names_v2) = input.data;
+        let (
+            ans_lookups_v2,
+            current_ans_lookups_v2,
+            ans_primary_names_v2,
+            current_ans_primary_names_v2,
+            ans_renewal_activities,
+        ) = input.data;
 
         let per_table_chunk_sizes: AHashMap<String, usize> =
             self.processor_config.default.per_table_chunk_sizes.clone();
 
+        // History tables are only written when they're in tables_to_write
+        let ans_lookups_v2 = filter_opt_in_data(
+            &self.tables_to_write,
+            TableFlags::ANS_LOOKUP_V2,
+            ans_lookups_v2,
+        );
+        let ans_primary_names_v2 = filter_opt_in_data(
+            &self.tables_to_write,
+            TableFlags::ANS_PRIMARY_NAME_V2,
+            ans_primary_names_v2,
+        );
+        let ans_renewal_activities = filter_opt_in_data(
+            &self.tables_to_write,
+            TableFlags::ANS_RENEWAL_ACTIVITIES,
+            ans_renewal_activities,
+        );
+
         let (current_ans_lookups_v2, current_ans_primary_names_v2) = filter_datasets!(self, {
             current_ans_lookups_v2 => TableFlags::CURRENT_ANS_LOOKUP_V2,
             current_ans_primary_names_v2 => TableFlags::CURRENT_ANS_PRIMARY_NAME_V2,
         });
 
+        let al_v2 = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_ans_lookups_v2_query,
+            &ans_lookups_v2,
+            get_config_table_chunk_size::<PostgresAnsLookupV2>(
+                "ans_lookup_v2",
+                &per_table_chunk_sizes,
+            ),
+        );
+
         let cal_v2 = execute_in_chunks(
             self.conn_pool.clone(),
             insert_current_ans_lookups_v2_query,
@@ -87,6 +127,15 @@ impl Processable for AnsStorer {
                 &per_table_chunk_sizes,
             ),
         );
+        let apn_v2 = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_ans_primary_names_v2_query,
+            &ans_primary_names_v2,
+            get_config_table_chunk_size::<PostgresAnsPrimaryNameV2>(
+                "ans_primary_name_v2",
+                &per_table_chunk_sizes,
+            ),
+        );
         let capn_v2 = execute_in_chunks(
             self.conn_pool.clone(),
             insert_current_ans_primary_names_v2_query,
@@ -97,7 +146,17 @@ impl Processable for AnsStorer {
             ),
         );
 
-        futures::try_join!(cal_v2, capn_v2)?;
+        let ara = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_ans_renewal_activities_query,
+            &ans_renewal_activities,
+            get_config_table_chunk_size::<PostgresAnsRenewalActivity>(
+                "ans_renewal_activities",
+                &per_table_chunk_sizes,
+            ),
+        );
+
+        futures::try_join!(al_v2, cal_v2, apn_v2, capn_v2, ara)?;
 
         Ok(Some(TransactionContext {
             data: (),
@@ -114,6 +173,17 @@ impl NamedStep for AnsStorer {
     }
 }
 
+pub fn insert_ans_lookups_v2_query(
+    item_to_insert: Vec<PostgresAnsLookupV2>,
+) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
+    use schema::ans_lookup_v2::dsl::*;
+
+    diesel::insert_into(schema::ans_lookup_v2::table)
+        .values(item_to_insert)
+        .on_conflict((transaction_version, write_set_change_index))
+        .do_nothing()
+}
+
 pub fn insert_current_ans_lookups_v2_query(
     item_to_insert: Vec<PostgresCurrentAnsLookupV2>,
 ) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
@@ -135,6 +205,17 @@ pub fn insert_current_ans_lookups_v2_query(
         .filter(last_transaction_version.le(excluded(last_transaction_version)))
 }
 
+pub fn insert_ans_primary_names_v2_query(
+    item_to_insert: Vec<PostgresAnsPrimaryNameV2>,
+) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
+    use schema::ans_primary_name_v2::dsl::*;
+
+    diesel::insert_into(schema::ans_primary_name_v2::table)
+        .values(item_to_insert)
+        .on_conflict((transaction_version, write_set_change_index))
+        .do_nothing()
+}
+
 pub fn insert_current_ans_primary_names_v2_query(
     item_to_insert: Vec<PostgresCurrentAnsPrimaryNameV2>,
 ) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
@@ -154,3 +235,14 @@ pub fn insert_current_ans_primary_names_v2_query(
         ))
         .filter(last_transaction_version.le(excluded(last_transaction_version)))
 }
+
+pub fn insert_ans_renewal_activities_query(
+    item_to_insert: Vec<PostgresAnsRenewalActivity>,
+) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
+    use schema::ans_renewal_activities::dsl::*;
+
+    diesel::insert_into(schema::ans_renewal_activities::table)
+        .values(item_to_insert)
+        .on_conflict((transaction_version, event_index))
+        .do_nothing()
+}
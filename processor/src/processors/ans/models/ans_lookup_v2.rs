@@ -6,7 +6,7 @@
 #![allow(clippy::unused_unit)]
 
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         ans::models::{
             ans_lookup::{AnsLookup, CurrentAnsLookup},
@@ -95,6 +95,10 @@ impl NamedTable for ParquetAnsLookupV2 {
     const TABLE_NAME: &'static str = "ans_lookup_v2";
 }
 
+impl HasSchemaVersion for ParquetAnsLookupV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetAnsLookupV2 {
     fn version(&self) -> i64 {
         self.txn_version
@@ -137,6 +141,10 @@ impl NamedTable for ParquetCurrentAnsLookupV2 {
     const TABLE_NAME: &'static str = "current_ans_lookup_v2";
 }
 
+impl HasSchemaVersion for ParquetCurrentAnsLookupV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCurrentAnsLookupV2 {
     fn version(&self) -> i64 {
         self.last_transaction_version
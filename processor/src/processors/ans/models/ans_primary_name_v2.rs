@@ -6,7 +6,7 @@
 
 use super::ans_lookup_v2::TokenStandardType;
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         ans::models::{
             ans_lookup::{AnsPrimaryName, CurrentAnsPrimaryName},
@@ -81,6 +81,10 @@ impl NamedTable for ParquetAnsPrimaryNameV2 {
     const TABLE_NAME: &'static str = "ans_primary_name_v2";
 }
 
+impl HasSchemaVersion for ParquetAnsPrimaryNameV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetAnsPrimaryNameV2 {
     fn version(&self) -> i64 {
         self.txn_version
@@ -118,6 +122,10 @@ impl NamedTable for ParquetCurrentAnsPrimaryNameV2 {
     const TABLE_NAME: &'static str = "current_ans_primary_name_v2";
 }
 
+impl HasSchemaVersion for ParquetCurrentAnsPrimaryNameV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCurrentAnsPrimaryNameV2 {
     fn version(&self) -> i64 {
         self.last_transaction_version
@@ -0,0 +1,161 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+#![allow(clippy::unused_unit)]
+
+use crate::{
+    processors::{
+        ans::models::ans_utils::RenewNameEvent,
+        token_v2::token_v2_models::v2_token_utils::TokenStandard,
+    },
+    schema::ans_renewal_activities,
+};
+use libra2_indexer_processor_sdk::libra2_protos::transaction::v1::Event;
+use diesel::{Identifiable, Insertable};
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+
+/// A renewal of an ANS v2 name, parsed from a `RenewNameEvent`. The expiration timestamp is the
+/// one the name was renewed to.
+///
+/// Expiries aren't recorded. A name expires once its expiration timestamp passes, which doesn't
+/// emit an event or even take a transaction.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AnsRenewalActivity {
+    pub transaction_version: i64,
+    pub event_index: i64,
+    pub domain: String,
+    pub subdomain: String,
+    pub token_standard: String,
+    pub token_name: String,
+    pub registered_address: Option<String>,
+    pub expiration_timestamp: chrono::NaiveDateTime,
+    pub is_primary_name: bool,
+    pub block_timestamp: chrono::NaiveDateTime,
+}
+
+impl AnsRenewalActivity {
+    pub fn from_event(
+        event: &Event,
+        ans_v2_contract_address: &str,
+        txn_version: i64,
+        event_index: i64,
+        block_timestamp: chrono::NaiveDateTime,
+    ) -> anyhow::Result<Option<Self>> {
+        if let Some(renew_name_event) =
+            RenewNameEvent::from_event(event, ans_v2_contract_address, txn_version)?
+        {
+            return Ok(Some(Self::from_renew_name_event(
+                &renew_name_event,
+                txn_version,
+                event_index,
+                block_timestamp,
+            )));
+        }
+        Ok(None)
+    }
+
+    pub fn from_renew_name_event(
+        renew_name_event: &RenewNameEvent,
+        txn_version: i64,
+        event_index: i64,
+        block_timestamp: chrono::NaiveDateTime,
+    ) -> Self {
+        Self {
+            transaction_version: txn_version,
+            event_index,
+            domain: renew_name_event.get_domain_trunc(),
+            subdomain: renew_name_event.get_subdomain_trunc(),
+            token_standard: TokenStandard::V2.to_string(),
+            token_name: renew_name_event.get_token_name(),
+            registered_address: renew_name_event.get_target_address(),
+            expiration_timestamp: renew_name_event.get_expiration_time(),
+            is_primary_name: renew_name_event.is_primary_name(),
+            block_timestamp,
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version, event_index))]
+#[diesel(table_name = ans_renewal_activities)]
+#[diesel(treat_none_as_null = true)]
+pub struct PostgresAnsRenewalActivity {
+    pub transaction_version: i64,
+    pub event_index: i64,
+    pub domain: String,
+    pub subdomain: String,
+    pub token_standard: String,
+    pub token_name: String,
+    pub registered_address: Option<String>,
+    pub expiration_timestamp: chrono::NaiveDateTime,
+    pub is_primary_name: bool,
+    pub block_timestamp: chrono::NaiveDateTime,
+}
+
+impl From<AnsRenewalActivity> for PostgresAnsRenewalActivity {
+    fn from(raw_item: AnsRenewalActivity) -> Self {
+        PostgresAnsRenewalActivity {
+            transaction_version: raw_item.transaction_version,
+            event_index: raw_item.event_index,
+            domain: raw_item.domain,
+            subdomain: raw_item.subdomain,
+            token_standard: raw_item.token_standard,
+            token_name: raw_item.token_name,
+            registered_address: raw_item.registered_address,
+            expiration_timestamp: raw_item.expiration_timestamp,
+            is_primary_name: raw_item.is_primary_name,
+            block_timestamp: raw_item.block_timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libra2_indexer_processor_sdk::utils::convert::standardize_address;
+
+    #[test]
+    fn test_renewal_activity_from_event() {
+        let block_timestamp = chrono::NaiveDateTime::default();
+        let event = Event {
+            type_str: "0xcafe::v2_1_domains::RenewNameEvent".to_string(),
+            data: r#"{
+                "domain_name": "alice",
+                "expiration_time_secs": "1700000000",
+                "is_primary_name": true,
+                "subdomain_name": {"vec": ["wallet"]},
+                "target_address": {"vec": ["0xa11ce"]}
+            }"#
+            .to_string(),
+            ..Default::default()
+        };
+
+        let activity = AnsRenewalActivity::from_event(&event, "0xcafe", 10, 2, block_timestamp)
+            .unwrap()
+            .unwrap();
+        assert_eq!(activity.transaction_version, 10);
+        assert_eq!(activity.event_index, 2);
+        assert_eq!(activity.domain, "alice");
+        assert_eq!(activity.subdomain, "wallet");
+        assert_eq!(activity.token_name, "wallet.alice.apt");
+        assert_eq!(
+            activity.registered_address,
+            Some(standardize_address("0xa11ce"))
+        );
+        assert_eq!(
+            activity.expiration_timestamp.and_utc().timestamp(),
+            1700000000
+        );
+        assert!(activity.is_primary_name);
+
+        // Events from other contracts are ignored
+        assert!(
+            AnsRenewalActivity::from_event(&event, "0xbeef", 10, 2, block_timestamp)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -243,6 +243,40 @@ pub struct RenewNameEvent {
 }
 
 impl RenewNameEvent {
+    pub fn get_domain_trunc(&self) -> String {
+        truncate_str(self.domain_name.as_str(), DOMAIN_LENGTH)
+    }
+
+    pub fn get_subdomain_trunc(&self) -> String {
+        truncate_str(
+            self.subdomain_name
+                .get_string()
+                .unwrap_or_default()
+                .as_str(),
+            DOMAIN_LENGTH,
+        )
+    }
+
+    pub fn get_token_name(&self) -> String {
+        let domain = self.get_domain_trunc();
+        let subdomain = self.get_subdomain_trunc();
+        get_token_name(&domain, &subdomain)
+    }
+
+    pub fn get_expiration_time(&self) -> chrono::NaiveDateTime {
+        parse_timestamp_secs(bigdecimal_to_u64(&self.expiration_time_secs), 0).naive_utc()
+    }
+
+    pub fn get_target_address(&self) -> Option<String> {
+        self.target_address
+            .get_string()
+            .map(|addr| standardize_address(&addr))
+    }
+
+    pub fn is_primary_name(&self) -> bool {
+        self.is_primary_name
+    }
+
     pub fn from_event(
         event: &Event,
         ans_v2_contract_address: &str,
@@ -1,4 +1,5 @@
 pub mod ans_lookup;
 pub mod ans_lookup_v2;
 pub mod ans_primary_name_v2;
+pub mod ans_renewal_activities;
 pub mod ans_utils;
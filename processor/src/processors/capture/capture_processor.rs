@@ -0,0 +1,182 @@
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::IndexerProcessorConfig,
+        processor_config::{DefaultProcessorConfig, ProcessorConfig},
+    },
+    processors::{
+        capture::capture_writer_step::CaptureWriterStep,
+        processor_status_saver::{
+            get_end_version, get_starting_version, PostgresProcessorStatusSaver,
+        },
+    },
+    MIGRATIONS,
+};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::{
+    builder::ProcessorBuilder,
+    common_steps::{
+        TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+    },
+    libra2_indexer_transaction_stream::TransactionStreamConfig,
+    postgres::utils::{
+        checkpoint::PostgresChainIdChecker,
+        database::{new_db_pool, run_migrations, ArcDbPool},
+    },
+    traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    utils::chain_id_check::check_or_update_chain_id,
+};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, time::Duration};
+use tracing::{debug, info};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CaptureProcessorConfig {
+    // Directory the capture files and their index are written to
+    pub output_dir: PathBuf,
+    // Size of channel between steps
+    #[serde(default = "DefaultProcessorConfig::default_channel_size")]
+    pub channel_size: usize,
+    // Uncompressed size in bytes after which a capture file is rotated
+    #[serde(default = "CaptureProcessorConfig::default_max_file_size_bytes")]
+    pub max_file_size_bytes: usize,
+    // Interval in seconds after which a capture file is rotated, even if it isn't full
+    #[serde(default = "CaptureProcessorConfig::default_file_rotation_interval_secs")]
+    pub file_rotation_interval_secs: u64,
+}
+
+impl CaptureProcessorConfig {
+    pub const fn default_max_file_size_bytes() -> usize {
+        1024 * 1024 * 100 // 100 MB
+    }
+
+    pub const fn default_file_rotation_interval_secs() -> u64 {
+        600 // 10 minutes
+    }
+}
+
+pub struct CaptureProcessor {
+    pub config: IndexerProcessorConfig,
+    pub db_pool: ArcDbPool,
+}
+
+impl CaptureProcessor {
+    pub async fn new(config: IndexerProcessorConfig) -> Result<Self> {
+        match config.db_config {
+            DbConfig::PostgresConfig(ref postgres_config) => {
+                let conn_pool = new_db_pool(
+                    &postgres_config.connection_string,
+                    Some(postgres_config.db_pool_size),
+                )
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to create connection pool for PostgresConfig: {:?}",
+                        e
+                    )
+                })?;
+
+                Ok(Self {
+                    config,
+                    db_pool: conn_pool,
+                })
+            },
+            _ => Err(anyhow::anyhow!(
+                "Invalid db config for CaptureProcessor {:?}",
+                config.db_config
+            )),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for CaptureProcessor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    /// Records the transaction stream to capture files that can be replayed with the replay
+    /// command. Only the checkpoint is stored in the database.
+    async fn run_processor(&self) -> Result<()> {
+        // Run migrations
+        if let DbConfig::PostgresConfig(ref postgres_config) = self.config.db_config {
+            run_migrations(
+                postgres_config.connection_string.clone(),
+                self.db_pool.clone(),
+                MIGRATIONS,
+            )
+            .await;
+        }
+
+        //  Merge the starting version from config and the latest processed version from the DB
+        let (starting_version, ending_version) = (
+            get_starting_version(&self.config, self.db_pool.clone()).await?,
+            get_end_version(&self.config, self.db_pool.clone()).await?,
+        );
+
+        // Check and update the ledger chain id to ensure we're indexing the correct chain
+        check_or_update_chain_id(
+            &self.config.transaction_stream_config,
+            &PostgresChainIdChecker::new(self.db_pool.clone()),
+        )
+        .await?;
+
+        let processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::CaptureProcessor(processor_config) => processor_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor config for CaptureProcessor: {:?}",
+                    self.config.processor_config
+                ))
+            },
+        };
+        let channel_size = processor_config.channel_size;
+        std::fs::create_dir_all(&processor_config.output_dir).with_context(|| {
+            format!(
+                "Failed to create capture output directory {:?}",
+                processor_config.output_dir
+            )
+        })?;
+
+        // Define processor steps
+        let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+            starting_version,
+            request_ending_version: ending_version,
+            ..self.config.transaction_stream_config.clone()
+        })
+        .await?;
+        let capture_writer = CaptureWriterStep::new(
+            processor_config.output_dir,
+            processor_config.max_file_size_bytes,
+            Duration::from_secs(processor_config.file_rotation_interval_secs),
+        );
+        let version_tracker = VersionTrackerStep::new(
+            PostgresProcessorStatusSaver::new(self.config.clone(), self.db_pool.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        // Connect processor steps together
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+            transaction_stream.into_runnable_step(),
+        )
+        .connect_to(capture_writer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+
+        loop {
+            match buffer_receiver.recv().await {
+                Ok(txn_context) => {
+                    debug!(
+                        "Captured versions [{:?}, {:?}]",
+                        txn_context.metadata.start_version, txn_context.metadata.end_version,
+                    );
+                },
+                Err(e) => {
+                    info!("No more transactions in channel: {:?}", e);
+                    break Ok(());
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use anyhow::Result;
+use libra2_indexer_processor_sdk::{
+    libra2_indexer_transaction_stream::utils::time::parse_timestamp,
+    libra2_protos::transaction::v1::Transaction,
+    traits::{
+        pollable_async_step::PollableAsyncRunType, NamedStep, PollableAsyncStep, Processable,
+    },
+    types::transaction_context::{TransactionContext, TransactionMetadata},
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use flate2::{write::GzEncoder, Compression};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::{io::Write, path::PathBuf, time::Duration};
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+/// Capture files hold gzip-compressed, length-delimited `Transaction` protos.
+pub const CAPTURE_FILE_EXTENSION: &str = "pb.gz";
+/// Name of the index in the output directory, with one JSON `CaptureIndexEntry` per line.
+pub const CAPTURE_INDEX_FILE_NAME: &str = "index.jsonl";
+
+/// Describes the versions in a capture file.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct CaptureIndexEntry {
+    pub file_name: String,
+    pub start_version: u64,
+    pub end_version: u64,
+    pub num_transactions: u64,
+    pub start_transaction_timestamp: Option<chrono::NaiveDateTime>,
+    pub end_transaction_timestamp: Option<chrono::NaiveDateTime>,
+    /// Compressed size of the file
+    pub size_in_bytes: u64,
+}
+
+/// The capture file that batches are currently appended to. It's only written to disk once it's
+/// rotated, so a file on disk is always complete.
+struct CaptureFile {
+    encoder: GzEncoder<Vec<u8>>,
+    uncompressed_size_bytes: usize,
+    num_transactions: u64,
+    metadata: TransactionMetadata,
+}
+
+/// `CaptureWriterStep` writes every transaction batch from the stream to capture files.
+///
+/// A file is rotated once it exceeds `max_file_size_bytes` uncompressed, or on every poll. The
+/// step only returns a batch once the file containing it is on disk, so the checkpoint never
+/// runs ahead of the capture files.
+pub struct CaptureWriterStep {
+    output_dir: PathBuf,
+    max_file_size_bytes: usize,
+    poll_interval: Duration,
+    current_file: Option<CaptureFile>,
+}
+
+impl CaptureWriterStep {
+    pub fn new(output_dir: PathBuf, max_file_size_bytes: usize, poll_interval: Duration) -> Self {
+        Self {
+            output_dir,
+            max_file_size_bytes,
+            poll_interval,
+            current_file: None,
+        }
+    }
+
+    fn append(
+        &mut self,
+        batch: TransactionContext<Vec<Transaction>>,
+    ) -> Result<(), ProcessorError> {
+        let file = self.current_file.get_or_insert_with(|| CaptureFile {
+            encoder: GzEncoder::new(vec![], Compression::default()),
+            uncompressed_size_bytes: 0,
+            num_transactions: 0,
+            metadata: batch.metadata.clone(),
+        });
+
+        if file.num_transactions > 0 {
+            if file.metadata.end_version + 1 != batch.metadata.start_version {
+                return Err(ProcessorError::ProcessError {
+                    message: format!(
+                        "Gap found: capture file end_version mismatch: {} != {}",
+                        file.metadata.end_version, batch.metadata.start_version
+                    ),
+                });
+            }
+            file.metadata.end_version = batch.metadata.end_version;
+            file.metadata.end_transaction_timestamp = batch.metadata.end_transaction_timestamp;
+            file.metadata.total_size_in_bytes += batch.metadata.total_size_in_bytes;
+        }
+
+        for transaction in &batch.data {
+            let encoded = transaction.encode_length_delimited_to_vec();
+            file.encoder
+                .write_all(&encoded)
+                .map_err(|e| ProcessorError::ProcessError {
+                    message: format!("Failed to compress transaction: {e:?}"),
+                })?;
+            file.uncompressed_size_bytes += encoded.len();
+        }
+        file.num_transactions += batch.data.len() as u64;
+        Ok(())
+    }
+
+    /// Writes the current file to `{output_dir}/{start_version}_{end_version}.pb.gz` and adds it
+    /// to the index. Returns the versions that were written.
+    async fn rotate(&mut self) -> Result<Option<TransactionContext<()>>, ProcessorError> {
+        let Some(file) = self.current_file.take() else {
+            return Ok(None);
+        };
+        let metadata = file.metadata;
+        let buffer = file
+            .encoder
+            .finish()
+            .map_err(|e| ProcessorError::ProcessError {
+                message: format!("Failed to compress capture file: {e:?}"),
+            })?;
+
+        let file_name = format!(
+            "{}_{}.{CAPTURE_FILE_EXTENSION}",
+            metadata.start_version, metadata.end_version
+        );
+        let entry = CaptureIndexEntry {
+            file_name: file_name.clone(),
+            start_version: metadata.start_version,
+            end_version: metadata.end_version,
+            num_transactions: file.num_transactions,
+            start_transaction_timestamp: metadata
+                .start_transaction_timestamp
+                .as_ref()
+                .map(|t| parse_timestamp(t, metadata.start_version as i64).naive_utc()),
+            end_transaction_timestamp: metadata
+                .end_transaction_timestamp
+                .as_ref()
+                .map(|t| parse_timestamp(t, metadata.end_version as i64).naive_utc()),
+            size_in_bytes: buffer.len() as u64,
+        };
+        let mut index_line =
+            serde_json::to_vec(&entry).map_err(|e| ProcessorError::ProcessError {
+                message: format!("Failed to serialize capture index entry: {e:?}"),
+            })?;
+        index_line.push(b'\n');
+
+        // Write to a temporary file first, so a crash never leaves a truncated capture file behind
+        let path = self.output_dir.join(&file_name);
+        let tmp_path = self.output_dir.join(format!("{file_name}.tmp"));
+        let write_result = async {
+            tokio::fs::write(&tmp_path, &buffer).await?;
+            tokio::fs::rename(&tmp_path, &path).await?;
+            tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.output_dir.join(CAPTURE_INDEX_FILE_NAME))
+                .await?
+                .write_all(&index_line)
+                .await
+        };
+        write_result
+            .await
+            .map_err(|e| ProcessorError::ProcessError {
+                message: format!("Failed to write capture file {path:?}: {e:?}"),
+            })?;
+
+        info!(
+            file_name,
+            start_version = entry.start_version,
+            end_version = entry.end_version,
+            size_in_bytes = entry.size_in_bytes,
+            "Capture file written",
+        );
+        Ok(Some(TransactionContext { data: (), metadata }))
+    }
+}
+
+#[async_trait]
+impl Processable for CaptureWriterStep {
+    type Input = Vec<Transaction>;
+    type Output = ();
+    type RunType = PollableAsyncRunType;
+
+    async fn process(
+        &mut self,
+        item: TransactionContext<Self::Input>,
+    ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
+        self.append(item)?;
+        if self
+            .current_file
+            .as_ref()
+            .is_some_and(|file| file.uncompressed_size_bytes >= self.max_file_size_bytes)
+        {
+            return self.rotate().await;
+        }
+        Ok(None)
+    }
+
+    async fn cleanup(
+        &mut self,
+    ) -> Result<Option<Vec<TransactionContext<Self::Output>>>, ProcessorError> {
+        Ok(self.rotate().await?.map(|context| vec![context]))
+    }
+}
+
+#[async_trait]
+impl PollableAsyncStep for CaptureWriterStep {
+    fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    /// Rotates the current file, so no file covers more than one poll interval.
+    async fn poll(
+        &mut self,
+    ) -> Result<Option<Vec<TransactionContext<Self::Output>>>, ProcessorError> {
+        Ok(self.rotate().await?.map(|context| vec![context]))
+    }
+}
+
+impl NamedStep for CaptureWriterStep {
+    fn name(&self) -> String {
+        "CaptureWriterStep".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::replay::read_transaction_files;
+
+    fn batch(start_version: u64, end_version: u64) -> TransactionContext<Vec<Transaction>> {
+        TransactionContext {
+            data: (start_version..=end_version)
+                .map(|version| Transaction {
+                    version,
+                    ..Transaction::default()
+                })
+                .collect(),
+            metadata: TransactionMetadata {
+                start_version,
+                end_version,
+                ..TransactionMetadata::default()
+            },
+        }
+    }
+
+    fn read_index(output_dir: &std::path::Path) -> Vec<CaptureIndexEntry> {
+        std::fs::read_to_string(output_dir.join(CAPTURE_INDEX_FILE_NAME))
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_capture_files_rotate_by_size() {
+        let output_dir = tempfile::tempdir().unwrap();
+        // Every transaction is a few bytes, so the file rotates after the second batch
+        let mut step =
+            CaptureWriterStep::new(output_dir.path().to_path_buf(), 10, Duration::from_secs(60));
+
+        assert!(step.process(batch(0, 1)).await.unwrap().is_none());
+        let written = step.process(batch(2, 4)).await.unwrap().unwrap();
+        assert_eq!(
+            (written.metadata.start_version, written.metadata.end_version),
+            (0, 4)
+        );
+
+        assert!(step.process(batch(5, 5)).await.unwrap().is_none());
+        let written = step.cleanup().await.unwrap().unwrap();
+        assert_eq!(written.len(), 1);
+        assert_eq!(
+            (
+                written[0].metadata.start_version,
+                written[0].metadata.end_version
+            ),
+            (5, 5)
+        );
+        assert!(step.cleanup().await.unwrap().is_none());
+
+        let index = read_index(output_dir.path());
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[0].file_name, "0_4.pb.gz");
+        assert_eq!(index[0].num_transactions, 5);
+        assert_eq!(index[1].file_name, "5_5.pb.gz");
+
+        // Capture files can be replayed
+        let transactions = read_transaction_files(output_dir.path()).unwrap();
+        assert_eq!(
+            transactions
+                .iter()
+                .map(|txn| txn.version)
+                .collect::<Vec<_>>(),
+            (0..=5).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_capture_gap_is_rejected() {
+        let output_dir = tempfile::tempdir().unwrap();
+        let mut step = CaptureWriterStep::new(
+            output_dir.path().to_path_buf(),
+            usize::MAX,
+            Duration::from_secs(60),
+        );
+
+        step.process(batch(0, 1)).await.unwrap();
+        assert!(step.process(batch(3, 4)).await.is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod capture_processor;
+pub mod capture_writer_step;
@@ -25,7 +25,7 @@ use diesel::{
     pg::{upsert::excluded, Pg},
     query_builder::QueryFragment,
     query_dsl::methods::FilterDsl,
-    ExpressionMethods,
+    BoolExpressionMethods, ExpressionMethods,
 };
 
 pub struct DefaultStorer
@@ -239,10 +239,17 @@ pub fn insert_table_metadata_query(
 ) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
     use schema::table_metadatas::dsl::*;
 
+    // Only fill in the owner once, the holder of a table handle doesn't change.
     diesel::insert_into(schema::table_metadatas::table)
         .values(items_to_insert)
         .on_conflict(handle)
-        .do_nothing()
+        .do_update()
+        .set(owner_address.eq(excluded(owner_address)))
+        .filter(
+            schema::table_metadatas::owner_address
+                .is_null()
+                .and(excluded(owner_address).is_not_null()),
+        )
 }
 
 pub fn insert_move_modules_query(
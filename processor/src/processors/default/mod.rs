@@ -54,6 +54,8 @@ pub fn process_transactions(
     let mut current_table_items = AHashMap::new();
     let mut table_metadata = AHashMap::new();
     let mut move_modules = vec![];
+    let table_handle_to_owner =
+        TableMetadata::get_table_handle_to_owner_from_transactions(&transactions);
 
     for transaction in transactions {
         let version = transaction.version as i64;
@@ -115,10 +117,9 @@ pub fn process_transactions(
                         (cti.table_handle.clone(), cti.key_hash.clone()),
                         cti.clone(),
                     );
-                    table_metadata.insert(
-                        cti.table_handle.clone(),
-                        TableMetadata::from_write_table_item(inner),
-                    );
+                    let mut metadata = TableMetadata::from_write_table_item(inner);
+                    metadata.owner_address = table_handle_to_owner.get(&cti.table_handle).cloned();
+                    table_metadata.insert(cti.table_handle.clone(), metadata);
                 },
                 WriteSetChangeEnum::DeleteTableItem(inner) => {
                     let (ti, cti) = TableItem::from_delete_table_item(
@@ -6,7 +6,7 @@
 #![allow(clippy::unused_unit)]
 
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     schema::block_metadata_transactions,
 };
 use allocative_derive::Allocative;
@@ -127,6 +127,10 @@ impl NamedTable for ParquetBlockMetadataTransaction {
     const TABLE_NAME: &'static str = "block_metadata_transactions";
 }
 
+impl HasSchemaVersion for ParquetBlockMetadataTransaction {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetBlockMetadataTransaction {
     fn version(&self) -> i64 {
         self.txn_version
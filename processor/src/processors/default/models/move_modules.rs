@@ -4,7 +4,7 @@
 #![allow(clippy::extra_unused_lifetimes)]
 
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     schema::move_modules,
 };
 use allocative_derive::Allocative;
@@ -169,6 +169,10 @@ impl NamedTable for ParquetMoveModule {
     const TABLE_NAME: &'static str = "move_modules";
 }
 
+impl HasSchemaVersion for ParquetMoveModule {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 // TODO: revisit and remove this if we can. this is currently onlyed used to log the version of the table when the parquet is written.
 impl HasVersion for ParquetMoveModule {
     fn version(&self) -> i64 {
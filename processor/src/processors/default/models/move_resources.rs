@@ -3,7 +3,7 @@
 
 #![allow(clippy::extra_unused_lifetimes)]
 
-use crate::parquet_processors::parquet_utils::util::{HasVersion, NamedTable};
+use crate::parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable};
 use allocative_derive::Allocative;
 use anyhow::{Context, Result};
 use libra2_indexer_processor_sdk::{
@@ -193,6 +193,10 @@ impl NamedTable for ParquetMoveResource {
     const TABLE_NAME: &'static str = "move_resources";
 }
 
+impl HasSchemaVersion for ParquetMoveResource {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 // TODO: This is currently used to log the version of the txns being written to the Parquet file.
 // TODO: Revisit and see if we can remove this
 impl HasVersion for ParquetMoveResource {
@@ -1,12 +1,18 @@
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     schema::{current_table_items, table_items, table_metadatas},
 };
+use ahash::AHashMap;
 use allocative_derive::Allocative;
 use libra2_indexer_processor_sdk::{
-   libra2_protos::transaction::v1::{DeleteTableItem, WriteTableItem},
+   libra2_protos::transaction::v1::{
+        write_set_change::Change, DeleteTableItem, Transaction, WriteTableItem,
+    },
+    postgres::utils::database::DbPoolConnection,
     utils::{convert::standardize_address, extract::hash_str},
 };
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
 use field_count::FieldCount;
 use parquet_derive::ParquetRecordWriter;
 use serde::{Deserialize, Serialize};
@@ -111,6 +117,10 @@ impl NamedTable for ParquetTableItem {
     const TABLE_NAME: &'static str = "table_items";
 }
 
+impl HasSchemaVersion for ParquetTableItem {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetTableItem {
     fn version(&self) -> i64 {
         self.txn_version
@@ -165,6 +175,10 @@ impl NamedTable for ParquetCurrentTableItem {
     const TABLE_NAME: &'static str = "current_table_items";
 }
 
+impl HasSchemaVersion for ParquetCurrentTableItem {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCurrentTableItem {
     fn version(&self) -> i64 {
         self.last_transaction_version
@@ -192,6 +206,8 @@ pub struct TableMetadata {
     pub handle: String,
     pub key_type: String,
     pub value_type: String,
+    /// Address of the resource holding the table handle, if it was written in the same batch
+    pub owner_address: Option<String>,
 }
 
 impl TableMetadata {
@@ -200,6 +216,58 @@ impl TableMetadata {
             handle: table_item.handle.to_string(),
             key_type: table_item.data.as_ref().unwrap().key_type.clone(),
             value_type: table_item.data.as_ref().unwrap().value_type.clone(),
+            owner_address: None,
+        }
+    }
+
+    /// Mapping from standardized table handle to the address of the resource that holds it,
+    /// from every resource written in a batch of transactions. Later writes win.
+    pub fn get_table_handle_to_owner_from_transactions(
+        transactions: &[Transaction],
+    ) -> AHashMap<String, String> {
+        let mut table_handle_to_owner = AHashMap::new();
+        for transaction in transactions {
+            let Some(info) = transaction.info.as_ref() else {
+                continue;
+            };
+            for wsc in &info.changes {
+                if let Some(Change::WriteResource(write_resource)) = wsc.change.as_ref() {
+                    let Ok(data) = serde_json::from_str::<serde_json::Value>(&write_resource.data)
+                    else {
+                        continue;
+                    };
+                    let owner_address = standardize_address(&write_resource.address);
+                    let mut handles = vec![];
+                    Self::collect_table_handles(&data, &mut handles);
+                    for handle in handles {
+                        table_handle_to_owner.insert(handle, owner_address.clone());
+                    }
+                }
+            }
+        }
+        table_handle_to_owner
+    }
+
+    /// Tables show up in resource data as `{"handle": "0x..."}`, at any depth.
+    fn collect_table_handles(value: &serde_json::Value, handles: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(serde_json::Value::String(handle)) = map.get("handle") {
+                    if map.len() == 1 && handle.starts_with("0x") {
+                        handles.push(standardize_address(handle));
+                        return;
+                    }
+                }
+                for inner in map.values() {
+                    Self::collect_table_handles(inner, handles);
+                }
+            },
+            serde_json::Value::Array(values) => {
+                for inner in values {
+                    Self::collect_table_handles(inner, handles);
+                }
+            },
+            _ => {},
         }
     }
 }
@@ -217,6 +285,10 @@ impl NamedTable for ParquetTableMetadata {
     const TABLE_NAME: &'static str = "table_metadata";
 }
 
+impl HasSchemaVersion for ParquetTableMetadata {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetTableMetadata {
     fn version(&self) -> i64 {
         0 // This is a placeholder value to avoid a compile error
@@ -304,6 +376,7 @@ pub struct PostgresTableMetadata {
     pub handle: String,
     pub key_type: String,
     pub value_type: String,
+    pub owner_address: Option<String>,
 }
 
 impl From<TableMetadata> for PostgresTableMetadata {
@@ -312,6 +385,25 @@ impl From<TableMetadata> for PostgresTableMetadata {
             handle: base_item.handle,
             key_type: base_item.key_type,
             value_type: base_item.value_type,
+            owner_address: base_item.owner_address,
         }
     }
 }
+
+impl PostgresTableMetadata {
+    /// Returns (handle, owner_address) for the handles whose owner is known
+    pub async fn get_owners_by_handles(
+        handles: &[String],
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<Vec<(String, String)>> {
+        table_metadatas::table
+            .filter(table_metadatas::handle.eq_any(handles))
+            .filter(table_metadatas::owner_address.is_not_null())
+            .select((
+                table_metadatas::handle,
+                table_metadatas::owner_address.assume_not_null(),
+            ))
+            .load::<(String, String)>(conn)
+            .await
+    }
+}
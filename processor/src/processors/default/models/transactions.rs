@@ -7,7 +7,7 @@
 
 use super::write_set_changes::{WriteSetChangeDetail, WriteSetChangeModel};
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     utils::counters::PROCESSOR_UNKNOWN_TYPE_COUNT,
 };
 use allocative_derive::Allocative;
@@ -378,6 +378,10 @@ impl NamedTable for ParquetTransaction {
     const TABLE_NAME: &'static str = "transactions";
 }
 
+impl HasSchemaVersion for ParquetTransaction {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 // TODO: revisit and remove this if we can.
 impl HasVersion for ParquetTransaction {
     fn version(&self) -> i64 {
@@ -10,7 +10,7 @@ use super::{
     },
 };
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::default::models::move_resources::MoveResource,
 };
 use allocative_derive::Allocative;
@@ -290,6 +290,10 @@ impl NamedTable for ParquetWriteSetChange {
     const TABLE_NAME: &'static str = "write_set_changes";
 }
 
+impl HasSchemaVersion for ParquetWriteSetChange {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetWriteSetChange {
     fn version(&self) -> i64 {
         self.txn_version
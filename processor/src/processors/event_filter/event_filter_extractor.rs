@@ -0,0 +1,105 @@
+use libra2_indexer_processor_sdk::{
+    libra2_protos::transaction::v1::{transaction::TxnData, Transaction},
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::{convert::standardize_address, errors::ProcessorError},
+};
+use crate::processors::{
+    event_filter::{
+        event_filter_model::FilteredEvent, event_filter_processor::EventFilterProcessorConfig,
+    },
+    events::parse_events,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use rayon::prelude::*;
+use std::collections::HashSet;
+
+/// Keeps the events that match the configured event types and accounts.
+pub struct EventFilterExtractor
+where
+    Self: Sized + Send + 'static,
+{
+    config: EventFilterProcessorConfig,
+    account_addresses: HashSet<String>,
+    sender_addresses: HashSet<String>,
+}
+
+impl EventFilterExtractor {
+    pub fn new(config: EventFilterProcessorConfig) -> Self {
+        let standardize = |addresses: &[String]| {
+            addresses
+                .iter()
+                .map(|address| standardize_address(address))
+                .collect()
+        };
+        Self {
+            account_addresses: standardize(&config.account_addresses),
+            sender_addresses: standardize(&config.sender_addresses),
+            config,
+        }
+    }
+
+    fn filter_transaction(&self, txn: &Transaction) -> Result<Vec<FilteredEvent>> {
+        if !self.sender_addresses.is_empty() {
+            let sender = match txn.txn_data.as_ref() {
+                Some(TxnData::User(user_txn)) => user_txn
+                    .request
+                    .as_ref()
+                    .map(|request| standardize_address(&request.sender)),
+                _ => None,
+            };
+            if !sender.is_some_and(|sender| self.sender_addresses.contains(&sender)) {
+                return Ok(vec![]);
+            }
+        }
+
+        parse_events(txn, self.name().as_str())
+            .into_iter()
+            .filter(|event| {
+                (self.account_addresses.is_empty()
+                    || self.account_addresses.contains(&event.account_address))
+                    && (self.config.event_types.is_empty()
+                        || self
+                            .config
+                            .event_types
+                            .iter()
+                            .any(|pattern| pattern.matches(&event.type_)))
+            })
+            .map(|event| FilteredEvent::from_event(event, &self.config.columns))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Processable for EventFilterExtractor {
+    type Input = Vec<Transaction>;
+    type Output = Vec<FilteredEvent>;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        item: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<Vec<FilteredEvent>>>, ProcessorError> {
+        let events: Vec<Vec<FilteredEvent>> = item
+            .data
+            .par_iter()
+            .map(|txn| self.filter_transaction(txn))
+            .collect::<Result<_>>()
+            .map_err(|e| ProcessorError::ProcessError {
+                message: format!("{e:#}"),
+            })?;
+        Ok(Some(TransactionContext {
+            data: events.into_iter().flatten().collect(),
+            metadata: item.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for EventFilterExtractor {}
+
+impl NamedStep for EventFilterExtractor {
+    fn name(&self) -> String {
+        "EventFilterExtractor".to_string()
+    }
+}
@@ -0,0 +1,493 @@
+use crate::processors::events::events_model::Event;
+use anyhow::{Context, Result};
+use bigdecimal::BigDecimal;
+use libra2_indexer_processor_sdk::utils::convert::standardize_address;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{fmt, str::FromStr};
+use tracing::warn;
+
+static ADDRESS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"0x[0-9a-fA-F]+").unwrap());
+static COLUMN_NAME_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-z_][a-z0-9_]{0,62}$").unwrap());
+
+/// Columns of `filtered_events` that are always written, so they can't be projected to.
+pub const FILTERED_EVENT_COLUMNS: &[&str] = &[
+    "transaction_version",
+    "event_index",
+    "account_address",
+    "type",
+    "indexed_type",
+    "data",
+    "block_timestamp",
+    "inserted_at",
+];
+
+/// Standardizes every address in a Move type, so `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>`
+/// matches the same type written with long addresses.
+fn normalize_type(type_str: &str) -> String {
+    ADDRESS_RE
+        .replace_all(&type_str.replace(' ', ""), |caps: &regex::Captures| {
+            standardize_address(&caps[0])
+        })
+        .into_owned()
+}
+
+/// Splits the type arguments of a Move type at the top level commas.
+fn split_type_args(type_args: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in type_args.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(std::mem::take(&mut current));
+                continue;
+            },
+            _ => {},
+        }
+        current.push(c);
+    }
+    args.push(current);
+    args
+}
+
+/// A struct type split into `(address, module, name, type arguments)`. Returns `None` for types
+/// that aren't structs, like `vector<u8>`.
+fn parse_struct_type(type_str: &str) -> Option<(String, String, String, Option<Vec<String>>)> {
+    let type_str = normalize_type(type_str);
+    let (base, type_args) = match type_str.split_once('<') {
+        Some((base, rest)) => (
+            base.to_string(),
+            Some(split_type_args(rest.strip_suffix('>')?)),
+        ),
+        None => (type_str.clone(), None),
+    };
+    let mut parts = base.split("::");
+    let (address, module, name) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((
+        address.to_string(),
+        module.to_string(),
+        name.to_string(),
+        type_args,
+    ))
+}
+
+/// Matches the event types kept by the event filter processor.
+///
+/// Written as `address::module::name`, where any part can be `*`. Without type arguments, the
+/// pattern matches the struct with any type arguments. With type arguments, the event type must
+/// have the same number of them, and each one must be equal or matched by `*`. For example,
+/// `0x1::coin::CoinDeposit<*>` or `0xcafe::*::*`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EventTypePattern {
+    address: Option<String>,
+    module: Option<String>,
+    name: Option<String>,
+    type_args: Option<Vec<Option<String>>>,
+}
+
+fn wildcard(part: &str) -> Option<String> {
+    (part != "*").then(|| part.to_string())
+}
+
+fn part_matches(pattern: &Option<String>, value: &str) -> bool {
+    pattern.as_deref().is_none_or(|pattern| pattern == value)
+}
+
+impl EventTypePattern {
+    pub fn matches(&self, event_type: &str) -> bool {
+        let Some((address, module, name, type_args)) = parse_struct_type(event_type) else {
+            return false;
+        };
+        if !part_matches(&self.address, &address)
+            || !part_matches(&self.module, &module)
+            || !part_matches(&self.name, &name)
+        {
+            return false;
+        }
+        match (&self.type_args, type_args) {
+            (None, _) => true,
+            (Some(patterns), Some(type_args)) => {
+                patterns.len() == type_args.len()
+                    && patterns
+                        .iter()
+                        .zip(&type_args)
+                        .all(|(pattern, type_arg)| part_matches(pattern, type_arg))
+            },
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl FromStr for EventTypePattern {
+    type Err = anyhow::Error;
+
+    fn from_str(pattern: &str) -> Result<Self> {
+        let (base, type_args) = match pattern.trim().split_once('<') {
+            Some((base, rest)) => {
+                let type_args = rest.strip_suffix('>').ok_or_else(|| {
+                    anyhow::anyhow!("Event type pattern {pattern} is missing a closing '>'")
+                })?;
+                (base, Some(split_type_args(type_args)))
+            },
+            None => (pattern.trim(), None),
+        };
+        let parts: Vec<&str> = base.split("::").collect();
+        let [address, module, name] = parts.as_slice() else {
+            anyhow::bail!("Event type pattern {pattern} must be of the form address::module::name");
+        };
+        if [address, module, name].iter().any(|part| part.is_empty()) {
+            anyhow::bail!("Event type pattern {pattern} has an empty part");
+        }
+        Ok(Self {
+            address: wildcard(address).map(|address| standardize_address(&address)),
+            module: wildcard(module),
+            name: wildcard(name),
+            type_args: type_args.map(|type_args| {
+                type_args
+                    .iter()
+                    .map(|type_arg| wildcard(type_arg.trim()).map(|arg| normalize_type(&arg)))
+                    .collect()
+            }),
+        })
+    }
+}
+
+impl TryFrom<String> for EventTypePattern {
+    type Error = anyhow::Error;
+
+    fn try_from(pattern: String) -> Result<Self> {
+        pattern.parse()
+    }
+}
+
+impl fmt::Display for EventTypePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let part = |part: &Option<String>| part.clone().unwrap_or_else(|| "*".to_string());
+        write!(
+            f,
+            "{}::{}::{}",
+            part(&self.address),
+            part(&self.module),
+            part(&self.name)
+        )?;
+        if let Some(type_args) = &self.type_args {
+            let type_args: Vec<_> = type_args.iter().map(part).collect();
+            write!(f, "<{}>", type_args.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<EventTypePattern> for String {
+    fn from(pattern: EventTypePattern) -> Self {
+        pattern.to_string()
+    }
+}
+
+/// Postgres type of a column projected from the event data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectedColumnType {
+    Text,
+    BigInt,
+    Numeric,
+    Boolean,
+    Jsonb,
+}
+
+impl ProjectedColumnType {
+    pub fn sql_type(&self) -> &'static str {
+        match self {
+            ProjectedColumnType::Text => "TEXT",
+            ProjectedColumnType::BigInt => "BIGINT",
+            ProjectedColumnType::Numeric => "NUMERIC",
+            ProjectedColumnType::Boolean => "BOOLEAN",
+            ProjectedColumnType::Jsonb => "JSONB",
+        }
+    }
+
+    /// Converts a value of the event data to one that Postgres casts to this type, or returns
+    /// `None` if it can't be. Move integers above u32 are JSON strings, so numeric strings are
+    /// accepted for numbers.
+    pub fn convert(&self, value: &Value) -> Option<Value> {
+        match (self, value) {
+            (_, Value::Null) => None,
+            (ProjectedColumnType::Text | ProjectedColumnType::Jsonb, _) => Some(value.clone()),
+            (ProjectedColumnType::BigInt, Value::Number(number)) => {
+                number.as_i64().map(Value::from)
+            },
+            (ProjectedColumnType::BigInt, Value::String(value)) => {
+                value.parse::<i64>().ok().map(Value::from)
+            },
+            (ProjectedColumnType::Numeric, Value::Number(_)) => Some(value.clone()),
+            (ProjectedColumnType::Numeric, Value::String(number)) => {
+                // BigDecimal also parses digit separators, which Postgres doesn't
+                let is_numeric = !number.contains('_') && BigDecimal::from_str(number).is_ok();
+                is_numeric.then(|| value.clone())
+            },
+            (ProjectedColumnType::Boolean, Value::Bool(_)) => Some(value.clone()),
+            (ProjectedColumnType::Boolean, Value::String(value)) => {
+                value.parse::<bool>().ok().map(Value::Bool)
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A column of `filtered_events` filled from a field of the event data.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectedColumn {
+    // Name of the column
+    pub name: String,
+    // JSON pointer to the field in the event data, e.g. `/metadata/inner`
+    pub path: String,
+    #[serde(rename = "type")]
+    pub column_type: ProjectedColumnType,
+}
+
+impl ProjectedColumn {
+    pub fn validate(&self) -> Result<()> {
+        if !COLUMN_NAME_RE.is_match(&self.name) {
+            anyhow::bail!(
+                "Column name {} must be lowercase letters, digits and underscores",
+                self.name
+            );
+        }
+        if FILTERED_EVENT_COLUMNS.contains(&self.name.as_str()) {
+            anyhow::bail!(
+                "Column name {} is already a column of filtered_events",
+                self.name
+            );
+        }
+        if !self.path.is_empty() && !self.path.starts_with('/') {
+            anyhow::bail!(
+                "Path {} of column {} must be a JSON pointer starting with '/'",
+                self.path,
+                self.name
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A row of `filtered_events`. It's serialized to JSON to insert it, so the projected columns
+/// are converted to their types by Postgres. Values that can't be converted are left out, so
+/// they're NULL instead of failing the insert.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FilteredEvent {
+    pub transaction_version: i64,
+    pub event_index: i64,
+    pub account_address: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub indexed_type: String,
+    pub data: serde_json::Value,
+    pub block_timestamp: chrono::NaiveDateTime,
+    /// Values of the projected columns, missing for fields that aren't in the event data or
+    /// don't fit the column type.
+    #[serde(flatten)]
+    pub projected_columns: serde_json::Map<String, Value>,
+}
+
+impl FilteredEvent {
+    pub fn from_event(event: Event, columns: &[ProjectedColumn]) -> Result<Self> {
+        let data: Value = serde_json::from_str(&event.data).with_context(|| {
+            format!(
+                "Failed to parse the data of event {} in transaction {}",
+                event.event_index, event.transaction_version
+            )
+        })?;
+        let block_timestamp = event.block_timestamp.with_context(|| {
+            format!(
+                "Event {} in transaction {} has no block timestamp",
+                event.event_index, event.transaction_version
+            )
+        })?;
+        let projected_columns = columns
+            .iter()
+            .filter_map(|column| {
+                let value = data.pointer(&column.path)?;
+                let converted = column.column_type.convert(value);
+                if converted.is_none() && !value.is_null() {
+                    warn!(
+                        transaction_version = event.transaction_version,
+                        event_index = event.event_index,
+                        column = column.name,
+                        "Leaving projected column empty, {value} isn't a valid {}",
+                        column.column_type.sql_type()
+                    );
+                }
+                converted.map(|value| (column.name.clone(), value))
+            })
+            .collect();
+        Ok(Self {
+            transaction_version: event.transaction_version,
+            event_index: event.event_index,
+            account_address: event.account_address,
+            type_: event.type_,
+            indexed_type: event.indexed_type,
+            data,
+            block_timestamp,
+            projected_columns,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(pattern: &str) -> EventTypePattern {
+        pattern.parse().unwrap()
+    }
+
+    #[test]
+    fn test_event_type_pattern_matches() {
+        let deposit = pattern("0x1::coin::CoinDeposit");
+        assert!(deposit.matches("0x1::coin::CoinDeposit"));
+        assert!(deposit.matches(
+            "0x0000000000000000000000000000000000000000000000000000000000000001::coin::CoinDeposit"
+        ));
+        assert!(deposit.matches("0x1::coin::CoinDeposit<0x1::aptos_coin::AptosCoin>"));
+        assert!(!deposit.matches("0x1::coin::CoinWithdraw"));
+        assert!(!deposit.matches("vector<u8>"));
+
+        let generic = pattern("0x1::coin::CoinDeposit<*>");
+        assert!(generic.matches("0x1::coin::CoinDeposit<0x1::aptos_coin::AptosCoin>"));
+        assert!(!generic.matches("0x1::coin::CoinDeposit"));
+        assert!(!generic.matches("0x1::coin::CoinDeposit<u8, u64>"));
+
+        let exact = pattern("0x1::coin::CoinDeposit<0x01::aptos_coin::AptosCoin>");
+        assert!(exact.matches("0x1::coin::CoinDeposit<0x1::aptos_coin::AptosCoin>"));
+        assert!(!exact.matches("0x1::coin::CoinDeposit<0xcafe::usd::USD>"));
+
+        let nested = pattern("0xcafe::pool::Swap<*, 0x1::coin::Coin<u64>>");
+        assert!(nested.matches("0xcafe::pool::Swap<0x1::a::A, 0x1::coin::Coin<u64>>"));
+        assert!(!nested.matches("0xcafe::pool::Swap<0x1::a::A, 0x1::coin::Coin<u8>>"));
+
+        let module = pattern("0xcafe::*::*");
+        assert!(module.matches("0xcafe::pool::Swap<u8>"));
+        assert!(!module.matches("0xbeef::pool::Swap"));
+        assert!(pattern("*::*::Swap").matches("0xbeef::pool::Swap"));
+
+        assert!("0x1::coin".parse::<EventTypePattern>().is_err());
+        assert!("0x1::coin::CoinDeposit<u8"
+            .parse::<EventTypePattern>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_event_type_pattern_round_trip() {
+        let patterns: Vec<EventTypePattern> =
+            serde_json::from_str(r#"["0x1::coin::*<*>", "*::pool::Swap"]"#).unwrap();
+        assert_eq!(
+            serde_json::to_string(&patterns).unwrap(),
+            format!(
+                r#"["{}::coin::*<*>","*::pool::Swap"]"#,
+                standardize_address("0x1")
+            )
+        );
+    }
+
+    #[test]
+    fn test_filtered_event_projects_columns() {
+        let event = Event {
+            sequence_number: 0,
+            creation_number: 0,
+            account_address: standardize_address("0xcafe"),
+            transaction_version: 5,
+            transaction_block_height: 1,
+            type_: "0xcafe::pool::Swap".to_string(),
+            data: r#"{"amount_in": "100", "pool": {"inner": "0xbeef"}, "memo": null}"#.to_string(),
+            event_index: 2,
+            indexed_type: "0xcafe::pool::Swap".to_string(),
+            block_timestamp: Some(chrono::NaiveDateTime::default()),
+            type_tag_bytes: None,
+            total_bytes: None,
+        };
+        let column = |name: &str, path: &str, column_type| ProjectedColumn {
+            name: name.to_string(),
+            path: path.to_string(),
+            column_type,
+        };
+        let columns = vec![
+            column("amount_in", "/amount_in", ProjectedColumnType::Numeric),
+            column("pool", "/pool/inner", ProjectedColumnType::Text),
+            column("memo", "/memo", ProjectedColumnType::Text),
+            column("missing", "/missing", ProjectedColumnType::BigInt),
+        ];
+        columns.iter().for_each(|column| column.validate().unwrap());
+
+        let row = serde_json::to_value(FilteredEvent::from_event(event.clone(), &columns).unwrap())
+            .unwrap();
+        assert_eq!(row["amount_in"], "100");
+        assert_eq!(row["pool"], "0xbeef");
+        assert_eq!(row["type"], "0xcafe::pool::Swap");
+        assert!(row.get("memo").is_none());
+        assert!(row.get("missing").is_none());
+
+        assert!(column("Bad-Name", "/a", ProjectedColumnType::Text)
+            .validate()
+            .is_err());
+        assert!(column("data", "/a", ProjectedColumnType::Text)
+            .validate()
+            .is_err());
+        assert!(column("amount", "amount", ProjectedColumnType::Text)
+            .validate()
+            .is_err());
+
+        // Events without valid data are errors instead of panics
+        let bad_event = Event {
+            data: "not json".to_string(),
+            ..event.clone()
+        };
+        assert!(FilteredEvent::from_event(bad_event, &columns).is_err());
+        let bad_event = Event {
+            block_timestamp: None,
+            ..event
+        };
+        assert!(FilteredEvent::from_event(bad_event, &columns).is_err());
+    }
+
+    #[test]
+    fn test_projected_column_type_convert() {
+        use serde_json::json;
+        use ProjectedColumnType::*;
+
+        let cases = [
+            (BigInt, json!(5), Some(json!(5))),
+            (BigInt, json!("5"), Some(json!(5))),
+            (BigInt, json!("abc"), None),
+            (BigInt, json!(u64::MAX.to_string()), None),
+            (BigInt, json!(1.5), None),
+            (Numeric, json!("1.5"), Some(json!("1.5"))),
+            (Numeric, json!(1.5), Some(json!(1.5))),
+            (Numeric, json!("0xbeef"), None),
+            (Numeric, json!("1_000"), None),
+            (Numeric, json!({"a": 1}), None),
+            (Boolean, json!(true), Some(json!(true))),
+            (Boolean, json!("false"), Some(json!(false))),
+            (Boolean, json!(1), None),
+            (Text, json!(1), Some(json!(1))),
+            (Jsonb, json!({"a": 1}), Some(json!({"a": 1}))),
+            (Jsonb, Value::Null, None),
+        ];
+        for (column_type, value, expected) in cases {
+            assert_eq!(
+                column_type.convert(&value),
+                expected,
+                "{value} as {column_type:?}"
+            );
+        }
+    }
+}
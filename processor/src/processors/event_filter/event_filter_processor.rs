@@ -0,0 +1,189 @@
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::IndexerProcessorConfig,
+        processor_config::{DefaultProcessorConfig, ProcessorConfig},
+    },
+    processors::{
+        event_filter::{
+            event_filter_extractor::EventFilterExtractor,
+            event_filter_model::{EventTypePattern, ProjectedColumn},
+            event_filter_storer::{add_projected_columns, EventFilterStorer},
+        },
+        processor_status_saver::{
+            get_end_version, get_starting_version, PostgresProcessorStatusSaver,
+        },
+    },
+    MIGRATIONS,
+};
+use anyhow::Result;
+use libra2_indexer_processor_sdk::{
+    builder::ProcessorBuilder,
+    common_steps::{
+        TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+    },
+    libra2_indexer_transaction_stream::TransactionStreamConfig,
+    postgres::utils::{
+        checkpoint::PostgresChainIdChecker,
+        database::{new_db_pool, run_migrations, ArcDbPool},
+    },
+    traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    utils::chain_id_check::check_or_update_chain_id,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::{debug, info};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventFilterProcessorConfig {
+    #[serde(flatten)]
+    pub default_config: DefaultProcessorConfig,
+    // Event types to keep, e.g. `0xcafe::pool::Swap` or `0x1::coin::CoinDeposit<*>`. Any part
+    // can be `*`. If empty, events of every type are kept.
+    #[serde(default)]
+    pub event_types: Vec<EventTypePattern>,
+    // Only keep events emitted by these accounts. Module events are emitted by 0x0.
+    #[serde(default)]
+    pub account_addresses: Vec<String>,
+    // Only keep events of user transactions sent by these accounts
+    #[serde(default)]
+    pub sender_addresses: Vec<String>,
+    // Columns of `filtered_events` to fill from fields of the event data
+    #[serde(default)]
+    pub columns: Vec<ProjectedColumn>,
+}
+
+impl EventFilterProcessorConfig {
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for column in &self.columns {
+            column.validate()?;
+            if !names.insert(column.name.as_str()) {
+                anyhow::bail!("Column {} is declared more than once", column.name);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct EventFilterProcessor {
+    pub config: IndexerProcessorConfig,
+    pub db_pool: ArcDbPool,
+}
+
+impl EventFilterProcessor {
+    pub async fn new(config: IndexerProcessorConfig) -> Result<Self> {
+        match config.db_config {
+            DbConfig::PostgresConfig(ref postgres_config) => {
+                let conn_pool = new_db_pool(
+                    &postgres_config.connection_string,
+                    Some(postgres_config.db_pool_size),
+                )
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to create connection pool for PostgresConfig: {:?}",
+                        e
+                    )
+                })?;
+
+                Ok(Self {
+                    config,
+                    db_pool: conn_pool,
+                })
+            },
+            _ => Err(anyhow::anyhow!(
+                "Invalid db config for EventFilterProcessor {:?}",
+                config.db_config
+            )),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for EventFilterProcessor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    /// Writes the events that match the config to `filtered_events`, instead of every event like
+    /// the events processor.
+    async fn run_processor(&self) -> Result<()> {
+        let processor_config = match self.config.processor_config.clone() {
+            ProcessorConfig::EventFilterProcessor(processor_config) => processor_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor config for EventFilterProcessor: {:?}",
+                    self.config.processor_config
+                ))
+            },
+        };
+        processor_config.validate()?;
+
+        // Run migrations
+        if let DbConfig::PostgresConfig(ref postgres_config) = self.config.db_config {
+            run_migrations(
+                postgres_config.connection_string.clone(),
+                self.db_pool.clone(),
+                MIGRATIONS,
+            )
+            .await;
+        }
+        add_projected_columns(self.db_pool.clone(), &processor_config.columns).await?;
+
+        //  Merge the starting version from config and the latest processed version from the DB
+        let (starting_version, ending_version) = (
+            get_starting_version(&self.config, self.db_pool.clone()).await?,
+            get_end_version(&self.config, self.db_pool.clone()).await?,
+        );
+
+        // Check and update the ledger chain id to ensure we're indexing the correct chain
+        check_or_update_chain_id(
+            &self.config.transaction_stream_config,
+            &PostgresChainIdChecker::new(self.db_pool.clone()),
+        )
+        .await?;
+
+        let channel_size = processor_config.default_config.channel_size;
+
+        // Define processor steps
+        let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+            starting_version,
+            request_ending_version: ending_version,
+            ..self.config.transaction_stream_config.clone()
+        })
+        .await?;
+        let event_filter_extractor = EventFilterExtractor::new(processor_config.clone());
+        let event_filter_storer = EventFilterStorer::new(self.db_pool.clone(), processor_config);
+        let version_tracker = VersionTrackerStep::new(
+            PostgresProcessorStatusSaver::new(self.config.clone(), self.db_pool.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        // Connect processor steps together
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+            transaction_stream.into_runnable_step(),
+        )
+        .connect_to(event_filter_extractor.into_runnable_step(), channel_size)
+        .connect_to(event_filter_storer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+
+        // (Optional) Parse the results
+        loop {
+            match buffer_receiver.recv().await {
+                Ok(txn_context) => {
+                    debug!(
+                        "Finished filtering events from versions [{:?}, {:?}]",
+                        txn_context.metadata.start_version, txn_context.metadata.end_version,
+                    );
+                },
+                Err(e) => {
+                    info!("No more transactions in channel: {:?}", e);
+                    break Ok(());
+                },
+            }
+        }
+    }
+}
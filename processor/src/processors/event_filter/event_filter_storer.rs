@@ -0,0 +1,202 @@
+use crate::processors::event_filter::{
+    event_filter_model::{FilteredEvent, ProjectedColumn},
+    event_filter_processor::EventFilterProcessorConfig,
+};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::{
+    postgres::utils::database::ArcDbPool,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use diesel::sql_types::Jsonb;
+use diesel_async::RunQueryDsl;
+use tracing::{debug, info};
+
+pub const FILTERED_EVENTS_TABLE: &str = "filtered_events";
+/// Rows are sent as a single JSON parameter, so chunks are smaller than for regular inserts.
+const DEFAULT_FILTERED_EVENTS_CHUNK_SIZE: usize = 1000;
+
+/// Adds the projected columns that `filtered_events` doesn't have yet. Existing columns are left
+/// as they are, even if their type differs from the config.
+pub async fn add_projected_columns(
+    conn_pool: ArcDbPool,
+    columns: &[ProjectedColumn],
+) -> Result<()> {
+    let mut conn = conn_pool
+        .get()
+        .await
+        .context("Failed to get database connection")?;
+    for column in columns {
+        info!(
+            column = column.name,
+            column_type = column.column_type.sql_type(),
+            "Adding projected column to {FILTERED_EVENTS_TABLE}"
+        );
+        diesel::sql_query(format!(
+            "ALTER TABLE {FILTERED_EVENTS_TABLE} ADD COLUMN IF NOT EXISTS {} {}",
+            column.name,
+            column.column_type.sql_type()
+        ))
+        .execute(&mut conn)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to add column {} to {FILTERED_EVENTS_TABLE}",
+                column.name
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Builds an upsert of the rows in the JSON array bound as `$1`. `jsonb_to_recordset` converts
+/// every field to the type of its column, so projected values like u64 strings become numbers.
+/// `FilteredEvent::from_event` drops the values it can't convert, so one bad value doesn't fail
+/// the whole chunk.
+fn insert_filtered_events_query(columns: &[ProjectedColumn]) -> String {
+    let base_columns = [
+        ("transaction_version", "BIGINT"),
+        ("event_index", "BIGINT"),
+        ("account_address", "VARCHAR(66)"),
+        ("type", "TEXT"),
+        ("indexed_type", "VARCHAR(300)"),
+        ("data", "JSONB"),
+        ("block_timestamp", "TIMESTAMP"),
+    ];
+    let all_columns: Vec<(&str, &str)> = base_columns
+        .into_iter()
+        .chain(
+            columns
+                .iter()
+                .map(|column| (column.name.as_str(), column.column_type.sql_type())),
+        )
+        .collect();
+    let names: Vec<&str> = all_columns.iter().map(|(name, _)| *name).collect();
+    let definitions: Vec<String> = all_columns
+        .iter()
+        .map(|(name, sql_type)| format!("{name} {sql_type}"))
+        .collect();
+    let updates: Vec<String> = names
+        .iter()
+        .skip(2)
+        .chain(["inserted_at"].iter())
+        .map(|name| format!("{name} = EXCLUDED.{name}"))
+        .collect();
+    format!(
+        "INSERT INTO {FILTERED_EVENTS_TABLE} ({names}) \
+         SELECT {names} FROM jsonb_to_recordset($1) AS filtered({definitions}) \
+         ON CONFLICT (transaction_version, event_index) DO UPDATE SET {updates}",
+        names = names.join(", "),
+        definitions = definitions.join(", "),
+        updates = updates.join(", "),
+    )
+}
+
+pub struct EventFilterStorer
+where
+    Self: Sized + Send + 'static,
+{
+    conn_pool: ArcDbPool,
+    chunk_size: usize,
+    insert_query: String,
+}
+
+impl EventFilterStorer {
+    pub fn new(conn_pool: ArcDbPool, processor_config: EventFilterProcessorConfig) -> Self {
+        Self {
+            conn_pool,
+            chunk_size: processor_config
+                .default_config
+                .per_table_chunk_sizes
+                .get(FILTERED_EVENTS_TABLE)
+                .copied()
+                .unwrap_or(DEFAULT_FILTERED_EVENTS_CHUNK_SIZE),
+            insert_query: insert_filtered_events_query(&processor_config.columns),
+        }
+    }
+
+    async fn insert(&self, events: &[FilteredEvent]) -> Result<()> {
+        let mut conn = self
+            .conn_pool
+            .get()
+            .await
+            .context("Failed to get database connection")?;
+        for chunk in events.chunks(self.chunk_size) {
+            diesel::sql_query(&self.insert_query)
+                .bind::<Jsonb, _>(serde_json::to_value(chunk)?)
+                .execute(&mut conn)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Processable for EventFilterStorer {
+    type Input = Vec<FilteredEvent>;
+    type Output = ();
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        events: TransactionContext<Vec<FilteredEvent>>,
+    ) -> Result<Option<TransactionContext<()>>, ProcessorError> {
+        match self.insert(&events.data).await {
+            Ok(_) => {
+                debug!(
+                    "Filtered events version [{}, {}] stored successfully",
+                    events.metadata.start_version, events.metadata.end_version
+                );
+                Ok(Some(TransactionContext {
+                    data: (),
+                    metadata: events.metadata,
+                }))
+            },
+            Err(e) => Err(ProcessorError::DBStoreError {
+                message: format!(
+                    "Failed to store filtered events versions {} to {}: {:?}",
+                    events.metadata.start_version, events.metadata.end_version, e,
+                ),
+                query: None,
+            }),
+        }
+    }
+}
+
+impl AsyncStep for EventFilterStorer {}
+
+impl NamedStep for EventFilterStorer {
+    fn name(&self) -> String {
+        "EventFilterStorer".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::event_filter::event_filter_model::ProjectedColumnType;
+
+    #[test]
+    fn test_insert_filtered_events_query() {
+        let query = insert_filtered_events_query(&[ProjectedColumn {
+            name: "amount".to_string(),
+            path: "/amount".to_string(),
+            column_type: ProjectedColumnType::Numeric,
+        }]);
+        assert_eq!(
+            query,
+            "INSERT INTO filtered_events (transaction_version, event_index, account_address, \
+             type, indexed_type, data, block_timestamp, amount) SELECT transaction_version, \
+             event_index, account_address, type, indexed_type, data, block_timestamp, amount \
+             FROM jsonb_to_recordset($1) AS filtered(transaction_version BIGINT, event_index \
+             BIGINT, account_address VARCHAR(66), type TEXT, indexed_type VARCHAR(300), data \
+             JSONB, block_timestamp TIMESTAMP, amount NUMERIC) ON CONFLICT (transaction_version, \
+             event_index) DO UPDATE SET account_address = EXCLUDED.account_address, type = \
+             EXCLUDED.type, indexed_type = EXCLUDED.indexed_type, data = EXCLUDED.data, \
+             block_timestamp = EXCLUDED.block_timestamp, amount = EXCLUDED.amount, inserted_at = \
+             EXCLUDED.inserted_at"
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod event_filter_extractor;
+pub mod event_filter_model;
+pub mod event_filter_processor;
+pub mod event_filter_storer;
@@ -1,7 +1,7 @@
 #![allow(clippy::extra_unused_lifetimes)]
 
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     schema::events,
     utils::counters::PROCESSOR_UNKNOWN_TYPE_COUNT,
 };
@@ -145,6 +145,10 @@ impl NamedTable for ParquetEvent {
     const TABLE_NAME: &'static str = "events";
 }
 
+impl HasSchemaVersion for ParquetEvent {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetEvent {
     fn version(&self) -> i64 {
         self.txn_version
@@ -7,15 +7,18 @@
 
 use super::coin_utils::{CoinInfoType, CoinResource};
 use crate::{
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::fungible_asset::fungible_asset_models::v2_fungible_asset_activities::EventToCoinType,
     schema::{coin_balances, current_coin_balances},
 };
 use ahash::AHashMap;
+use allocative_derive::Allocative;
 use libra2_indexer_processor_sdk::{
    libra2_protos::transaction::v1::WriteResource, utils::convert::standardize_address,
 };
 use bigdecimal::BigDecimal;
 use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
@@ -97,3 +100,44 @@ impl CoinBalance {
         }
     }
 }
+
+/// This is a parquet version of CoinBalance
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetCoinBalance {
+    pub txn_version: i64,
+    pub owner_address: String,
+    pub coin_type_hash: String,
+    pub coin_type: String,
+    pub amount: String, // it is a string representation of the u64
+    #[allocative(skip)]
+    pub block_timestamp: chrono::NaiveDateTime,
+}
+
+impl NamedTable for ParquetCoinBalance {
+    const TABLE_NAME: &'static str = "coin_balances";
+}
+
+impl HasSchemaVersion for ParquetCoinBalance {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetCoinBalance {
+    fn version(&self) -> i64 {
+        self.txn_version
+    }
+}
+
+impl From<CoinBalance> for ParquetCoinBalance {
+    fn from(raw: CoinBalance) -> Self {
+        Self {
+            txn_version: raw.transaction_version,
+            owner_address: raw.owner_address,
+            coin_type_hash: raw.coin_type_hash,
+            coin_type: raw.coin_type,
+            amount: raw.amount.to_string(),
+            block_timestamp: raw.transaction_timestamp,
+        }
+    }
+}
@@ -6,9 +6,14 @@
 #![allow(clippy::unused_unit)]
 
 use super::coin_utils::{CoinInfoType, CoinResource};
-use crate::schema::coin_infos;
+use crate::{
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
+    schema::coin_infos,
+};
+use allocative_derive::Allocative;
 use libra2_indexer_processor_sdk::libra2_protos::transaction::v1::WriteResource;
 use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
@@ -65,3 +70,52 @@ impl CoinInfo {
         }
     }
 }
+
+/// This is a parquet version of CoinInfo
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetCoinInfo {
+    pub coin_type_hash: String,
+    pub coin_type: String,
+    pub transaction_version_created: i64,
+    pub creator_address: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: i32,
+    #[allocative(skip)]
+    pub transaction_created_timestamp: chrono::NaiveDateTime,
+    pub supply_aggregator_table_handle: Option<String>,
+    pub supply_aggregator_table_key: Option<String>,
+}
+
+impl NamedTable for ParquetCoinInfo {
+    const TABLE_NAME: &'static str = "coin_infos";
+}
+
+impl HasSchemaVersion for ParquetCoinInfo {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetCoinInfo {
+    fn version(&self) -> i64 {
+        self.transaction_version_created
+    }
+}
+
+impl From<CoinInfo> for ParquetCoinInfo {
+    fn from(raw: CoinInfo) -> Self {
+        Self {
+            coin_type_hash: raw.coin_type_hash,
+            coin_type: raw.coin_type,
+            transaction_version_created: raw.transaction_version_created,
+            creator_address: raw.creator_address,
+            name: raw.name,
+            symbol: raw.symbol,
+            decimals: raw.decimals,
+            transaction_created_timestamp: raw.transaction_created_timestamp,
+            supply_aggregator_table_handle: raw.supply_aggregator_table_handle,
+            supply_aggregator_table_key: raw.supply_aggregator_table_key,
+        }
+    }
+}
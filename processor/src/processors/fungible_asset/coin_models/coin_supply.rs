@@ -6,16 +6,20 @@
 #![allow(clippy::unused_unit)]
 
 use crate::{
+    db::resources::V2FungibleAssetResource,
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::default::models::table_items::{PostgresTableItem, TableItem},
     schema::coin_supply,
 };
+use allocative_derive::Allocative;
 use anyhow::Context;
 use libra2_indexer_processor_sdk::{
-   libra2_protos::transaction::v1::WriteTableItem,
-    utils::{constants::LIBRA2_COIN_TYPE_STR, extract::hash_str},
+   libra2_protos::transaction::v1::{WriteResource, WriteTableItem},
+    utils::{constants::LIBRA2_COIN_TYPE_STR, convert::standardize_address, extract::hash_str},
 };
 use bigdecimal::BigDecimal;
 use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
 use serde::{Deserialize, Serialize};
 const LIBRA2_COIN_SUPPLY_TABLE_HANDLE: &str =
     "0x1b854694ae746cdbd8d44186ca4929b2b337df21d1c74633be19b2710552fdca";
@@ -93,4 +97,72 @@ impl CoinSupply {
         }
         Ok(None)
     }
+
+    /// Supply of a v2 fungible asset, from the `ConcurrentFungibleAssetSupply` or
+    /// `FungibleAssetSupply` resource at its metadata address. The metadata address is used as
+    /// the coin type, like `asset_type` in the other fungible asset tables.
+    pub fn get_v2_from_write_resource(
+        write_resource: &WriteResource,
+        txn_version: i64,
+        txn_timestamp: chrono::NaiveDateTime,
+        txn_epoch: i64,
+    ) -> anyhow::Result<Option<Self>> {
+        let supply = match V2FungibleAssetResource::from_write_resource(write_resource)? {
+            Some(V2FungibleAssetResource::ConcurrentFungibleAssetSupply(inner)) => {
+                inner.current.value
+            },
+            Some(V2FungibleAssetResource::FungibleAssetSupply(inner)) => inner.current,
+            _ => return Ok(None),
+        };
+        let asset_type = standardize_address(&write_resource.address);
+        Ok(Some(Self {
+            transaction_version: txn_version,
+            coin_type_hash: hash_str(&asset_type),
+            coin_type: asset_type,
+            supply,
+            transaction_timestamp: txn_timestamp,
+            transaction_epoch: txn_epoch,
+        }))
+    }
+}
+
+/// This is a parquet version of CoinSupply
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetCoinSupply {
+    pub txn_version: i64,
+    pub coin_type_hash: String,
+    pub coin_type: String,
+    pub supply: String, // it is a string representation of the u128
+    #[allocative(skip)]
+    pub block_timestamp: chrono::NaiveDateTime,
+    pub transaction_epoch: i64,
+}
+
+impl NamedTable for ParquetCoinSupply {
+    const TABLE_NAME: &'static str = "coin_supply";
+}
+
+impl HasSchemaVersion for ParquetCoinSupply {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetCoinSupply {
+    fn version(&self) -> i64 {
+        self.txn_version
+    }
+}
+
+impl From<CoinSupply> for ParquetCoinSupply {
+    fn from(raw: CoinSupply) -> Self {
+        Self {
+            txn_version: raw.transaction_version,
+            coin_type_hash: raw.coin_type_hash,
+            coin_type: raw.coin_type,
+            supply: raw.supply.to_string(),
+            block_timestamp: raw.transaction_timestamp,
+            transaction_epoch: raw.transaction_epoch,
+        }
+    }
 }
@@ -10,7 +10,7 @@ use super::{
     v2_fungible_asset_utils::FungibleAssetStoreDeletionEvent,
 };
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         fungible_asset::{
             coin_models::{
@@ -345,6 +345,10 @@ impl NamedTable for ParquetFungibleAssetActivity {
     const TABLE_NAME: &'static str = "fungible_asset_activities";
 }
 
+impl HasSchemaVersion for ParquetFungibleAssetActivity {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetFungibleAssetActivity {
     fn version(&self) -> i64 {
         self.txn_version
@@ -13,7 +13,7 @@ use super::{
 };
 use crate::{
     db::resources::FromWriteResource,
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         default::models::move_resources::MoveResource,
         fungible_asset::{
@@ -419,6 +419,10 @@ impl NamedTable for ParquetFungibleAssetBalance {
     const TABLE_NAME: &'static str = "fungible_asset_balances";
 }
 
+impl HasSchemaVersion for ParquetFungibleAssetBalance {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetFungibleAssetBalance {
     fn version(&self) -> i64 {
         self.txn_version
@@ -466,6 +470,10 @@ impl NamedTable for ParquetCurrentUnifiedFungibleAssetBalance {
     const TABLE_NAME: &'static str = "current_fungible_asset_balances";
 }
 
+impl HasSchemaVersion for ParquetCurrentUnifiedFungibleAssetBalance {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 /// This will be deprecated.
 impl HasVersion for ParquetCurrentUnifiedFungibleAssetBalance {
     fn version(&self) -> i64 {
@@ -7,7 +7,7 @@
 
 use super::v2_fungible_metadata::FungibleAssetMetadataModel;
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::fungible_asset::fungible_asset_models::v2_fungible_asset_balances::get_paired_metadata_address,
     schema::fungible_asset_to_coin_mappings,
 };
@@ -207,6 +207,10 @@ impl NamedTable for ParquetFungibleAssetToCoinMapping {
     const TABLE_NAME: &'static str = "fungible_asset_to_coin_mappings";
 }
 
+impl HasSchemaVersion for ParquetFungibleAssetToCoinMapping {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetFungibleAssetToCoinMapping {
     fn version(&self) -> i64 {
         self.last_transaction_version
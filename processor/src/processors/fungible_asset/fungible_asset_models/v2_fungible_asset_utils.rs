@@ -289,5 +289,18 @@ mod tests {
         }
     }
 
-    // TODO: Add similar tests for ConcurrentFungibleAssetSupply.
+    #[test]
+    fn test_concurrent_fungible_asset_supply() {
+        let test = r#"{"current": {"max_value": "5000", "value": "100"}}"#;
+        let test: serde_json::Value = serde_json::from_str(test).unwrap();
+        let supply = serde_json::from_value(test)
+            .map(V2FungibleAssetResource::ConcurrentFungibleAssetSupply)
+            .unwrap();
+        if let V2FungibleAssetResource::ConcurrentFungibleAssetSupply(supply) = supply {
+            assert_eq!(supply.current.value, BigDecimal::from(100));
+            assert_eq!(supply.current.max_value, BigDecimal::from(5000));
+        } else {
+            panic!("Wrong type")
+        }
+    }
 }
@@ -7,7 +7,7 @@
 
 use crate::{
     db::resources::FromWriteResource,
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         fungible_asset::{
             coin_models::coin_utils::{CoinInfoType, CoinResource},
@@ -223,6 +223,10 @@ impl NamedTable for ParquetFungibleAssetMetadataModel {
     const TABLE_NAME: &'static str = "fungible_asset_metadata";
 }
 
+impl HasSchemaVersion for ParquetFungibleAssetMetadataModel {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetFungibleAssetMetadataModel {
     fn version(&self) -> i64 {
         self.last_transaction_version
@@ -6,7 +6,9 @@ use crate::{
     db::resources::{FromWriteResource, V2FungibleAssetResource},
     processors::{
         fungible_asset::{
-            coin_models::coin_supply::CoinSupply,
+            coin_models::{
+                coin_balances::CoinBalance, coin_infos::CoinInfo, coin_supply::CoinSupply,
+            },
             fungible_asset_models::{
                 v2_fungible_asset_activities::{EventToCoinType, FungibleAssetActivity},
                 v2_fungible_asset_balances::{
@@ -28,7 +30,8 @@ use crate::{
 };
 use ahash::AHashMap;
 use libra2_indexer_processor_sdk::{
-   libra2_protos::transaction::v1::{transaction::TxnData, write_set_change::Change, Transaction},
+    libra2_indexer_transaction_stream::utils::time::parse_timestamp,
+    libra2_protos::transaction::v1::{transaction::TxnData, write_set_change::Change, Transaction},
     utils::{convert::standardize_address, extract::get_entry_function_from_user_request},
 };
 use chrono::NaiveDateTime;
@@ -84,6 +87,69 @@ pub async fn get_fa_to_coin_mapping(transactions: &[Transaction]) -> FungibleAss
     kv_mapping
 }
 
+/// Gets coin v1 infos and balances from the `CoinInfo` and `CoinStore` resources in transactions.
+/// A coin info can't be modified, so only the first one written for each coin type is kept.
+pub fn parse_v1_coin(transactions: &[Transaction]) -> (Vec<CoinInfo>, Vec<CoinBalance>) {
+    let data: Vec<_> = transactions
+        .par_iter()
+        .map(|txn| {
+            let mut coin_infos = vec![];
+            let mut coin_balances = vec![];
+
+            let txn_version = txn.version as i64;
+            let txn_timestamp =
+                parse_timestamp(txn.timestamp.as_ref().unwrap(), txn_version).naive_utc();
+            let transaction_info = txn.info.as_ref().expect("Transaction info doesn't exist!");
+            for (index, wsc) in transaction_info.changes.iter().enumerate() {
+                if let Change::WriteResource(wr) = wsc.change.as_ref().unwrap() {
+                    if let Some(coin_info) =
+                        CoinInfo::from_write_resource(wr, txn_version, txn_timestamp, index as i64)
+                            .unwrap_or_else(|e| {
+                                tracing::error!(
+                                    transaction_version = txn_version,
+                                    index = index,
+                                    error = ?e,
+                                    "[Parser] error parsing coin info");
+                                panic!("[Parser] error parsing coin info");
+                            })
+                    {
+                        coin_infos.push(coin_info);
+                    }
+                    if let Some((coin_balance, _, _)) = CoinBalance::from_write_resource(
+                        wr,
+                        txn_version,
+                        txn_timestamp,
+                        index as i64,
+                    )
+                    .unwrap_or_else(|e| {
+                        tracing::error!(
+                                    transaction_version = txn_version,
+                                    index = index,
+                                    error = ?e,
+                                    "[Parser] error parsing coin balance");
+                        panic!("[Parser] error parsing coin balance");
+                    }) {
+                        coin_balances.push(coin_balance);
+                    }
+                }
+            }
+            (coin_infos, coin_balances)
+        })
+        .collect();
+
+    let mut all_coin_infos: AHashMap<String, CoinInfo> = AHashMap::new();
+    let mut all_coin_balances = vec![];
+    for (coin_infos, mut coin_balances) in data {
+        for coin_info in coin_infos {
+            all_coin_infos
+                .entry(coin_info.coin_type_hash.clone())
+                .or_insert(coin_info);
+        }
+        all_coin_balances.append(&mut coin_balances);
+    }
+    (all_coin_infos.into_values().collect(), all_coin_balances)
+}
+
 /// TODO: After the migration is complete, we can move this to common models folder
 /// V2 coin is called fungible assets and this flow includes all data from V1 in coin_processor
 pub async fn parse_v2_coin(
@@ -343,7 +409,7 @@ pub async fn parse_v2_coin(
                 }
             }
 
-            // Loop 5 to handle write set changes for metadata, balance, and v1 supply
+            // Loop 5 to handle write set changes for metadata, balance, and supply
             for (index, wsc) in transaction_info.changes.iter().enumerate() {
                 match wsc.change.as_ref().unwrap() {
                     Change::WriteResource(write_resource) => {
@@ -407,6 +473,22 @@ pub async fn parse_v2_coin(
                         }) {
                             fungible_asset_balances.push(balance);
                         }
+                        if let Some(coin_supply) = CoinSupply::get_v2_from_write_resource(
+                            write_resource,
+                            txn_version,
+                            txn_timestamp,
+                            txn_epoch,
+                        )
+                        .unwrap_or_else(|e| {
+                            tracing::error!(
+                                    transaction_version = txn_version,
+                                    index = index,
+                                    error = ?e,
+                                    "[Parser] error parsing fungible asset supply v2");
+                            panic!("[Parser] error parsing fungible asset supply v2");
+                        }) {
+                            all_coin_supply.push(coin_supply);
+                        }
                     },
                     Change::WriteTableItem(table_item) => {
                         if let Some(coin_supply) = CoinSupply::from_write_table_item(
@@ -496,3 +578,135 @@ pub async fn parse_v2_coin(
         fa_to_coin_mapping,
     )
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::processors::fungible_asset::coin_models::{
+        coin_balances::ParquetCoinBalance, coin_infos::ParquetCoinInfo,
+    };
+    use bigdecimal::BigDecimal;
+    use libra2_indexer_processor_sdk::libra2_protos::{
+        transaction::v1::{
+            move_type::Content, MoveStructTag, MoveType, TransactionInfo, WriteResource,
+            WriteSetChange,
+        },
+        util::timestamp::Timestamp,
+    };
+
+    pub const COIN_TYPE: &str = "0x1::libra2_coin::Libra2Coin";
+    pub const OWNER_ADDRESS: &str =
+        "0x000000000000000000000000000000000000000000000000000000000000a11c";
+
+    /// Writes a `0x1::coin::{name}<0x1::libra2_coin::Libra2Coin>` resource.
+    fn coin_resource(name: &str, address: &str, data: serde_json::Value) -> WriteSetChange {
+        let coin_type = MoveType {
+            content: Some(Content::Struct(MoveStructTag {
+                address: "0x1".to_string(),
+                module: "libra2_coin".to_string(),
+                name: "Libra2Coin".to_string(),
+                generic_type_params: vec![],
+            })),
+            ..MoveType::default()
+        };
+        WriteSetChange {
+            change: Some(Change::WriteResource(WriteResource {
+                address: address.to_string(),
+                type_str: format!("0x1::coin::{name}<{COIN_TYPE}>"),
+                r#type: Some(MoveStructTag {
+                    address: "0x1".to_string(),
+                    module: "coin".to_string(),
+                    name: name.to_string(),
+                    generic_type_params: vec![coin_type],
+                }),
+                data: data.to_string(),
+                ..WriteResource::default()
+            })),
+            ..WriteSetChange::default()
+        }
+    }
+
+    /// A transaction that writes the coin info of Libra2Coin and a coin store of `amount`.
+    pub fn coin_transaction(version: u64, amount: u64) -> Transaction {
+        let coin_info = serde_json::json!({
+            "name": "Libra2 Coin",
+            "symbol": "LBT",
+            "decimals": 8,
+            "supply": { "vec": [] },
+        });
+        let coin_store = serde_json::json!({
+            "coin": { "value": amount.to_string() },
+            "deposit_events": { "guid": { "id": { "addr": OWNER_ADDRESS, "creation_num": "2" } } },
+            "withdraw_events": { "guid": { "id": { "addr": OWNER_ADDRESS, "creation_num": "3" } } },
+            "frozen": false,
+        });
+        Transaction {
+            version,
+            timestamp: Some(Timestamp {
+                seconds: 1_700_000_000 + version as i64,
+                nanos: 0,
+            }),
+            info: Some(TransactionInfo {
+                changes: vec![
+                    coin_resource("CoinInfo", "0x1", coin_info),
+                    coin_resource("CoinStore", OWNER_ADDRESS, coin_store),
+                ],
+                ..TransactionInfo::default()
+            }),
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_v1_coin() {
+        let (coin_infos, coin_balances) =
+            parse_v1_coin(&[coin_transaction(1, 100), coin_transaction(2, 40)]);
+
+        // The coin info can't change, so only the first write is kept
+        assert_eq!(coin_infos.len(), 1);
+        assert_eq!(coin_infos[0].coin_type, COIN_TYPE);
+        assert_eq!(coin_infos[0].transaction_version_created, 1);
+        assert_eq!(coin_infos[0].symbol, "LBT");
+        assert_eq!(coin_infos[0].decimals, 8);
+        assert_eq!(coin_infos[0].supply_aggregator_table_handle, None);
+
+        assert_eq!(coin_balances.len(), 2);
+        assert_eq!(coin_balances[0].transaction_version, 1);
+        assert_eq!(coin_balances[0].owner_address, OWNER_ADDRESS);
+        assert_eq!(coin_balances[0].amount, BigDecimal::from(100));
+        assert_eq!(coin_balances[1].transaction_version, 2);
+        assert_eq!(coin_balances[1].amount, BigDecimal::from(40));
+    }
+
+    #[test]
+    fn test_parse_v1_coin_ignores_other_resources() {
+        let mut transaction = coin_transaction(1, 100);
+        transaction.info.as_mut().unwrap().changes = vec![coin_resource(
+            "CoinRegisterEvent",
+            OWNER_ADDRESS,
+            serde_json::json!({}),
+        )];
+        let (coin_infos, coin_balances) = parse_v1_coin(&[transaction]);
+        assert!(coin_infos.is_empty());
+        assert!(coin_balances.is_empty());
+    }
+
+    #[test]
+    fn test_parquet_coin_models() {
+        let (coin_infos, coin_balances) = parse_v1_coin(&[coin_transaction(7, 100)]);
+
+        let coin_info = ParquetCoinInfo::from(coin_infos[0].clone());
+        assert_eq!(coin_info.coin_type, COIN_TYPE);
+        assert_eq!(coin_info.coin_type_hash, coin_infos[0].coin_type_hash);
+        assert_eq!(coin_info.transaction_version_created, 7);
+
+        let coin_balance = ParquetCoinBalance::from(coin_balances[0].clone());
+        assert_eq!(coin_balance.txn_version, 7);
+        assert_eq!(coin_balance.owner_address, OWNER_ADDRESS);
+        assert_eq!(coin_balance.amount, "100");
+        assert_eq!(
+            coin_balance.block_timestamp,
+            coin_balances[0].transaction_timestamp
+        );
+    }
+}
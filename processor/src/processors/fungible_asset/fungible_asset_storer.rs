@@ -16,7 +16,7 @@ use crate::{
         },
     },
     schema,
-    utils::table_flags::{filter_data, TableFlags},
+    utils::table_flags::{filter_data, filter_opt_in_data, TableFlags},
 };
 use ahash::AHashMap;
 use anyhow::Result;
@@ -92,9 +92,9 @@ impl Processable for FungibleAssetStorer {
         let (
             fungible_asset_activities,
             fungible_asset_metadata,
-            _,
+            fungible_asset_balances,
             (current_unified_fab_v1, current_unified_fab_v2),
-            _,
+            coin_supply,
             fa_to_coin_mappings,
         ) = input.data;
 
@@ -114,6 +114,14 @@ impl Processable for FungibleAssetStorer {
             fungible_asset_metadata => TableFlags::FUNGIBLE_ASSET_METADATA,
             fa_to_coin_mappings => TableFlags::FUNGIBLE_ASSET_TO_COIN_MAPPINGS,
         });
+        // The history tables are opt in, they're only written if they're in tables_to_write.
+        let fungible_asset_balances = filter_opt_in_data(
+            &self.tables_to_write,
+            TableFlags::FUNGIBLE_ASSET_BALANCES,
+            fungible_asset_balances,
+        );
+        let coin_supply =
+            filter_opt_in_data(&self.tables_to_write, TableFlags::COIN_SUPPLY, coin_supply);
 
         let faa = execute_in_chunks(
             self.conn_pool.clone(),
@@ -133,6 +141,15 @@ impl Processable for FungibleAssetStorer {
                 &per_table_chunk_sizes,
             ),
         );
+        let fab = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_fungible_asset_balances_query,
+            &fungible_asset_balances,
+            get_config_table_chunk_size::<PostgresFungibleAssetBalance>(
+                "fungible_asset_balances",
+                &per_table_chunk_sizes,
+            ),
+        );
         let cufab_v1 = execute_in_chunks(
             self.conn_pool.clone(),
             insert_current_unified_fungible_asset_balances_v1_query,
@@ -160,9 +177,17 @@ impl Processable for FungibleAssetStorer {
                 &per_table_chunk_sizes,
             ),
         );
-        let (faa_res, fam_res, cufab1_res, cufab2_res, fatcm_res) =
-            tokio::join!(faa, fam, cufab_v1, cufab_v2, fatcm);
-        for res in [faa_res, fam_res, cufab1_res, cufab2_res, fatcm_res] {
+        let cs = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_coin_supply_query,
+            &coin_supply,
+            get_config_table_chunk_size::<CoinSupply>("coin_supply", &per_table_chunk_sizes),
+        );
+        let (faa_res, fam_res, fab_res, cufab1_res, cufab2_res, cs_res, fatcm_res) =
+            tokio::join!(faa, fam, fab, cufab_v1, cufab_v2, cs, fatcm);
+        for res in [
+            faa_res, fam_res, fab_res, cufab1_res, cufab2_res, cs_res, fatcm_res,
+        ] {
             match res {
                 Ok(_) => {},
                 Err(e) => {
@@ -302,6 +327,17 @@ pub fn insert_current_unified_fungible_asset_balances_v2_query(
         )
 }
 
+pub fn insert_coin_supply_query(
+    items_to_insert: Vec<CoinSupply>,
+) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
+    use schema::coin_supply::dsl::*;
+
+    diesel::insert_into(schema::coin_supply::table)
+        .values(items_to_insert)
+        .on_conflict((transaction_version, coin_type_hash))
+        .do_nothing()
+}
+
 pub fn insert_fungible_asset_to_coin_mappings_query(
     items_to_insert: Vec<PostgresFungibleAssetToCoinMapping>,
 ) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
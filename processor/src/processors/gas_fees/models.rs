@@ -1,4 +1,5 @@
 use crate::{
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         fungible_asset::fungible_asset_models::v2_fungible_asset_utils::FeeStatement,
         user_transaction::models::signature_utils::parent_signature_utils::get_fee_payer_address,
@@ -15,9 +16,11 @@ use libra2_indexer_processor_sdk::{
         extract::get_entry_function_from_user_request,
     },
 };
+use allocative_derive::Allocative;
 use bigdecimal::{BigDecimal, Zero};
 use chrono::NaiveDateTime;
 use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
@@ -111,3 +114,50 @@ impl GasFee {
         }
     }
 }
+
+/// This is a parquet version of GasFee
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetGasFee {
+    pub txn_version: i64,
+    pub owner_address: Option<String>,
+    pub amount: Option<String>, // String format of BigDecimal
+    pub gas_fee_payer_address: Option<String>,
+    pub is_transaction_success: bool,
+    pub entry_function_id_str: Option<String>,
+    pub block_height: i64,
+    pub storage_refund_amount: String, // String format of BigDecimal
+    #[allocative(skip)]
+    pub block_timestamp: NaiveDateTime,
+}
+
+impl NamedTable for ParquetGasFee {
+    const TABLE_NAME: &'static str = "gas_fees";
+}
+
+impl HasSchemaVersion for ParquetGasFee {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetGasFee {
+    fn version(&self) -> i64 {
+        self.txn_version
+    }
+}
+
+impl From<GasFee> for ParquetGasFee {
+    fn from(raw: GasFee) -> Self {
+        Self {
+            txn_version: raw.transaction_version,
+            owner_address: raw.owner_address,
+            amount: raw.amount.map(|amount| amount.to_string()),
+            gas_fee_payer_address: raw.gas_fee_payer_address,
+            is_transaction_success: raw.is_transaction_success,
+            entry_function_id_str: raw.entry_function_id_str,
+            block_height: raw.block_height,
+            storage_refund_amount: raw.storage_refund_amount.to_string(),
+            block_timestamp: raw.transaction_timestamp,
+        }
+    }
+}
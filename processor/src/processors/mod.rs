@@ -1,13 +1,18 @@
+pub mod account_activity;
 pub mod account_restoration;
 pub mod account_transactions;
 pub mod ans;
+pub mod capture;
 pub mod default;
+pub mod event_filter;
 pub mod events;
 pub mod fungible_asset;
 pub mod gas_fees;
 pub mod monitoring;
+pub mod nft_points;
 pub mod objects;
 pub mod processor_status_saver;
+pub mod spam_assets;
 pub mod stake;
 pub mod token_v2;
 pub mod user_transaction;
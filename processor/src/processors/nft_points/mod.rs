@@ -0,0 +1,4 @@
+pub mod nft_points_extractor;
+pub mod nft_points_model;
+pub mod nft_points_processor;
+pub mod nft_points_storer;
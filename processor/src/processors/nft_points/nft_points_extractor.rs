@@ -0,0 +1,54 @@
+use libra2_indexer_processor_sdk::{
+    libra2_protos::transaction::v1::Transaction,
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use crate::processors::nft_points::nft_points_model::{NftPoints, NftPointsRules};
+use async_trait::async_trait;
+use rayon::prelude::*;
+
+/// Parses the points awarded by the entry functions and events of the points contract.
+pub struct NftPointsExtractor
+where
+    Self: Sized + Send + 'static,
+{
+    rules: NftPointsRules,
+}
+
+impl NftPointsExtractor {
+    pub fn new(rules: NftPointsRules) -> Self {
+        Self { rules }
+    }
+}
+
+#[async_trait]
+impl Processable for NftPointsExtractor {
+    type Input = Vec<Transaction>;
+    type Output = Vec<NftPoints>;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        item: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<Vec<NftPoints>>>, ProcessorError> {
+        let name = self.name();
+        let points: Vec<NftPoints> = item
+            .data
+            .par_iter()
+            .flat_map(|txn| self.rules.parse_transaction(txn, name.as_str()))
+            .collect();
+        Ok(Some(TransactionContext {
+            data: points,
+            metadata: item.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for NftPointsExtractor {}
+
+impl NamedStep for NftPointsExtractor {
+    fn name(&self) -> String {
+        "NftPointsExtractor".to_string()
+    }
+}
@@ -0,0 +1,465 @@
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::{
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
+    processors::{
+        event_filter::event_filter_model::EventTypePattern,
+        events::{events_model::Event, parse_events},
+    },
+    schema::nft_points,
+};
+use allocative_derive::Allocative;
+use anyhow::Result;
+use libra2_indexer_processor_sdk::{
+    libra2_indexer_transaction_stream::utils::time::parse_timestamp,
+    libra2_protos::transaction::v1::{
+        transaction::TxnData, transaction_payload::Payload, Transaction,
+    },
+    utils::convert::standardize_address,
+};
+use bigdecimal::BigDecimal;
+use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use tracing::warn;
+
+/// Event index of the points awarded by the entry function of a transaction, rather than by one
+/// of its events.
+pub const ENTRY_FUNCTION_EVENT_INDEX: i64 = -1;
+
+/// An entry function of the points contract that awards points, with the positions of the
+/// arguments the points are read from.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PointsEntryFunction {
+    // `module::function` of the contract, e.g. `campaign::claim_points`
+    pub function: String,
+    // Argument with the account that earns the points. The sender earns them if not set.
+    #[serde(default)]
+    pub owner_address_arg: Option<usize>,
+    pub token_name_arg: usize,
+    pub amount_arg: usize,
+    // Argument with the point type. The point type is `function` if not set.
+    #[serde(default)]
+    pub point_type_arg: Option<usize>,
+}
+
+/// An event of the points contract that awards points, with the JSON pointers to the fields the
+/// points are read from.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PointsEvent {
+    // `module::struct` of the event, e.g. `campaign::PointsAwarded`
+    pub event_type: String,
+    // JSON pointers to fields of the event data, e.g. `/owner`
+    pub owner_address_path: String,
+    pub token_name_path: String,
+    pub amount_path: String,
+    // JSON pointer to the point type. The point type is `event_type` if not set.
+    #[serde(default)]
+    pub point_type_path: Option<String>,
+}
+
+/// The entry functions and events of the points contract, and how to read points from them.
+#[derive(Clone, Debug)]
+pub struct NftPointsRules {
+    contract_address: String,
+    entry_functions: Vec<PointsEntryFunction>,
+    events: Vec<(EventTypePattern, PointsEvent)>,
+}
+
+impl NftPointsRules {
+    pub fn new(
+        contract_address: &str,
+        entry_functions: &[PointsEntryFunction],
+        events: &[PointsEvent],
+    ) -> Result<Self> {
+        if entry_functions.is_empty() && events.is_empty() {
+            anyhow::bail!("At least one entry function or event has to award points");
+        }
+        let contract_address = standardize_address(contract_address);
+        for entry_function in entry_functions {
+            if entry_function.function.split("::").count() != 2 {
+                anyhow::bail!(
+                    "Entry function {} must be written as module::function",
+                    entry_function.function
+                );
+            }
+        }
+        let events = events
+            .iter()
+            .map(|event| {
+                let paths = [
+                    Some(&event.owner_address_path),
+                    Some(&event.token_name_path),
+                    Some(&event.amount_path),
+                    event.point_type_path.as_ref(),
+                ];
+                if let Some(path) = paths.into_iter().flatten().find(|p| !p.starts_with('/')) {
+                    anyhow::bail!(
+                        "Path {} of event {} must be a JSON pointer starting with '/'",
+                        path,
+                        event.event_type
+                    );
+                }
+                if event.event_type.split("::").count() != 2 {
+                    anyhow::bail!(
+                        "Event {} must be written as module::struct",
+                        event.event_type
+                    );
+                }
+                let pattern = format!("{}::{}", contract_address, event.event_type).parse()?;
+                Ok((pattern, event.clone()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            contract_address,
+            entry_functions: entry_functions.to_vec(),
+            events,
+        })
+    }
+
+    /// Points awarded by a transaction, from its entry function and its events. Failed
+    /// transactions don't award points.
+    pub fn parse_transaction(&self, txn: &Transaction, processor_name: &str) -> Vec<NftPoints> {
+        let txn_version = txn.version as i64;
+        if !txn.info.as_ref().is_some_and(|info| info.success) {
+            return vec![];
+        }
+        let transaction_timestamp =
+            parse_timestamp(txn.timestamp.as_ref().unwrap(), txn_version).naive_utc();
+
+        let mut points = vec![];
+        if let Some(TxnData::User(user_txn)) = txn.txn_data.as_ref() {
+            let request = user_txn.request.as_ref().unwrap();
+            if let Some(Payload::EntryFunctionPayload(payload)) = request
+                .payload
+                .as_ref()
+                .and_then(|payload| payload.payload.as_ref())
+            {
+                points.extend(self.points_from_entry_function(
+                    &payload.entry_function_id_str,
+                    &payload.arguments,
+                    &request.sender,
+                    txn_version,
+                    transaction_timestamp,
+                ));
+            }
+        }
+        points.extend(
+            parse_events(txn, processor_name)
+                .iter()
+                .filter_map(|event| self.points_from_event(event, transaction_timestamp)),
+        );
+        points
+    }
+
+    fn points_from_entry_function(
+        &self,
+        entry_function_id_str: &str,
+        arguments: &[String],
+        sender: &str,
+        txn_version: i64,
+        transaction_timestamp: chrono::NaiveDateTime,
+    ) -> Option<NftPoints> {
+        let (address, function) = entry_function_id_str.split_once("::")?;
+        if standardize_address(address) != self.contract_address {
+            return None;
+        }
+        let entry_function = self
+            .entry_functions
+            .iter()
+            .find(|entry_function| entry_function.function == function)?;
+
+        // Arguments are JSON encoded, e.g. `"\"0xcafe\""` or `"\"100\""`
+        let argument = |position: usize| {
+            arguments
+                .get(position)
+                .and_then(|argument| serde_json::from_str(argument).ok())
+                .and_then(|value| value_to_string(&value))
+        };
+        let owner_address = match entry_function.owner_address_arg {
+            Some(position) => argument(position),
+            None => Some(sender.to_string()),
+        };
+        let point_type = match entry_function.point_type_arg {
+            Some(position) => argument(position),
+            None => Some(entry_function.function.clone()),
+        };
+        NftPoints::new(
+            txn_version,
+            ENTRY_FUNCTION_EVENT_INDEX,
+            owner_address,
+            argument(entry_function.token_name_arg),
+            point_type,
+            argument(entry_function.amount_arg),
+            transaction_timestamp,
+            entry_function_id_str,
+        )
+    }
+
+    fn points_from_event(
+        &self,
+        event: &Event,
+        transaction_timestamp: chrono::NaiveDateTime,
+    ) -> Option<NftPoints> {
+        let (_, points_event) = self
+            .events
+            .iter()
+            .find(|(pattern, _)| pattern.matches(&event.type_))?;
+        let data: serde_json::Value = serde_json::from_str(&event.data).ok()?;
+        let field = |path: &str| data.pointer(path).and_then(value_to_string);
+        let point_type = match &points_event.point_type_path {
+            Some(path) => field(path),
+            None => Some(points_event.event_type.clone()),
+        };
+        NftPoints::new(
+            event.transaction_version,
+            event.event_index,
+            field(&points_event.owner_address_path),
+            field(&points_event.token_name_path),
+            point_type,
+            field(&points_event.amount_path),
+            transaction_timestamp,
+            &event.type_,
+        )
+    }
+}
+
+/// Strings are taken as is and numbers are written out, e.g. u64 arguments that are JSON
+/// numbers rather than strings.
+fn value_to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NftPoints {
+    pub transaction_version: i64,
+    pub event_index: i64,
+    pub owner_address: String,
+    pub token_name: String,
+    pub point_type: String,
+    pub amount: BigDecimal,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+}
+
+impl NftPoints {
+    /// Returns `None` and logs a warning when one of the values is missing or the amount isn't a
+    /// number, so a malformed call or event doesn't stop the processor.
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        transaction_version: i64,
+        event_index: i64,
+        owner_address: Option<String>,
+        token_name: Option<String>,
+        point_type: Option<String>,
+        amount: Option<String>,
+        transaction_timestamp: chrono::NaiveDateTime,
+        source: &str,
+    ) -> Option<Self> {
+        let amount = amount.and_then(|amount| BigDecimal::from_str(&amount).ok());
+        match (owner_address, token_name, point_type, amount) {
+            (Some(owner_address), Some(token_name), Some(point_type), Some(amount)) => Some(Self {
+                transaction_version,
+                event_index,
+                owner_address: standardize_address(&owner_address),
+                token_name,
+                point_type,
+                amount,
+                transaction_timestamp,
+            }),
+            _ => {
+                warn!(
+                    transaction_version,
+                    event_index, source, "Failed to read nft points from {}", source
+                );
+                None
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version, event_index))]
+#[diesel(table_name = nft_points)]
+pub struct PostgresNftPoints {
+    pub transaction_version: i64,
+    pub event_index: i64,
+    pub owner_address: String,
+    pub token_name: String,
+    pub point_type: String,
+    pub amount: BigDecimal,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+}
+
+impl From<NftPoints> for PostgresNftPoints {
+    fn from(raw: NftPoints) -> Self {
+        Self {
+            transaction_version: raw.transaction_version,
+            event_index: raw.event_index,
+            owner_address: raw.owner_address,
+            token_name: raw.token_name,
+            point_type: raw.point_type,
+            amount: raw.amount,
+            transaction_timestamp: raw.transaction_timestamp,
+        }
+    }
+}
+
+#[derive(Allocative, Clone, Debug, Default, Deserialize, ParquetRecordWriter, Serialize)]
+pub struct ParquetNftPoints {
+    pub txn_version: i64,
+    pub event_index: i64,
+    pub owner_address: String,
+    pub token_name: String,
+    pub point_type: String,
+    pub amount: String, // it is a string representation of the numeric amount
+    #[allocative(skip)]
+    pub block_timestamp: chrono::NaiveDateTime,
+}
+
+impl NamedTable for ParquetNftPoints {
+    const TABLE_NAME: &'static str = "nft_points";
+}
+
+impl HasSchemaVersion for ParquetNftPoints {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetNftPoints {
+    fn version(&self) -> i64 {
+        self.txn_version
+    }
+}
+
+impl From<NftPoints> for ParquetNftPoints {
+    fn from(raw: NftPoints) -> Self {
+        Self {
+            txn_version: raw.transaction_version,
+            event_index: raw.event_index,
+            owner_address: raw.owner_address,
+            token_name: raw.token_name,
+            point_type: raw.point_type,
+            amount: raw.amount.to_string(),
+            block_timestamp: raw.transaction_timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "0xcafe";
+
+    fn rules() -> NftPointsRules {
+        NftPointsRules::new(
+            CONTRACT,
+            &[PointsEntryFunction {
+                function: "campaign::claim_points".to_string(),
+                owner_address_arg: None,
+                token_name_arg: 0,
+                amount_arg: 1,
+                point_type_arg: None,
+            }],
+            &[PointsEvent {
+                event_type: "campaign::PointsAwarded".to_string(),
+                owner_address_path: "/owner".to_string(),
+                token_name_path: "/token/name".to_string(),
+                amount_path: "/amount".to_string(),
+                point_type_path: Some("/kind".to_string()),
+            }],
+        )
+        .unwrap()
+    }
+
+    fn event(type_: &str, data: &str) -> Event {
+        Event {
+            sequence_number: 0,
+            creation_number: 0,
+            account_address: standardize_address("0x0"),
+            transaction_version: 7,
+            transaction_block_height: 1,
+            type_: type_.to_string(),
+            data: data.to_string(),
+            event_index: 2,
+            indexed_type: type_.to_string(),
+            block_timestamp: None,
+            type_tag_bytes: None,
+            total_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_points_from_entry_function() {
+        let rules = rules();
+        let arguments = vec!["\"Badge #1\"".to_string(), "\"250\"".to_string()];
+        let points = rules
+            .points_from_entry_function(
+                &format!("{}::campaign::claim_points", standardize_address(CONTRACT)),
+                &arguments,
+                "0x1",
+                7,
+                chrono::NaiveDateTime::default(),
+            )
+            .unwrap();
+        assert_eq!(points.event_index, ENTRY_FUNCTION_EVENT_INDEX);
+        assert_eq!(points.owner_address, standardize_address("0x1"));
+        assert_eq!(points.token_name, "Badge #1");
+        assert_eq!(points.point_type, "campaign::claim_points");
+        assert_eq!(points.amount, BigDecimal::from(250));
+
+        // Other contracts and functions, and calls with missing arguments, award nothing
+        let ts = chrono::NaiveDateTime::default();
+        assert!(rules
+            .points_from_entry_function("0xbeef::campaign::claim_points", &arguments, "0x1", 7, ts)
+            .is_none());
+        assert!(rules
+            .points_from_entry_function("0xcafe::campaign::other", &arguments, "0x1", 7, ts)
+            .is_none());
+        assert!(rules
+            .points_from_entry_function("0xcafe::campaign::claim_points", &[], "0x1", 7, ts)
+            .is_none());
+    }
+
+    #[test]
+    fn test_points_from_event() {
+        let rules = rules();
+        let ts = chrono::NaiveDateTime::default();
+        let data = r#"{"owner":"0x2","token":{"name":"Badge #2"},"amount":"10","kind":"mint"}"#;
+        let points = rules
+            .points_from_event(&event("0xcafe::campaign::PointsAwarded", data), ts)
+            .unwrap();
+        assert_eq!(points.event_index, 2);
+        assert_eq!(points.owner_address, standardize_address("0x2"));
+        assert_eq!(points.token_name, "Badge #2");
+        assert_eq!(points.point_type, "mint");
+        assert_eq!(points.amount, BigDecimal::from(10));
+
+        assert!(rules
+            .points_from_event(&event("0xbeef::campaign::PointsAwarded", data), ts)
+            .is_none());
+        let bad_amount = r#"{"owner":"0x2","token":{"name":"Badge"},"amount":"x","kind":"mint"}"#;
+        assert!(rules
+            .points_from_event(&event("0xcafe::campaign::PointsAwarded", bad_amount), ts)
+            .is_none());
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(NftPointsRules::new(CONTRACT, &[], &[]).is_err());
+        let event = PointsEvent {
+            event_type: "campaign::PointsAwarded".to_string(),
+            owner_address_path: "owner".to_string(),
+            token_name_path: "/token".to_string(),
+            amount_path: "/amount".to_string(),
+            point_type_path: None,
+        };
+        assert!(NftPointsRules::new(CONTRACT, &[], &[event]).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    config::processor_config::DefaultProcessorConfig,
+    processors::nft_points::nft_points_model::{NftPoints, PostgresNftPoints},
+    schema,
+};
+use ahash::AHashMap;
+use anyhow::Result;
+use libra2_indexer_processor_sdk::{
+    postgres::utils::database::{execute_in_chunks, get_config_table_chunk_size, ArcDbPool},
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use diesel::{pg::Pg, query_builder::QueryFragment};
+
+pub struct NftPointsStorer
+where
+    Self: Sized + Send + 'static,
+{
+    conn_pool: ArcDbPool,
+    processor_config: DefaultProcessorConfig,
+}
+
+impl NftPointsStorer {
+    pub fn new(conn_pool: ArcDbPool, processor_config: DefaultProcessorConfig) -> Self {
+        Self {
+            conn_pool,
+            processor_config,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for NftPointsStorer {
+    type Input = Vec<NftPoints>;
+    type Output = ();
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        input: TransactionContext<Vec<NftPoints>>,
+    ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
+        let per_table_chunk_sizes: AHashMap<String, usize> =
+            self.processor_config.per_table_chunk_sizes.clone();
+        let nft_points: Vec<PostgresNftPoints> = input
+            .data
+            .into_iter()
+            .map(PostgresNftPoints::from)
+            .collect();
+
+        let np = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_nft_points_query,
+            &nft_points,
+            get_config_table_chunk_size::<PostgresNftPoints>("nft_points", &per_table_chunk_sizes),
+        );
+
+        match np.await {
+            Ok(_) => {},
+            Err(e) => {
+                return Err(ProcessorError::DBStoreError {
+                    message: format!(
+                        "Failed to store versions {} to {}: {:?}",
+                        input.metadata.start_version, input.metadata.end_version, e,
+                    ),
+                    query: None,
+                })
+            },
+        }
+
+        Ok(Some(TransactionContext {
+            data: (),
+            metadata: input.metadata,
+        }))
+    }
+}
+
+impl NamedStep for NftPointsStorer {
+    fn name(&self) -> String {
+        "nft_points_storer".to_string()
+    }
+}
+
+impl AsyncStep for NftPointsStorer {}
+
+fn insert_nft_points_query(
+    items_to_insert: Vec<PostgresNftPoints>,
+) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
+    use schema::nft_points::dsl::*;
+
+    diesel::insert_into(schema::nft_points::table)
+        .values(items_to_insert)
+        .on_conflict((transaction_version, event_index))
+        .do_nothing()
+}
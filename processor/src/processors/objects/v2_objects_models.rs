@@ -7,7 +7,7 @@
 
 use super::v2_object_utils::{CurrentObjectPK, ObjectAggregatedDataMapping};
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::default::models::move_resources::MoveResource,
     schema::{current_objects, objects},
 };
@@ -290,6 +290,22 @@ impl CurrentObjectQuery {
             .first::<Self>(conn)
             .await
     }
+
+    /// Returns (object_address, owner_address) for the live objects among `object_addresses`
+    pub async fn get_owners_by_addresses(
+        object_addresses: &[String],
+        conn: &mut DbPoolConnection<'_>,
+    ) -> diesel::QueryResult<Vec<(String, String)>> {
+        current_objects::table
+            .filter(current_objects::object_address.eq_any(object_addresses))
+            .filter(current_objects::is_deleted.eq(false))
+            .select((
+                current_objects::object_address,
+                current_objects::owner_address,
+            ))
+            .load::<(String, String)>(conn)
+            .await
+    }
 }
 
 /// Parquet
@@ -315,6 +331,10 @@ impl NamedTable for ParquetObject {
     const TABLE_NAME: &'static str = "objects";
 }
 
+impl HasSchemaVersion for ParquetObject {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetObject {
     fn version(&self) -> i64 {
         self.txn_version
@@ -358,6 +378,10 @@ impl NamedTable for ParquetCurrentObject {
     const TABLE_NAME: &'static str = "objects";
 }
 
+impl HasSchemaVersion for ParquetCurrentObject {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCurrentObject {
     fn version(&self) -> i64 {
         self.last_transaction_version
@@ -21,7 +21,8 @@ use libra2_indexer_processor_sdk::{
     utils::errors::ProcessorError,
 };
 use async_trait::async_trait;
-use diesel::{query_dsl::methods::FilterDsl, upsert::excluded, ExpressionMethods};
+use diesel::{query_dsl::methods::FilterDsl, upsert::excluded, ExpressionMethods, QueryResult};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
 
 /// A trait implementation of ProcessorStatusSaver for Postgres.
 pub struct PostgresProcessorStatusSaver {
@@ -57,6 +58,29 @@ pub async fn save_processor_status(
     last_success_batch: &TransactionContext<()>,
     db_pool: ArcDbPool,
 ) -> Result<(), ProcessorError> {
+    let mut conn = db_pool
+        .get()
+        .await
+        .map_err(|e| ProcessorError::DBStoreError {
+            message: format!("Failed to get connection from pool: {e:?}"),
+            query: None,
+        })?;
+    save_processor_status_with_conn(processor_id, &processor_mode, last_success_batch, &mut conn)
+        .await
+        .map_err(|e| ProcessorError::DBStoreError {
+            message: format!("Failed to save processor status of {processor_id}: {e:?}"),
+            query: None,
+        })
+}
+
+/// Same as `save_processor_status`, on a connection the caller may have opened a transaction on,
+/// so the status can be saved atomically with the caller's own writes.
+pub async fn save_processor_status_with_conn(
+    processor_id: &str,
+    processor_mode: &ProcessorMode,
+    last_success_batch: &TransactionContext<()>,
+    conn: &mut AsyncPgConnection,
+) -> QueryResult<()> {
     let last_success_version = last_success_batch.metadata.end_version as i64;
     let last_transaction_timestamp = last_success_batch
         .metadata
@@ -73,25 +97,23 @@ pub async fn save_processor_status(
     match processor_mode {
         ProcessorMode::Default(_) => {
             // Save regular processor status to the database
-            execute_with_better_error(
-                db_pool.clone(),
-                diesel::insert_into(processor_status::table)
-                    .values(&status)
-                    .on_conflict(processor_status::processor)
-                    .do_update()
-                    .set((
-                        processor_status::last_success_version
-                            .eq(excluded(processor_status::last_success_version)),
-                        processor_status::last_updated.eq(excluded(processor_status::last_updated)),
-                        processor_status::last_transaction_timestamp
-                            .eq(excluded(processor_status::last_transaction_timestamp)),
-                    ))
-                    .filter(
-                        processor_status::last_success_version
-                            .le(excluded(processor_status::last_success_version)),
-                    ),
-            )
-            .await?;
+            diesel::insert_into(processor_status::table)
+                .values(&status)
+                .on_conflict(processor_status::processor)
+                .do_update()
+                .set((
+                    processor_status::last_success_version
+                        .eq(excluded(processor_status::last_success_version)),
+                    processor_status::last_updated.eq(excluded(processor_status::last_updated)),
+                    processor_status::last_transaction_timestamp
+                        .eq(excluded(processor_status::last_transaction_timestamp)),
+                ))
+                .filter(
+                    processor_status::last_success_version
+                        .le(excluded(processor_status::last_success_version)),
+                )
+                .execute(conn)
+                .await?;
         },
         ProcessorMode::Backfill(BackfillConfig {
             backfill_id,
@@ -112,7 +134,7 @@ pub async fn save_processor_status(
                 backfill_status,
                 last_success_version,
                 last_transaction_timestamp,
-                backfill_start_version: initial_starting_version as i64,
+                backfill_start_version: *initial_starting_version as i64,
                 backfill_end_version: ending_version.map(|v| v as i64),
             };
 
@@ -137,17 +159,16 @@ pub async fn save_processor_status(
                 ));
 
             // If overwrite_checkpoint is true, then always update the backfill status.
-            if overwrite_checkpoint {
-                execute_with_better_error(db_pool.clone(), query).await?;
+            if *overwrite_checkpoint {
+                query.execute(conn).await?;
             } else {
-                execute_with_better_error(
-                    db_pool.clone(),
-                    query.filter(
+                query
+                    .filter(
                         backfill_processor_status::last_success_version
                             .le(excluded(backfill_processor_status::last_success_version)),
-                    ),
-                )
-                .await?;
+                    )
+                    .execute(conn)
+                    .await?;
             }
         },
         ProcessorMode::Testing(_) => {
@@ -282,7 +303,8 @@ pub async fn get_end_version(
     let processor_name = config.processor_config.name();
     let processor_mode = &config.processor_mode;
     match processor_mode {
-        ProcessorMode::Default(_) => Ok(None),
+        // Normally unset. The replay command sets it to stop after the last replayed transaction.
+        ProcessorMode::Default(_) => Ok(config.transaction_stream_config.request_ending_version),
         ProcessorMode::Backfill(BackfillConfig { ending_version, .. }) => {
             match ending_version {
                 Some(ending_version) => Ok(Some(*ending_version)),
@@ -378,6 +400,7 @@ mod tests {
             processor_config,
             db_config,
             processor_mode,
+            additional_processor_configs: vec![],
             transaction_stream_config: TransactionStreamConfig {
                 indexer_grpc_data_service_address: Url::parse("https://test.com").unwrap(),
                 starting_version: None,
@@ -0,0 +1,4 @@
+pub mod spam_asset_extractor;
+pub mod spam_asset_model;
+pub mod spam_asset_processor;
+pub mod spam_asset_storer;
@@ -0,0 +1,116 @@
+use libra2_indexer_processor_sdk::{
+    libra2_protos::transaction::v1::Transaction,
+    postgres::utils::database::{ArcDbPool, DbContext},
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use crate::processors::{
+    fungible_asset::fungible_asset_processor_helpers::parse_v2_coin,
+    spam_assets::spam_asset_model::{SpamAsset, SpamRules},
+    token_v2::{
+        token_models::tokens::TableMetadataForToken, token_v2_processor_helpers::parse_v2_token,
+    },
+};
+use async_trait::async_trait;
+
+/// Parses fungible assets and tokens from transactions, and flags the ones that match the spam
+/// rules.
+pub struct SpamAssetExtractor
+where
+    Self: Sized + Send + 'static,
+{
+    spam_rules: SpamRules,
+    query_retries: u32,
+    query_retry_delay_ms: u64,
+    conn_pool: ArcDbPool,
+}
+
+impl SpamAssetExtractor {
+    pub fn new(
+        spam_rules: SpamRules,
+        query_retries: u32,
+        query_retry_delay_ms: u64,
+        conn_pool: ArcDbPool,
+    ) -> Self {
+        Self {
+            spam_rules,
+            query_retries,
+            query_retry_delay_ms,
+            conn_pool,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for SpamAssetExtractor {
+    type Input = Vec<Transaction>;
+    type Output = Vec<SpamAsset>;
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        transactions: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<Vec<SpamAsset>>>, ProcessorError> {
+        let conn = self
+            .conn_pool
+            .get()
+            .await
+            .map_err(|e| ProcessorError::DBStoreError {
+                message: format!("Failed to get connection from pool: {e:?}"),
+                query: None,
+            })?;
+
+        // Fungible asset to coin mappings are only needed for balances
+        let (fungible_asset_activities, fungible_asset_metadata, _, _, _, _) =
+            parse_v2_coin(&transactions.data, None).await;
+
+        let table_handle_to_owner =
+            TableMetadataForToken::get_table_handle_to_owner_from_transactions(&transactions.data);
+        let db_connection = DbContext {
+            conn,
+            query_retries: self.query_retries,
+            query_retry_delay_ms: self.query_retry_delay_ms,
+        };
+        let (
+            _,
+            _,
+            _,
+            current_collections_v2,
+            current_token_datas_v2,
+            _,
+            _,
+            _,
+            token_activities_v2,
+            _,
+            _,
+            _,
+        ) = parse_v2_token(
+            &transactions.data,
+            &table_handle_to_owner,
+            &mut Some(db_connection),
+        )
+        .await;
+
+        let spam_assets = self.spam_rules.classify(
+            &fungible_asset_metadata,
+            &fungible_asset_activities,
+            &current_collections_v2,
+            &current_token_datas_v2,
+            &token_activities_v2,
+        );
+
+        Ok(Some(TransactionContext {
+            data: spam_assets,
+            metadata: transactions.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for SpamAssetExtractor {}
+
+impl NamedStep for SpamAssetExtractor {
+    fn name(&self) -> String {
+        "SpamAssetExtractor".to_string()
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright © A-p-t-o-s Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+#![allow(clippy::unused_unit)]
+
+use crate::{
+    processors::{
+        fungible_asset::fungible_asset_models::{
+            v2_fungible_asset_activities::FungibleAssetActivity,
+            v2_fungible_metadata::FungibleAssetMetadataModel,
+        },
+        spam_assets::spam_asset_processor::SpamAssetProcessorConfig,
+        token_v2::token_v2_models::{
+            v2_collections::CurrentCollectionV2, v2_token_activities::TokenActivityV2,
+            v2_token_datas::CurrentTokenDataV2,
+        },
+    },
+    schema::spam_assets,
+};
+use ahash::{AHashMap, AHashSet};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::utils::convert::standardize_address;
+use diesel::{Identifiable, Insertable};
+use field_count::FieldCount;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Events of coins and fungible assets that deposit into a store of the activity's owner.
+const DEPOSIT_EVENT_TYPES: [&str; 4] = [
+    "0x1::coin::CoinDeposit",
+    "0x1::coin::DepositEvent",
+    "0x1::fungible_asset::Deposit",
+    "0x1::fungible_asset::DepositEvent",
+];
+
+/// `asset` is the asset type of a fungible asset, or the id of a collection or token.
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, PartialEq, Serialize)]
+#[diesel(primary_key(asset))]
+#[diesel(table_name = spam_assets)]
+pub struct SpamAsset {
+    pub asset: String,
+    pub is_spam: bool,
+}
+
+/// Standardizes the addresses of v2 asset types and of collection and token ids, and leaves v1
+/// coin types as they are.
+fn standardize_asset(asset: &str) -> String {
+    if asset.contains("::") {
+        asset.to_string()
+    } else {
+        standardize_address(asset)
+    }
+}
+
+/// The rules of a `SpamAssetProcessorConfig`, with the patterns compiled.
+#[derive(Clone, Debug)]
+pub struct SpamRules {
+    name_patterns: Vec<Regex>,
+    uri_patterns: Vec<Regex>,
+    blocked_domains: Vec<String>,
+    airdrop_receiver_threshold: Option<usize>,
+    allowed_assets: AHashSet<String>,
+    denied_assets: AHashSet<String>,
+}
+
+impl SpamRules {
+    pub fn new(config: &SpamAssetProcessorConfig) -> Result<Self> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    RegexBuilder::new(pattern)
+                        .case_insensitive(true)
+                        .build()
+                        .with_context(|| format!("Invalid spam pattern {pattern}"))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        let standardize = |assets: &[String]| -> AHashSet<String> {
+            assets
+                .iter()
+                .map(|asset| standardize_asset(asset))
+                .collect()
+        };
+        let allowed_assets = standardize(&config.allowed_assets);
+        let denied_assets = standardize(&config.denied_assets);
+        if let Some(asset) = allowed_assets.intersection(&denied_assets).next() {
+            anyhow::bail!("Asset {asset} is both allowed and denied");
+        }
+        if config.airdrop_receiver_threshold == Some(0) {
+            anyhow::bail!("airdrop_receiver_threshold must be at least 1");
+        }
+
+        Ok(Self {
+            name_patterns: compile(&config.name_patterns)?,
+            uri_patterns: compile(&config.uri_patterns)?,
+            blocked_domains: config
+                .blocked_domains
+                .iter()
+                .map(|domain| domain.trim_start_matches('.').to_lowercase())
+                .collect(),
+            airdrop_receiver_threshold: config.airdrop_receiver_threshold,
+            allowed_assets,
+            denied_assets,
+        })
+    }
+
+    /// The rows of the allow and deny lists, sorted by asset.
+    pub fn listed_assets(&self) -> Vec<SpamAsset> {
+        let allowed = self.allowed_assets.iter().map(|asset| (asset, false));
+        let denied = self.denied_assets.iter().map(|asset| (asset, true));
+        let mut listed_assets: Vec<SpamAsset> = allowed
+            .chain(denied)
+            .map(|(asset, is_spam)| SpamAsset {
+                asset: asset.clone(),
+                is_spam,
+            })
+            .collect();
+        listed_assets.sort_by(|a, b| a.asset.cmp(&b.asset));
+        listed_assets
+    }
+
+    /// Whether a name or symbol matches a pattern or mentions a blocked domain.
+    fn is_spam_name(&self, name: &str) -> bool {
+        let name_lowercase = name.to_lowercase();
+        self.name_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(name))
+            || self
+                .blocked_domains
+                .iter()
+                .any(|domain| name_lowercase.contains(domain.as_str()))
+    }
+
+    /// Whether a uri matches a pattern or its host is a blocked domain or one of its subdomains.
+    fn is_spam_uri(&self, uri: &str) -> bool {
+        if self
+            .uri_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(uri))
+        {
+            return true;
+        }
+        let Some(host) = url::Url::parse(uri.trim())
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+        else {
+            return false;
+        };
+        self.blocked_domains.iter().any(|domain| {
+            host == *domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+    }
+
+    /// Assets whose number of distinct receivers in one transaction reaches the airdrop
+    /// threshold. `transfers` are (transaction version, asset, receiver).
+    fn airdropped_assets<'a>(
+        &self,
+        transfers: impl Iterator<Item = (i64, &'a str, &'a str)>,
+    ) -> Vec<&'a str> {
+        let Some(threshold) = self.airdrop_receiver_threshold else {
+            return vec![];
+        };
+        let mut receivers: AHashMap<(i64, &str), AHashSet<&str>> = AHashMap::new();
+        for (txn_version, asset, receiver) in transfers {
+            receivers
+                .entry((txn_version, asset))
+                .or_default()
+                .insert(receiver);
+        }
+        receivers
+            .into_iter()
+            .filter(|(_, receivers)| receivers.len() >= threshold)
+            .map(|((_, asset), _)| asset)
+            .collect()
+    }
+
+    /// Flags the assets of a batch of transactions that match a rule. Assets in the allow or
+    /// deny list are left out, since their rows are written from the lists.
+    ///
+    /// Tokens are airdropped by collection, so the fan-out of a token is counted towards its
+    /// collection if the token data is in the batch, and towards the token otherwise.
+    pub fn classify(
+        &self,
+        fungible_asset_metadata: &[FungibleAssetMetadataModel],
+        fungible_asset_activities: &[FungibleAssetActivity],
+        current_collections: &[CurrentCollectionV2],
+        current_token_datas: &[CurrentTokenDataV2],
+        token_activities: &[TokenActivityV2],
+    ) -> Vec<SpamAsset> {
+        let mut spam_assets: BTreeSet<&str> = BTreeSet::new();
+
+        for metadata in fungible_asset_metadata {
+            let uris = [&metadata.icon_uri, &metadata.project_uri];
+            if self.is_spam_name(&metadata.name)
+                || self.is_spam_name(&metadata.symbol)
+                || uris.into_iter().flatten().any(|uri| self.is_spam_uri(uri))
+            {
+                spam_assets.insert(&metadata.asset_type);
+            }
+        }
+        for collection in current_collections {
+            if self.is_spam_name(&collection.collection_name) || self.is_spam_uri(&collection.uri) {
+                spam_assets.insert(&collection.collection_id);
+            }
+        }
+        for token_data in current_token_datas {
+            if self.is_spam_name(&token_data.token_name) || self.is_spam_uri(&token_data.token_uri)
+            {
+                spam_assets.insert(&token_data.token_data_id);
+            }
+        }
+
+        let fungible_asset_deposits = fungible_asset_activities
+            .iter()
+            .filter(|activity| {
+                activity.is_transaction_success
+                    && DEPOSIT_EVENT_TYPES.contains(&activity.event_type.as_str())
+            })
+            .filter_map(|activity| {
+                Some((
+                    activity.transaction_version,
+                    activity.asset_type.as_deref()?,
+                    activity.owner_address.as_deref()?,
+                ))
+            });
+        spam_assets.extend(self.airdropped_assets(fungible_asset_deposits));
+
+        let token_collections: AHashMap<&str, &str> = current_token_datas
+            .iter()
+            .map(|token_data| {
+                (
+                    token_data.token_data_id.as_str(),
+                    token_data.collection_id.as_str(),
+                )
+            })
+            .collect();
+        let token_transfers = token_activities.iter().filter_map(|activity| {
+            let token_data_id = activity.token_data_id.as_str();
+            Some((
+                activity.transaction_version,
+                token_collections
+                    .get(token_data_id)
+                    .copied()
+                    .unwrap_or(token_data_id),
+                activity.to_address.as_deref()?,
+            ))
+        });
+        spam_assets.extend(self.airdropped_assets(token_transfers));
+
+        spam_assets
+            .into_iter()
+            .filter(|asset| {
+                !self.allowed_assets.contains(*asset) && !self.denied_assets.contains(*asset)
+            })
+            .map(|asset| SpamAsset {
+                asset: asset.to_string(),
+                is_spam: true,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(config: serde_json::Value) -> SpamRules {
+        SpamRules::new(&serde_json::from_value(config).unwrap()).unwrap()
+    }
+
+    fn metadata(asset_type: &str, name: &str, icon_uri: &str) -> FungibleAssetMetadataModel {
+        FungibleAssetMetadataModel {
+            asset_type: asset_type.to_string(),
+            creator_address: "0x1".to_string(),
+            name: name.to_string(),
+            symbol: "SYM".to_string(),
+            decimals: 8,
+            icon_uri: Some(icon_uri.to_string()),
+            project_uri: None,
+            last_transaction_version: 1,
+            last_transaction_timestamp: chrono::NaiveDateTime::default(),
+            supply_aggregator_table_handle_v1: None,
+            supply_aggregator_table_key_v1: None,
+            token_standard: "v2".to_string(),
+            is_token_v2: None,
+            supply_v2: None,
+            maximum_v2: None,
+        }
+    }
+
+    fn deposit(txn_version: i64, asset_type: &str, owner_address: &str) -> FungibleAssetActivity {
+        FungibleAssetActivity {
+            transaction_version: txn_version,
+            event_index: 0,
+            owner_address: Some(owner_address.to_string()),
+            storage_id: "0x5".to_string(),
+            asset_type: Some(asset_type.to_string()),
+            is_frozen: None,
+            amount: None,
+            event_type: "0x1::fungible_asset::Deposit".to_string(),
+            is_gas_fee: false,
+            gas_fee_payer_address: None,
+            is_transaction_success: true,
+            entry_function_id_str: None,
+            block_height: 0,
+            token_standard: "v2".to_string(),
+            transaction_timestamp: chrono::NaiveDateTime::default(),
+            storage_refund_amount: bigdecimal::BigDecimal::default(),
+        }
+    }
+
+    fn assets(spam_assets: Vec<SpamAsset>) -> Vec<String> {
+        spam_assets.into_iter().map(|asset| asset.asset).collect()
+    }
+
+    #[test]
+    fn test_name_and_uri_rules() {
+        let rules = rules(serde_json::json!({
+            "name_patterns": ["claim.*reward"],
+            "uri_patterns": ["^ipfs://bafyscam"],
+            "blocked_domains": ["evil.xyz"],
+        }));
+        let fungible_asset_metadata = [
+            metadata("0xa", "CLAIM your REWARD", "https://example.com/a.png"),
+            metadata("0xb", "Visit evil.xyz", "https://example.com/b.png"),
+            metadata("0xc", "Coin", "https://cdn.evil.xyz/c.png"),
+            metadata("0xd", "Coin", "ipfs://bafyscam/d.png"),
+            metadata("0xe", "Coin", "https://notevil.xyz/e.png"),
+        ];
+
+        let spam_assets = rules.classify(&fungible_asset_metadata, &[], &[], &[], &[]);
+        assert_eq!(assets(spam_assets), ["0xa", "0xb", "0xc", "0xd"]);
+    }
+
+    #[test]
+    fn test_airdrop_rule() {
+        let rules = rules(serde_json::json!({ "airdrop_receiver_threshold": 3 }));
+        let activities = [
+            deposit(1, "0xa", "0x10"),
+            deposit(1, "0xa", "0x11"),
+            deposit(1, "0xa", "0x12"),
+            // Receivers are counted per transaction
+            deposit(1, "0xb", "0x10"),
+            deposit(1, "0xb", "0x11"),
+            deposit(2, "0xb", "0x12"),
+            // Deposits to the same receiver are counted once
+            deposit(3, "0xc", "0x10"),
+            deposit(3, "0xc", "0x10"),
+            deposit(3, "0xc", "0x11"),
+        ];
+
+        let spam_assets = rules.classify(&[], &activities, &[], &[], &[]);
+        assert_eq!(assets(spam_assets), ["0xa"]);
+    }
+
+    #[test]
+    fn test_allow_and_deny_lists() {
+        let rules = rules(serde_json::json!({
+            "blocked_domains": ["evil.xyz"],
+            "allowed_assets": ["0xA"],
+            "denied_assets": ["0x1::scam::Coin"],
+        }));
+        assert_eq!(rules.listed_assets(), vec![
+            SpamAsset {
+                asset: "0x1::scam::Coin".to_string(),
+                is_spam: true,
+            },
+            SpamAsset {
+                asset: standardize_address("0xa"),
+                is_spam: false,
+            },
+        ]);
+
+        let fungible_asset_metadata = [metadata(
+            &standardize_address("0xa"),
+            "Coin",
+            "https://evil.xyz/a.png",
+        )];
+        assert!(rules
+            .classify(&fungible_asset_metadata, &[], &[], &[], &[])
+            .is_empty());
+    }
+}
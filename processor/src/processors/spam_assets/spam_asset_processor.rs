@@ -0,0 +1,213 @@
+use crate::{
+    config::{
+        db_config::DbConfig,
+        indexer_processor_config::{
+            IndexerProcessorConfig, QUERY_DEFAULT_RETRIES, QUERY_DEFAULT_RETRY_DELAY_MS,
+        },
+        processor_config::{DefaultProcessorConfig, ProcessorConfig},
+    },
+    processors::{
+        processor_status_saver::{
+            get_end_version, get_starting_version, PostgresProcessorStatusSaver,
+        },
+        spam_assets::{
+            spam_asset_extractor::SpamAssetExtractor,
+            spam_asset_model::SpamRules,
+            spam_asset_storer::{upsert_listed_assets, SpamAssetStorer},
+        },
+    },
+    MIGRATIONS,
+};
+use anyhow::Result;
+use libra2_indexer_processor_sdk::{
+    builder::ProcessorBuilder,
+    common_steps::{
+        TransactionStreamStep, VersionTrackerStep, DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+    },
+    libra2_indexer_transaction_stream::TransactionStreamConfig,
+    postgres::utils::{
+        checkpoint::PostgresChainIdChecker,
+        database::{new_db_pool, run_migrations, ArcDbPool},
+    },
+    traits::{processor_trait::ProcessorTrait, IntoRunnableStep},
+    utils::chain_id_check::check_or_update_chain_id,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpamAssetProcessorConfig {
+    #[serde(flatten)]
+    pub default_config: DefaultProcessorConfig,
+    #[serde(default = "SpamAssetProcessorConfig::default_query_retries")]
+    pub query_retries: u32,
+    #[serde(default = "SpamAssetProcessorConfig::default_query_retry_delay_ms")]
+    pub query_retry_delay_ms: u64,
+    // Regexes matched case-insensitively against the names and symbols of fungible assets and
+    // the names of collections and tokens
+    #[serde(default)]
+    pub name_patterns: Vec<String>,
+    // Regexes matched case-insensitively against the icon and project uris of fungible assets and
+    // the uris of collections and tokens
+    #[serde(default)]
+    pub uri_patterns: Vec<String>,
+    // Domains that flag an asset when a uri is on the domain or a subdomain of it, or when a name
+    // or symbol mentions it
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+    // Flag an asset when one transaction deposits it to at least this many accounts. Disabled if
+    // not set.
+    #[serde(default)]
+    pub airdrop_receiver_threshold: Option<usize>,
+    // Assets that are never spam, by asset type or collection or token id
+    #[serde(default)]
+    pub allowed_assets: Vec<String>,
+    // Assets that are always spam, by asset type or collection or token id
+    #[serde(default)]
+    pub denied_assets: Vec<String>,
+}
+
+impl SpamAssetProcessorConfig {
+    pub const fn default_query_retries() -> u32 {
+        QUERY_DEFAULT_RETRIES
+    }
+
+    pub const fn default_query_retry_delay_ms() -> u64 {
+        QUERY_DEFAULT_RETRY_DELAY_MS
+    }
+}
+
+pub struct SpamAssetProcessor {
+    pub config: IndexerProcessorConfig,
+    pub db_pool: ArcDbPool,
+}
+
+impl SpamAssetProcessor {
+    pub async fn new(config: IndexerProcessorConfig) -> Result<Self> {
+        match config.db_config {
+            DbConfig::PostgresConfig(ref postgres_config) => {
+                let conn_pool = new_db_pool(
+                    &postgres_config.connection_string,
+                    Some(postgres_config.db_pool_size),
+                )
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to create connection pool for PostgresConfig: {:?}",
+                        e
+                    )
+                })?;
+
+                Ok(Self {
+                    config,
+                    db_pool: conn_pool,
+                })
+            },
+            _ => Err(anyhow::anyhow!(
+                "Invalid db config for SpamAssetProcessor {:?}",
+                config.db_config
+            )),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProcessorTrait for SpamAssetProcessor {
+    fn name(&self) -> &'static str {
+        self.config.processor_config.name()
+    }
+
+    /// Flags spam fungible assets, collections and tokens in `spam_assets`, with the rules and
+    /// the allow and deny lists of the config.
+    async fn run_processor(&self) -> Result<()> {
+        let processor_config = match &self.config.processor_config {
+            ProcessorConfig::SpamAssetProcessor(processor_config) => processor_config,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid processor config for SpamAssetProcessor: {:?}",
+                    self.config.processor_config
+                ))
+            },
+        };
+        let spam_rules = SpamRules::new(processor_config)?;
+
+        // Run migrations
+        if let DbConfig::PostgresConfig(ref postgres_config) = self.config.db_config {
+            run_migrations(
+                postgres_config.connection_string.clone(),
+                self.db_pool.clone(),
+                MIGRATIONS,
+            )
+            .await;
+        }
+        upsert_listed_assets(
+            self.db_pool.clone(),
+            spam_rules.listed_assets(),
+            &processor_config.default_config.per_table_chunk_sizes,
+        )
+        .await?;
+
+        // Merge the starting version from config and the latest processed version from the DB
+        let (starting_version, ending_version) = (
+            get_starting_version(&self.config, self.db_pool.clone()).await?,
+            get_end_version(&self.config, self.db_pool.clone()).await?,
+        );
+
+        // Check and update the ledger chain id to ensure we're indexing the correct chain
+        check_or_update_chain_id(
+            &self.config.transaction_stream_config,
+            &PostgresChainIdChecker::new(self.db_pool.clone()),
+        )
+        .await?;
+
+        let channel_size = processor_config.default_config.channel_size;
+
+        // Define processor steps
+        let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+            starting_version,
+            request_ending_version: ending_version,
+            ..self.config.transaction_stream_config.clone()
+        })
+        .await?;
+        let spam_asset_extractor = SpamAssetExtractor::new(
+            spam_rules,
+            processor_config.query_retries,
+            processor_config.query_retry_delay_ms,
+            self.db_pool.clone(),
+        );
+        let spam_asset_storer = SpamAssetStorer::new(
+            self.db_pool.clone(),
+            processor_config.default_config.clone(),
+        );
+        let version_tracker = VersionTrackerStep::new(
+            PostgresProcessorStatusSaver::new(self.config.clone(), self.db_pool.clone()),
+            DEFAULT_UPDATE_PROCESSOR_STATUS_SECS,
+        );
+
+        // Connect processor steps together
+        let (_, buffer_receiver) = ProcessorBuilder::new_with_inputless_first_step(
+            transaction_stream.into_runnable_step(),
+        )
+        .connect_to(spam_asset_extractor.into_runnable_step(), channel_size)
+        .connect_to(spam_asset_storer.into_runnable_step(), channel_size)
+        .connect_to(version_tracker.into_runnable_step(), channel_size)
+        .end_and_return_output_receiver(channel_size);
+
+        // (Optional) Parse the results
+        loop {
+            match buffer_receiver.recv().await {
+                Ok(txn_context) => {
+                    debug!(
+                        "Finished classifying assets from versions [{:?}, {:?}]",
+                        txn_context.metadata.start_version, txn_context.metadata.end_version,
+                    );
+                },
+                Err(e) => {
+                    info!("No more transactions in channel: {:?}", e);
+                    break Ok(());
+                },
+            }
+        }
+    }
+}
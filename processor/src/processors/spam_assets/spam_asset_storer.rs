@@ -0,0 +1,127 @@
+use crate::{
+    config::processor_config::DefaultProcessorConfig,
+    processors::spam_assets::spam_asset_model::SpamAsset, schema,
+};
+use ahash::AHashMap;
+use anyhow::Result;
+use libra2_indexer_processor_sdk::{
+    postgres::utils::database::{execute_in_chunks, get_config_table_chunk_size, ArcDbPool},
+    traits::{async_step::AsyncRunType, AsyncStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::errors::ProcessorError,
+};
+use async_trait::async_trait;
+use diesel::{
+    pg::{upsert::excluded, Pg},
+    query_builder::QueryFragment,
+    ExpressionMethods,
+};
+
+/// Writes the allow and deny lists, overwriting what the rules flagged before.
+pub async fn upsert_listed_assets(
+    conn_pool: ArcDbPool,
+    listed_assets: Vec<SpamAsset>,
+    per_table_chunk_sizes: &AHashMap<String, usize>,
+) -> Result<()> {
+    execute_in_chunks(
+        conn_pool,
+        upsert_listed_assets_query,
+        &listed_assets,
+        get_config_table_chunk_size::<SpamAsset>("spam_assets", per_table_chunk_sizes),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to write allowed and denied assets: {e:?}"))
+}
+
+pub struct SpamAssetStorer
+where
+    Self: Sized + Send + 'static,
+{
+    conn_pool: ArcDbPool,
+    processor_config: DefaultProcessorConfig,
+}
+
+impl SpamAssetStorer {
+    pub fn new(conn_pool: ArcDbPool, processor_config: DefaultProcessorConfig) -> Self {
+        Self {
+            conn_pool,
+            processor_config,
+        }
+    }
+}
+
+#[async_trait]
+impl Processable for SpamAssetStorer {
+    type Input = Vec<SpamAsset>;
+    type Output = ();
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        input: TransactionContext<Vec<SpamAsset>>,
+    ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
+        let per_table_chunk_sizes: AHashMap<String, usize> =
+            self.processor_config.per_table_chunk_sizes.clone();
+
+        let sa = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_spam_assets_query,
+            &input.data,
+            get_config_table_chunk_size::<SpamAsset>("spam_assets", &per_table_chunk_sizes),
+        );
+
+        match sa.await {
+            Ok(_) => {},
+            Err(e) => {
+                return Err(ProcessorError::DBStoreError {
+                    message: format!(
+                        "Failed to store versions {} to {}: {:?}",
+                        input.metadata.start_version, input.metadata.end_version, e,
+                    ),
+                    query: None,
+                })
+            },
+        }
+
+        Ok(Some(TransactionContext {
+            data: (),
+            metadata: input.metadata,
+        }))
+    }
+}
+
+impl NamedStep for SpamAssetStorer {
+    fn name(&self) -> String {
+        "spam_asset_storer".to_string()
+    }
+}
+
+impl AsyncStep for SpamAssetStorer {}
+
+/// Assets that already have a row keep it, so flags set by hand or from the lists aren't
+/// overwritten by the rules.
+fn insert_spam_assets_query(
+    items_to_insert: Vec<SpamAsset>,
+) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
+    use schema::spam_assets::dsl::*;
+
+    diesel::insert_into(schema::spam_assets::table)
+        .values(items_to_insert)
+        .on_conflict(asset)
+        .do_nothing()
+}
+
+fn upsert_listed_assets_query(
+    items_to_insert: Vec<SpamAsset>,
+) -> impl QueryFragment<Pg> + diesel::query_builder::QueryId + Send {
+    use schema::spam_assets::dsl::*;
+
+    diesel::insert_into(schema::spam_assets::table)
+        .values(items_to_insert)
+        .on_conflict(asset)
+        .do_update()
+        .set((
+            is_spam.eq(excluded(is_spam)),
+            last_updated.eq(diesel::dsl::now),
+        ))
+}
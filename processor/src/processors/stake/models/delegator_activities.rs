@@ -4,7 +4,7 @@
 #![allow(clippy::extra_unused_lifetimes)]
 
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::stake::models::stake_utils::StakeEvent,
     schema::delegated_staking_activities,
     utils::counters::PROCESSOR_UNKNOWN_TYPE_COUNT,
@@ -169,6 +169,10 @@ impl NamedTable for ParquetDelegatedStakingActivity {
     const TABLE_NAME: &'static str = "delegated_staking_activities";
 }
 
+impl HasSchemaVersion for ParquetDelegatedStakingActivity {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl From<DelegatedStakingActivity> for ParquetDelegatedStakingActivity {
     fn from(base: DelegatedStakingActivity) -> Self {
         Self {
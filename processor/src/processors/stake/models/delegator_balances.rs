@@ -3,7 +3,7 @@
 // This is required because a diesel macro makes clippy sad
 #![allow(clippy::extra_unused_lifetimes)]
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         default::models::table_items::{PostgresTableItem, TableItem},
         stake::models::delegator_pools::{
@@ -604,6 +604,10 @@ impl NamedTable for ParquetCurrentDelegatorBalance {
     const TABLE_NAME: &'static str = "current_delegator_balances";
 }
 
+impl HasSchemaVersion for ParquetCurrentDelegatorBalance {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 #[derive(
     Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
 )]
@@ -624,6 +628,10 @@ impl NamedTable for ParquetDelegatorBalance {
     const TABLE_NAME: &'static str = "delegator_balances";
 }
 
+impl HasSchemaVersion for ParquetDelegatorBalance {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetDelegatorBalance {
     fn version(&self) -> i64 {
         self.transaction_version
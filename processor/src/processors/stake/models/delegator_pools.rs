@@ -6,6 +6,7 @@
 
 use super::stake_utils::{StakeResource, StakeTableItem};
 use crate::{
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     schema::{
         current_delegated_staking_pool_balances, delegated_staking_pool_balances,
         delegated_staking_pools,
@@ -13,6 +14,7 @@ use crate::{
     utils::counters::PROCESSOR_UNKNOWN_TYPE_COUNT,
 };
 use ahash::AHashMap;
+use allocative_derive::Allocative;
 use libra2_indexer_processor_sdk::{
     libra2_indexer_transaction_stream::utils::time::parse_timestamp,
    libra2_protos::transaction::v1::{
@@ -22,6 +24,7 @@ use libra2_indexer_processor_sdk::{
 };
 use bigdecimal::BigDecimal;
 use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
 use serde::{Deserialize, Serialize};
 
 type StakingPoolAddress = String;
@@ -366,3 +369,118 @@ impl From<CurrentDelegatorPoolBalance> for PostgresCurrentDelegatorPoolBalance {
         }
     }
 }
+
+// Parquet models
+
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetDelegatorPool {
+    pub staking_pool_address: String,
+    pub first_transaction_version: i64,
+}
+
+impl NamedTable for ParquetDelegatorPool {
+    const TABLE_NAME: &'static str = "delegated_staking_pools";
+}
+
+impl HasSchemaVersion for ParquetDelegatorPool {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetDelegatorPool {
+    fn version(&self) -> i64 {
+        self.first_transaction_version
+    }
+}
+
+impl From<DelegatorPool> for ParquetDelegatorPool {
+    fn from(base: DelegatorPool) -> Self {
+        Self {
+            staking_pool_address: base.staking_pool_address,
+            first_transaction_version: base.first_transaction_version,
+        }
+    }
+}
+
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetDelegatorPoolBalance {
+    pub transaction_version: i64,
+    pub staking_pool_address: String,
+    pub total_coins: String,                    // BigDecimal
+    pub total_shares: String,                   // BigDecimal
+    pub operator_commission_percentage: String, // BigDecimal
+    pub inactive_table_handle: String,
+    pub active_table_handle: String,
+}
+
+impl NamedTable for ParquetDelegatorPoolBalance {
+    const TABLE_NAME: &'static str = "delegated_staking_pool_balances";
+}
+
+impl HasSchemaVersion for ParquetDelegatorPoolBalance {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetDelegatorPoolBalance {
+    fn version(&self) -> i64 {
+        self.transaction_version
+    }
+}
+
+impl From<DelegatorPoolBalance> for ParquetDelegatorPoolBalance {
+    fn from(base: DelegatorPoolBalance) -> Self {
+        Self {
+            transaction_version: base.transaction_version,
+            staking_pool_address: base.staking_pool_address,
+            total_coins: base.total_coins.to_string(),
+            total_shares: base.total_shares.to_string(),
+            operator_commission_percentage: base.operator_commission_percentage.to_string(),
+            inactive_table_handle: base.inactive_table_handle,
+            active_table_handle: base.active_table_handle,
+        }
+    }
+}
+
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetCurrentDelegatorPoolBalance {
+    pub staking_pool_address: String,
+    pub total_coins: String,  // BigDecimal
+    pub total_shares: String, // BigDecimal
+    pub last_transaction_version: i64,
+    pub operator_commission_percentage: String, // BigDecimal
+    pub inactive_table_handle: String,
+    pub active_table_handle: String,
+}
+
+impl NamedTable for ParquetCurrentDelegatorPoolBalance {
+    const TABLE_NAME: &'static str = "current_delegated_staking_pool_balances";
+}
+
+impl HasSchemaVersion for ParquetCurrentDelegatorPoolBalance {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetCurrentDelegatorPoolBalance {
+    fn version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
+impl From<CurrentDelegatorPoolBalance> for ParquetCurrentDelegatorPoolBalance {
+    fn from(base: CurrentDelegatorPoolBalance) -> Self {
+        Self {
+            staking_pool_address: base.staking_pool_address,
+            total_coins: base.total_coins.to_string(),
+            total_shares: base.total_shares.to_string(),
+            last_transaction_version: base.last_transaction_version,
+            operator_commission_percentage: base.operator_commission_percentage.to_string(),
+            inactive_table_handle: base.inactive_table_handle,
+            active_table_handle: base.active_table_handle,
+        }
+    }
+}
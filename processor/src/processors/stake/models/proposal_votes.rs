@@ -5,7 +5,7 @@
 #![allow(clippy::extra_unused_lifetimes)]
 
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::stake::models::stake_utils::StakeEvent,
     schema::proposal_votes,
     utils::counters::PROCESSOR_UNKNOWN_TYPE_COUNT,
@@ -96,6 +96,10 @@ impl NamedTable for ParquetProposalVote {
     const TABLE_NAME: &'static str = "proposal_votes";
 }
 
+impl HasSchemaVersion for ParquetProposalVote {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetProposalVote {
     fn version(&self) -> i64 {
         self.transaction_version
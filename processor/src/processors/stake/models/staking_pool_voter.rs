@@ -5,15 +5,19 @@
 #![allow(clippy::extra_unused_lifetimes)]
 
 use crate::{
-    processors::stake::models::stake_utils::StakeResource, schema::current_staking_pool_voter,
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
+    processors::stake::models::stake_utils::StakeResource,
+    schema::current_staking_pool_voter,
 };
 use ahash::AHashMap;
+use allocative_derive::Allocative;
 use libra2_indexer_processor_sdk::{
     libra2_indexer_transaction_stream::utils::time::parse_timestamp,
    libra2_protos::transaction::v1::{write_set_change::Change, Transaction},
     utils::convert::standardize_address,
 };
 use field_count::FieldCount;
+use parquet_derive::ParquetRecordWriter;
 use serde::{Deserialize, Serialize};
 
 type StakingPoolAddress = String;
@@ -82,3 +86,42 @@ impl From<CurrentStakingPoolVoter> for PostgresCurrentStakingPoolVoter {
         }
     }
 }
+
+// Parquet models
+#[derive(
+    Allocative, Clone, Debug, Default, Deserialize, FieldCount, ParquetRecordWriter, Serialize,
+)]
+pub struct ParquetCurrentStakingPoolVoter {
+    pub staking_pool_address: String,
+    pub voter_address: String,
+    pub last_transaction_version: i64,
+    pub operator_address: String,
+    #[allocative(skip)]
+    pub block_timestamp: chrono::NaiveDateTime,
+}
+
+impl NamedTable for ParquetCurrentStakingPoolVoter {
+    const TABLE_NAME: &'static str = "current_staking_pool_voter";
+}
+
+impl HasSchemaVersion for ParquetCurrentStakingPoolVoter {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl HasVersion for ParquetCurrentStakingPoolVoter {
+    fn version(&self) -> i64 {
+        self.last_transaction_version
+    }
+}
+
+impl From<CurrentStakingPoolVoter> for ParquetCurrentStakingPoolVoter {
+    fn from(base: CurrentStakingPoolVoter) -> Self {
+        Self {
+            staking_pool_address: base.staking_pool_address,
+            voter_address: base.voter_address,
+            last_transaction_version: base.last_transaction_version,
+            operator_address: base.operator_address,
+            block_timestamp: base.block_timestamp,
+        }
+    }
+}
@@ -6,7 +6,7 @@
 #![allow(clippy::unused_unit)]
 
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::token_v2::token_models::{
         token_utils::TokenWriteSet,
         tokens::{TableHandleToOwner, TokenV1AggregatedEventsMapping},
@@ -278,6 +278,10 @@ impl NamedTable for ParquetCurrentTokenPendingClaim {
     const TABLE_NAME: &'static str = "current_token_pending_claims";
 }
 
+impl HasSchemaVersion for ParquetCurrentTokenPendingClaim {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCurrentTokenPendingClaim {
     fn version(&self) -> i64 {
         self.last_transaction_version
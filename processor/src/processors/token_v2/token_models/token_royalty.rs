@@ -6,7 +6,7 @@
 #![allow(clippy::unused_unit)]
 
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::token_v2::token_models::token_utils::TokenWriteSet,
     schema::current_token_royalty_v1,
 };
@@ -117,6 +117,10 @@ impl NamedTable for ParquetCurrentTokenRoyaltyV1 {
     const TABLE_NAME: &'static str = "current_token_royalties_v1";
 }
 
+impl HasSchemaVersion for ParquetCurrentTokenRoyaltyV1 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCurrentTokenRoyaltyV1 {
     fn version(&self) -> i64 {
         self.last_transaction_version
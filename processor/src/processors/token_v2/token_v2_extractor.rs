@@ -4,9 +4,10 @@ use crate::processors::token_v2::{
         token_royalty::PostgresCurrentTokenRoyaltyV1, tokens::TableMetadataForToken,
     },
     token_v2_models::{
-        v2_collections::CurrentCollectionV2, v2_token_activities::PostgresTokenActivityV2,
-        v2_token_datas::PostgresCurrentTokenDataV2,
-        v2_token_ownerships::PostgresCurrentTokenOwnershipV2,
+        v2_collections::{CollectionV2, CurrentCollectionV2},
+        v2_token_activities::PostgresTokenActivityV2,
+        v2_token_datas::{PostgresCurrentTokenDataV2, PostgresTokenDataV2},
+        v2_token_ownerships::{PostgresCurrentTokenOwnershipV2, PostgresTokenOwnershipV2},
     },
     token_v2_processor_helpers::parse_v2_token,
 };
@@ -43,6 +44,9 @@ impl TokenV2Extractor {
 impl Processable for TokenV2Extractor {
     type Input = Vec<Transaction>;
     type Output = (
+        Vec<CollectionV2>,
+        Vec<PostgresTokenDataV2>,
+        Vec<PostgresTokenOwnershipV2>,
         Vec<CurrentCollectionV2>,
         Vec<PostgresCurrentTokenDataV2>,
         Vec<PostgresCurrentTokenDataV2>,
@@ -60,6 +64,9 @@ impl Processable for TokenV2Extractor {
     ) -> Result<
         Option<
             TransactionContext<(
+                Vec<CollectionV2>,
+                Vec<PostgresTokenDataV2>,
+                Vec<PostgresTokenOwnershipV2>,
                 Vec<CurrentCollectionV2>,
                 Vec<PostgresCurrentTokenDataV2>,
                 Vec<PostgresCurrentTokenDataV2>,
@@ -91,12 +98,12 @@ impl Processable for TokenV2Extractor {
             query_retry_delay_ms: self.query_retry_delay_ms,
         };
 
-        // Token v2 processor only writes to current tables. If you need to write to non-current
-        // tables, modify TokenV2Storer step to include the tables you want to write to.
+        // The history tables are opt in. TokenV2Storer only writes them if they're in
+        // tables_to_write.
         let (
-            _,
-            _,
-            _,
+            collections_v2,
+            raw_token_datas_v2,
+            raw_token_ownerships_v2,
             current_collections_v2,
             raw_current_token_datas_v2,
             raw_current_deleted_token_datas_v2,
@@ -113,6 +120,16 @@ impl Processable for TokenV2Extractor {
         )
         .await;
 
+        let postgres_token_datas_v2: Vec<PostgresTokenDataV2> = raw_token_datas_v2
+            .into_iter()
+            .map(PostgresTokenDataV2::from)
+            .collect();
+
+        let postgres_token_ownerships_v2: Vec<PostgresTokenOwnershipV2> = raw_token_ownerships_v2
+            .into_iter()
+            .map(PostgresTokenOwnershipV2::from)
+            .collect();
+
         let postgres_current_token_claims: Vec<PostgresCurrentTokenPendingClaim> =
             raw_current_token_claims
                 .into_iter()
@@ -156,6 +173,9 @@ impl Processable for TokenV2Extractor {
 
         Ok(Some(TransactionContext {
             data: (
+                collections_v2,
+                postgres_token_datas_v2,
+                postgres_token_ownerships_v2,
                 current_collections_v2,
                 postgres_current_token_datas_v2,
                 postgress_current_deleted_token_datas_v2,
@@ -7,7 +7,7 @@
 
 use crate::{
     db::resources::FromWriteResource,
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         objects::v2_object_utils::ObjectAggregatedDataMapping,
         token_v2::{
@@ -379,6 +379,10 @@ impl NamedTable for ParquetCollectionV2 {
     const TABLE_NAME: &'static str = "collections_v2";
 }
 
+impl HasSchemaVersion for ParquetCollectionV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCollectionV2 {
     fn version(&self) -> i64 {
         self.txn_version
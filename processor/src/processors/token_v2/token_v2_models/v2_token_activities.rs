@@ -6,7 +6,7 @@
 #![allow(clippy::unused_unit)]
 
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         objects::v2_object_utils::ObjectAggregatedDataMapping,
         token_v2::{
@@ -439,6 +439,10 @@ impl NamedTable for ParquetTokenActivityV2 {
     const TABLE_NAME: &'static str = "token_activities_v2";
 }
 
+impl HasSchemaVersion for ParquetTokenActivityV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetTokenActivityV2 {
     fn version(&self) -> i64 {
         self.txn_version
@@ -7,7 +7,7 @@
 
 use crate::{
     db::resources::FromWriteResource,
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         objects::v2_object_utils::ObjectAggregatedDataMapping,
         token_v2::{
@@ -17,7 +17,7 @@ use crate::{
             },
         },
     },
-    schema::current_token_datas_v2,
+    schema::{current_token_datas_v2, token_datas_v2},
 };
 use allocative_derive::Allocative;
 use anyhow::Context;
@@ -328,6 +328,10 @@ impl NamedTable for ParquetTokenDataV2 {
     const TABLE_NAME: &'static str = "token_datas_v2";
 }
 
+impl HasSchemaVersion for ParquetTokenDataV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetTokenDataV2 {
     fn version(&self) -> i64 {
         self.txn_version
@@ -383,6 +387,10 @@ impl NamedTable for ParquetCurrentTokenDataV2 {
     const TABLE_NAME: &'static str = "current_token_datas_v2";
 }
 
+impl HasSchemaVersion for ParquetCurrentTokenDataV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCurrentTokenDataV2 {
     fn version(&self) -> i64 {
         self.last_transaction_version
@@ -424,6 +432,52 @@ impl From<CurrentTokenDataV2> for ParquetCurrentTokenDataV2 {
 // PK of current_token_datas_v2, i.e. token_data_id
 pub type CurrentTokenDataV2PK = String;
 
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version, write_set_change_index))]
+#[diesel(table_name = token_datas_v2)]
+pub struct PostgresTokenDataV2 {
+    pub transaction_version: i64,
+    pub write_set_change_index: i64,
+    pub token_data_id: String,
+    pub collection_id: String,
+    pub token_name: String,
+    pub maximum: Option<BigDecimal>,
+    pub supply: Option<BigDecimal>,
+    pub largest_property_version_v1: Option<BigDecimal>,
+    pub token_uri: String,
+    pub token_properties: serde_json::Value,
+    pub description: String,
+    pub token_standard: String,
+    pub is_fungible_v2: Option<bool>,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+    // Deprecated, but still here for backwards compatibility
+    pub decimals: Option<i64>,
+    pub is_deleted_v2: Option<bool>,
+}
+
+impl From<TokenDataV2> for PostgresTokenDataV2 {
+    fn from(raw_item: TokenDataV2) -> Self {
+        Self {
+            transaction_version: raw_item.transaction_version,
+            write_set_change_index: raw_item.write_set_change_index,
+            token_data_id: raw_item.token_data_id,
+            collection_id: raw_item.collection_id,
+            token_name: raw_item.token_name,
+            maximum: raw_item.maximum,
+            supply: raw_item.supply,
+            largest_property_version_v1: raw_item.largest_property_version_v1,
+            token_uri: raw_item.token_uri,
+            token_properties: raw_item.token_properties,
+            description: raw_item.description,
+            token_standard: raw_item.token_standard,
+            is_fungible_v2: raw_item.is_fungible_v2,
+            transaction_timestamp: raw_item.transaction_timestamp,
+            decimals: raw_item.decimals,
+            is_deleted_v2: raw_item.is_deleted_v2,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
 #[diesel(primary_key(token_data_id))]
 #[diesel(table_name = current_token_datas_v2)]
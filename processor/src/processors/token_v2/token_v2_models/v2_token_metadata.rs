@@ -7,7 +7,7 @@
 
 use crate::{
     db::resources::{COIN_ADDR, TOKEN_ADDR, TOKEN_V2_ADDR},
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         default::models::move_resources::MoveResource,
         objects::v2_object_utils::ObjectAggregatedDataMapping,
@@ -133,6 +133,10 @@ impl NamedTable for ParquetCurrentTokenV2Metadata {
     const TABLE_NAME: &'static str = "current_token_v2_metadata";
 }
 
+impl HasSchemaVersion for ParquetCurrentTokenV2Metadata {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCurrentTokenV2Metadata {
     fn version(&self) -> i64 {
         self.last_transaction_version
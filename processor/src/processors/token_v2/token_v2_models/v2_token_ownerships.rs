@@ -7,7 +7,7 @@
 
 use crate::{
     db::resources::FromWriteResource,
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::{
         objects::v2_object_utils::{ObjectAggregatedDataMapping, ObjectWithMetadata},
         token_v2::{
@@ -23,7 +23,7 @@ use crate::{
             },
         },
     },
-    schema::current_token_ownerships_v2,
+    schema::{current_token_ownerships_v2, token_ownerships_v2},
 };
 use ahash::AHashMap;
 use allocative_derive::Allocative;
@@ -710,6 +710,10 @@ impl NamedTable for ParquetTokenOwnershipV2 {
     const TABLE_NAME: &'static str = "token_ownerships_v2";
 }
 
+impl HasSchemaVersion for ParquetTokenOwnershipV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetTokenOwnershipV2 {
     fn version(&self) -> i64 {
         self.txn_version
@@ -762,6 +766,10 @@ impl NamedTable for ParquetCurrentTokenOwnershipV2 {
     const TABLE_NAME: &'static str = "current_token_ownerships_v2";
 }
 
+impl HasSchemaVersion for ParquetCurrentTokenOwnershipV2 {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetCurrentTokenOwnershipV2 {
     fn version(&self) -> i64 {
         self.last_transaction_version
@@ -856,3 +864,45 @@ impl From<CurrentTokenOwnershipV2> for PostgresCurrentTokenOwnershipV2 {
         }
     }
 }
+
+/// This is the postgres version of TokenOwnershipV2
+#[derive(Clone, Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version, write_set_change_index))]
+#[diesel(table_name = token_ownerships_v2)]
+pub struct PostgresTokenOwnershipV2 {
+    pub transaction_version: i64,
+    pub write_set_change_index: i64,
+    pub token_data_id: String,
+    pub property_version_v1: BigDecimal,
+    pub owner_address: Option<String>,
+    pub storage_id: String,
+    pub amount: BigDecimal,
+    pub table_type_v1: Option<String>,
+    pub token_properties_mutated_v1: Option<serde_json::Value>,
+    pub is_soulbound_v2: Option<bool>,
+    pub token_standard: String,
+    pub is_fungible_v2: Option<bool>,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+    pub non_transferrable_by_owner: Option<bool>,
+}
+
+impl From<TokenOwnershipV2> for PostgresTokenOwnershipV2 {
+    fn from(raw_item: TokenOwnershipV2) -> Self {
+        Self {
+            transaction_version: raw_item.transaction_version,
+            write_set_change_index: raw_item.write_set_change_index,
+            token_data_id: raw_item.token_data_id,
+            property_version_v1: raw_item.property_version_v1,
+            owner_address: raw_item.owner_address,
+            storage_id: raw_item.storage_id,
+            amount: raw_item.amount,
+            table_type_v1: raw_item.table_type_v1,
+            token_properties_mutated_v1: raw_item.token_properties_mutated_v1,
+            is_soulbound_v2: raw_item.is_soulbound_v2,
+            token_standard: raw_item.token_standard,
+            is_fungible_v2: raw_item.is_fungible_v2,
+            transaction_timestamp: raw_item.transaction_timestamp,
+            non_transferrable_by_owner: raw_item.non_transferrable_by_owner,
+        }
+    }
+}
@@ -6,19 +6,22 @@ use crate::{
             token_royalty::PostgresCurrentTokenRoyaltyV1,
         },
         token_v2_models::{
-            v2_collections::CurrentCollectionV2, v2_token_activities::PostgresTokenActivityV2,
-            v2_token_datas::PostgresCurrentTokenDataV2,
-            v2_token_ownerships::PostgresCurrentTokenOwnershipV2,
+            v2_collections::{CollectionV2, CurrentCollectionV2},
+            v2_token_activities::PostgresTokenActivityV2,
+            v2_token_datas::{PostgresCurrentTokenDataV2, PostgresTokenDataV2},
+            v2_token_ownerships::{PostgresCurrentTokenOwnershipV2, PostgresTokenOwnershipV2},
         },
         token_v2_processor::TokenV2ProcessorConfig,
         token_v2_processor_queries::{
-            insert_current_collections_v2_query, insert_current_deleted_token_datas_v2_query,
+            insert_collections_v2_query, insert_current_collections_v2_query,
+            insert_current_deleted_token_datas_v2_query,
             insert_current_deleted_token_ownerships_v2_query, insert_current_token_claims_query,
             insert_current_token_datas_v2_query, insert_current_token_ownerships_v2_query,
             insert_current_token_royalties_v1_query, insert_token_activities_v2_query,
+            insert_token_datas_v2_query, insert_token_ownerships_v2_query,
         },
     },
-    utils::table_flags::{filter_data, TableFlags},
+    utils::table_flags::{filter_data, filter_opt_in_data, TableFlags},
 };
 use ahash::AHashMap;
 use anyhow::Result;
@@ -30,7 +33,6 @@ use libra2_indexer_processor_sdk::{
 };
 use async_trait::async_trait;
 
-// TODO: Add table opt in filters
 pub struct TokenV2Storer
 where
     Self: Sized + Send + 'static,
@@ -57,6 +59,9 @@ impl TokenV2Storer {
 #[async_trait]
 impl Processable for TokenV2Storer {
     type Input = (
+        Vec<CollectionV2>,
+        Vec<PostgresTokenDataV2>,
+        Vec<PostgresTokenOwnershipV2>,
         Vec<CurrentCollectionV2>,
         Vec<PostgresCurrentTokenDataV2>,
         Vec<PostgresCurrentTokenDataV2>,
@@ -72,6 +77,9 @@ impl Processable for TokenV2Storer {
     async fn process(
         &mut self,
         input: TransactionContext<(
+            Vec<CollectionV2>,
+            Vec<PostgresTokenDataV2>,
+            Vec<PostgresTokenOwnershipV2>,
             Vec<CurrentCollectionV2>,
             Vec<PostgresCurrentTokenDataV2>,
             Vec<PostgresCurrentTokenDataV2>,
@@ -83,6 +91,9 @@ impl Processable for TokenV2Storer {
         )>,
     ) -> Result<Option<TransactionContext<Self::Output>>, ProcessorError> {
         let (
+            collections_v2,
+            token_datas_v2,
+            token_ownerships_v2,
             current_collections_v2,
             current_token_datas_v2,
             current_deleted_token_datas_v2,
@@ -93,6 +104,23 @@ impl Processable for TokenV2Storer {
             current_token_claims,
         ) = input.data;
 
+        // History tables are only written when they're in tables_to_write
+        let collections_v2 = filter_opt_in_data(
+            &self.tables_to_write,
+            TableFlags::COLLECTIONS_V2,
+            collections_v2,
+        );
+        let token_datas_v2 = filter_opt_in_data(
+            &self.tables_to_write,
+            TableFlags::TOKEN_DATAS_V2,
+            token_datas_v2,
+        );
+        let token_ownerships_v2 = filter_opt_in_data(
+            &self.tables_to_write,
+            TableFlags::TOKEN_OWNERSHIPS_V2,
+            token_ownerships_v2,
+        );
+
         let (
             current_collections_v2,
             current_token_datas_v2,
@@ -119,6 +147,30 @@ impl Processable for TokenV2Storer {
             .per_table_chunk_sizes
             .clone();
 
+        let c_v2 = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_collections_v2_query,
+            &collections_v2,
+            get_config_table_chunk_size::<CollectionV2>("collections_v2", &per_table_chunk_sizes),
+        );
+        let td_v2 = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_token_datas_v2_query,
+            &token_datas_v2,
+            get_config_table_chunk_size::<PostgresTokenDataV2>(
+                "token_datas_v2",
+                &per_table_chunk_sizes,
+            ),
+        );
+        let to_v2 = execute_in_chunks(
+            self.conn_pool.clone(),
+            insert_token_ownerships_v2_query,
+            &token_ownerships_v2,
+            get_config_table_chunk_size::<PostgresTokenOwnershipV2>(
+                "token_ownerships_v2",
+                &per_table_chunk_sizes,
+            ),
+        );
         let cc_v2 = execute_in_chunks(
             self.conn_pool.clone(),
             insert_current_collections_v2_query,
@@ -193,6 +245,9 @@ impl Processable for TokenV2Storer {
         );
 
         let (
+            c_v2_res,
+            td_v2_res,
+            to_v2_res,
             cc_v2_res,
             ctd_v2_res,
             cdtd_v2_res,
@@ -201,9 +256,14 @@ impl Processable for TokenV2Storer {
             ta_v2_res,
             ctr_v1_res,
             ctc_v1_res,
-        ) = tokio::join!(cc_v2, ctd_v2, cdtd_v2, cto_v2, cdto_v2, ta_v2, ctr_v1, ctc_v1);
+        ) = tokio::join!(
+            c_v2, td_v2, to_v2, cc_v2, ctd_v2, cdtd_v2, cto_v2, cdto_v2, ta_v2, ctr_v1, ctc_v1
+        );
 
         for res in [
+            c_v2_res,
+            td_v2_res,
+            to_v2_res,
             cc_v2_res,
             ctd_v2_res,
             cdtd_v2_res,
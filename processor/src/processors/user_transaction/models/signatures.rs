@@ -5,7 +5,7 @@
 
 use super::signature_utils::parent_signature_utils::from_parent_signature;
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     schema::signatures::{self},
 };
 use allocative_derive::Allocative;
@@ -123,6 +123,10 @@ impl NamedTable for ParquetSignature {
     const TABLE_NAME: &'static str = "signatures";
 }
 
+impl HasSchemaVersion for ParquetSignature {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetSignature {
     fn version(&self) -> i64 {
         self.txn_version
@@ -12,7 +12,7 @@ use super::{
     signatures::Signature,
 };
 use crate::{
-    parquet_processors::parquet_utils::util::{HasVersion, NamedTable},
+    parquet_processors::parquet_utils::util::{HasSchemaVersion, HasVersion, NamedTable},
     processors::fungible_asset::fungible_asset_models::v2_fungible_asset_utils::FeeStatement,
     schema::user_transactions,
 };
@@ -199,6 +199,10 @@ impl NamedTable for ParquetUserTransaction {
     const TABLE_NAME: &'static str = "user_transactions";
 }
 
+impl HasSchemaVersion for ParquetUserTransaction {
+    const SCHEMA_VERSION: u32 = 1;
+}
+
 impl HasVersion for ParquetUserTransaction {
     fn version(&self) -> i64 {
         self.txn_version
@@ -52,3 +52,33 @@ pub static PARQUET_BUFFER_SIZE_AFTER_UPLOAD: Lazy<IntGaugeVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Number of rows that failed a data consistency check of the audit command
+pub static AUDIT_MISMATCH_COUNT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_audit_mismatch_count",
+        "Number of rows that failed a data consistency check",
+        &["processor_name", "check"]
+    )
+    .unwrap()
+});
+
+/// Unix timestamp of the last completed audit
+pub static AUDIT_LAST_RUN_TIMESTAMP_SECS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_audit_last_run_timestamp_secs",
+        "Unix timestamp of the last completed audit",
+        &["processor_name"]
+    )
+    .unwrap()
+});
+
+/// Number of object or table owner chains the account transactions processor couldn't resolve
+pub static ACCOUNT_TRANSACTIONS_UNRESOLVED_OWNER_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_account_transactions_unresolved_owner_count",
+        "Number of owner chains that couldn't be resolved to an account",
+        &["reason"]
+    )
+    .unwrap()
+});
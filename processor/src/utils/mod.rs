@@ -1,2 +1,4 @@
 pub mod counters;
+pub mod raw_data_server;
 pub mod table_flags;
+pub mod transaction_fanout;
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use libra2_indexer_processor_sdk::libra2_protos::indexer::v1::raw_data_server::{
+    RawData, RawDataServer,
+};
+use tonic::{codec::CompressionEncoding, transport::Server};
+use tracing::error;
+use url::Url;
+
+/// Starts a gRPC server on localhost that serves `service` in place of the indexer gRPC data
+/// service, and returns its address to use as `indexer_grpc_data_service_address`.
+pub async fn serve_raw_data<S: RawData>(service: S) -> Result<Url> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = Url::parse(&format!("http://{}", listener.local_addr()?))?;
+    let incoming = Box::pin(futures::stream::unfold(listener, |listener| async move {
+        let stream = listener.accept().await.map(|(stream, _)| stream);
+        Some((stream, listener))
+    }));
+    let service = RawDataServer::new(service)
+        .accept_compressed(CompressionEncoding::Zstd)
+        .accept_compressed(CompressionEncoding::Gzip)
+        .send_compressed(CompressionEncoding::Zstd);
+
+    tokio::spawn(async move {
+        if let Err(e) = Server::builder()
+            .add_service(service)
+            .serve_with_incoming(incoming)
+            .await
+        {
+            error!("Local transaction server failed: {e:?}");
+        }
+    });
+    Ok(address)
+}
@@ -22,6 +22,8 @@ bitflags! {
         const CURRENT_UNIFIED_FUNGIBLE_ASSET_BALANCES = 1 << 15;
         const CURRENT_FUNGIBLE_ASSET_BALANCES_LEGACY = 1 << 16;
         const FUNGIBLE_ASSET_TO_COIN_MAPPINGS = 1 << 17;
+        const COIN_INFOS = 1 << 19;
+        const COIN_BALANCES = 1 << 20;
         // TODO:: Add new v1 to v2 fa mapping table when migrating fa processor
 
         // Objects Processor: 21-30
@@ -32,6 +34,7 @@ bitflags! {
         const CURRENT_ANS_LOOKUP_V2 = 1 << 31;
         const CURRENT_ANS_PRIMARY_NAME_V2 = 1 << 32;
         const ANS_LOOKUP_V2 = 1 << 33;
+        const ANS_RENEWAL_ACTIVITIES = 1 << 34;
 
         // Stake Processor: 41-50
         const DELEGATED_STAKING_ACTIVITIES = 1 << 41;
@@ -81,6 +84,12 @@ bitflags! {
         const AUTH_KEY_ACCOUNT_ADDRESSES = 1 << 111;
         const PUBLIC_KEY_AUTH_KEYS = 1 << 112;
         const GAS_FEES = 1 << 123;
+
+        // Nft Points Processor: 124
+        const NFT_POINTS = 1 << 124;
+
+        // Account Activity Processor: 125
+        const ACCOUNT_ACTIVITIES = 1 << 125;
     }
 }
 
@@ -109,6 +118,20 @@ pub fn filter_data<T>(tables_to_write: &TableFlags, flag: TableFlags, data: Vec<
     }
 }
 
+/// Like `filter_data`, but for opt-in tables that are only written when the tables_to_write set
+/// contains their flag. An empty tables_to_write set doesn't include them.
+pub fn filter_opt_in_data<T>(
+    tables_to_write: &TableFlags,
+    flag: TableFlags,
+    data: Vec<T>,
+) -> Vec<T> {
+    if tables_to_write.contains(flag) {
+        data
+    } else {
+        vec![]
+    }
+}
+
 /// Macro to filter multiple data sets with their corresponding table flags in one go
 #[macro_export]
 macro_rules! filter_datasets {
@@ -0,0 +1,613 @@
+//! Runs several processors from a single transaction stream.
+//!
+//! The stream is read once, from the lowest starting version of all processors, into a shared
+//! buffer. Every processor connects to its own gRPC server on localhost that serves the buffer in
+//! place of the indexer gRPC data service, so each processor keeps its own steps and checkpoint.
+
+use crate::{
+    config::{
+        db_config::DbConfig, indexer_processor_config::IndexerProcessorConfig,
+        processor_mode::ProcessorMode,
+    },
+    parquet_processors::{
+        clickhouse_utils::{
+            clickhouse_client::ClickhouseClient,
+            clickhouse_status_saver::{
+                create_clickhouse_metadata_tables, get_clickhouse_starting_version,
+            },
+        },
+        parquet_processor_status_saver::get_parquet_starting_version,
+    },
+    processors::processor_status_saver::get_starting_version,
+    utils::raw_data_server::serve_raw_data,
+    MIGRATIONS,
+};
+use anyhow::{Context, Result};
+use libra2_indexer_processor_sdk::{
+    builder::ProcessorBuilder,
+    common_steps::TransactionStreamStep,
+    libra2_indexer_transaction_stream::TransactionStreamConfig,
+    libra2_protos::{
+        indexer::v1::{raw_data_server::RawData, GetTransactionsRequest, TransactionsResponse},
+        transaction::v1::Transaction,
+    },
+    postgres::utils::database::{new_db_pool, run_migrations},
+    server_framework::RunnableConfig,
+    traits::{async_step::AsyncRunType, AsyncStep, IntoRunnableStep, NamedStep, Processable},
+    types::transaction_context::TransactionContext,
+    utils::{
+        chain_id_check::{check_or_update_chain_id, ChainIdChecker},
+        errors::ProcessorError,
+    },
+};
+use async_trait::async_trait;
+use futures::{stream::FuturesUnordered, Stream, StreamExt};
+use std::{
+    collections::{HashSet, VecDeque},
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::Notify;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+/// Maximum number of stream batches held in memory. The stream is paused while the buffer is full
+/// and the slowest processor still needs the oldest batch.
+const MAX_BUFFERED_BATCHES: usize = 100;
+const FANOUT_CHANNEL_SIZE: usize = 10;
+
+struct Batch {
+    start_version: u64,
+    end_version: u64,
+    transactions: Arc<Vec<Transaction>>,
+}
+
+struct BufferState {
+    batches: VecDeque<Batch>,
+    /// First version that is still buffered, or that will be buffered next.
+    oldest_version: u64,
+    /// Next version each consumer needs. Batches below all of them are dropped.
+    positions: Vec<u64>,
+    closed: bool,
+}
+
+impl BufferState {
+    fn evict(&mut self) {
+        let min_position = self.positions.iter().copied().min().unwrap_or(u64::MAX);
+        while let Some(batch) = self.batches.front() {
+            if batch.end_version >= min_position {
+                break;
+            }
+            self.oldest_version = batch.end_version + 1;
+            self.batches.pop_front();
+        }
+    }
+}
+
+/// Batches of the shared stream, kept until every consumer has read them.
+pub struct TransactionBuffer {
+    state: Mutex<BufferState>,
+    changed: Notify,
+    max_batches: usize,
+}
+
+impl TransactionBuffer {
+    /// Creates a buffer for one consumer per starting version. The stream must start at the lowest
+    /// of them.
+    pub fn new(starting_versions: Vec<u64>, max_batches: usize) -> Self {
+        Self {
+            state: Mutex::new(BufferState {
+                batches: VecDeque::new(),
+                oldest_version: starting_versions.iter().copied().min().unwrap_or(0),
+                positions: starting_versions,
+                closed: false,
+            }),
+            changed: Notify::new(),
+            max_batches,
+        }
+    }
+
+    /// Appends a batch from the stream, waiting while the buffer is full.
+    pub async fn push(&self, start_version: u64, end_version: u64, transactions: Vec<Transaction>) {
+        let mut batch = Some(Batch {
+            start_version,
+            end_version,
+            transactions: Arc::new(transactions),
+        });
+        loop {
+            let changed = self.changed.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                state.evict();
+                if state.batches.len() < self.max_batches {
+                    state.batches.extend(batch.take());
+                    drop(state);
+                    self.changed.notify_waiters();
+                    return;
+                }
+            }
+            changed.await;
+        }
+    }
+
+    /// Marks the end of the stream. Consumers get the remaining batches and then the end.
+    pub fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.changed.notify_waiters();
+    }
+
+    fn set_position(&self, consumer: usize, version: u64) {
+        self.state.lock().unwrap().positions[consumer] = version;
+        self.changed.notify_waiters();
+    }
+
+    /// Stops keeping batches for a consumer that won't read anymore.
+    pub fn release(&self, consumer: usize) {
+        self.set_position(consumer, u64::MAX);
+    }
+
+    fn oldest_version(&self) -> u64 {
+        self.state.lock().unwrap().oldest_version
+    }
+
+    /// Returns the transactions from `from_version` up to `ending_version` of the next batch that
+    /// has any, along with the version after that batch. Waits for the stream if it hasn't
+    /// reached `from_version` yet, and returns `None` once there is nothing left to read.
+    async fn next(
+        &self,
+        mut from_version: u64,
+        ending_version: u64,
+    ) -> Result<Option<(Vec<Transaction>, u64)>, Status> {
+        loop {
+            let changed = self.changed.notified();
+            {
+                let state = self.state.lock().unwrap();
+                if from_version < state.oldest_version {
+                    return Err(Status::out_of_range(format!(
+                        "Version {from_version} is no longer buffered, the oldest buffered \
+                         version is {}",
+                        state.oldest_version
+                    )));
+                }
+                for batch in &state.batches {
+                    if from_version >= ending_version {
+                        return Ok(None);
+                    }
+                    if batch.end_version < from_version {
+                        continue;
+                    }
+                    let transactions: Vec<_> = batch
+                        .transactions
+                        .iter()
+                        .filter(|txn| txn.version >= from_version && txn.version < ending_version)
+                        .cloned()
+                        .collect();
+                    if !transactions.is_empty() {
+                        return Ok(Some((transactions, batch.end_version + 1)));
+                    }
+                    from_version = batch.end_version + 1;
+                }
+                if state.closed || from_version >= ending_version {
+                    return Ok(None);
+                }
+            }
+            changed.await;
+        }
+    }
+}
+
+/// Position of a consumer's stream in the buffer.
+struct ConsumerCursor {
+    buffer: Arc<TransactionBuffer>,
+    consumer: usize,
+    chain_id: u64,
+    next_version: u64,
+    ending_version: u64,
+    done: bool,
+}
+
+type TransactionsResponseStream =
+    Pin<Box<dyn Stream<Item = Result<TransactionsResponse, Status>> + Send>>;
+
+/// Serves the shared buffer to one consumer in place of the indexer gRPC data service.
+pub struct FanoutService {
+    buffer: Arc<TransactionBuffer>,
+    consumer: usize,
+    chain_id: u64,
+}
+
+#[tonic::async_trait]
+impl RawData for FanoutService {
+    type GetTransactionsStream = TransactionsResponseStream;
+
+    async fn get_transactions(
+        &self,
+        request: Request<GetTransactionsRequest>,
+    ) -> Result<Response<Self::GetTransactionsStream>, Status> {
+        let request = request.into_inner();
+        // Requests without a starting version only check the chain id
+        let starting_version = request
+            .starting_version
+            .unwrap_or_else(|| self.buffer.oldest_version());
+        self.buffer.set_position(self.consumer, starting_version);
+
+        let cursor = ConsumerCursor {
+            buffer: self.buffer.clone(),
+            consumer: self.consumer,
+            chain_id: self.chain_id,
+            next_version: starting_version,
+            ending_version: request
+                .transactions_count
+                .map_or(u64::MAX, |count| starting_version.saturating_add(count)),
+            done: false,
+        };
+        let responses = futures::stream::unfold(cursor, |mut cursor| async move {
+            if cursor.done {
+                return None;
+            }
+            match cursor
+                .buffer
+                .next(cursor.next_version, cursor.ending_version)
+                .await
+            {
+                Ok(Some((transactions, next_version))) => {
+                    cursor.next_version = next_version;
+                    cursor.buffer.set_position(cursor.consumer, next_version);
+                    let response = TransactionsResponse {
+                        transactions,
+                        chain_id: Some(cursor.chain_id),
+                        ..Default::default()
+                    };
+                    Some((Ok(response), cursor))
+                },
+                Ok(None) => {
+                    // The stream is closed and drained, so this consumer is done. A stream that
+                    // stops at its requested end keeps its position, since the processor may
+                    // still request the versions after it.
+                    if cursor.next_version < cursor.ending_version {
+                        cursor.buffer.release(cursor.consumer);
+                    }
+                    None
+                },
+                Err(status) => {
+                    cursor.done = true;
+                    Some((Err(status), cursor))
+                },
+            }
+        });
+        Ok(Response::new(Box::pin(responses)))
+    }
+}
+
+/// Copies every batch of the stream into the shared buffer.
+pub struct TransactionFanoutStep {
+    buffer: Arc<TransactionBuffer>,
+}
+
+#[async_trait]
+impl Processable for TransactionFanoutStep {
+    type Input = Vec<Transaction>;
+    type Output = ();
+    type RunType = AsyncRunType;
+
+    async fn process(
+        &mut self,
+        item: TransactionContext<Vec<Transaction>>,
+    ) -> Result<Option<TransactionContext<()>>, ProcessorError> {
+        self.buffer
+            .push(
+                item.metadata.start_version,
+                item.metadata.end_version,
+                item.data,
+            )
+            .await;
+        Ok(Some(TransactionContext {
+            data: (),
+            metadata: item.metadata,
+        }))
+    }
+}
+
+impl AsyncStep for TransactionFanoutStep {}
+
+impl NamedStep for TransactionFanoutStep {
+    fn name(&self) -> String {
+        "TransactionFanoutStep".to_string()
+    }
+}
+
+/// Keeps the chain id of the stream instead of checking it against a database.
+#[derive(Default)]
+struct ChainIdRecorder {
+    chain_id: Mutex<Option<u64>>,
+}
+
+#[async_trait]
+impl ChainIdChecker for ChainIdRecorder {
+    async fn save_chain_id(&self, chain_id: u64) -> Result<()> {
+        *self.chain_id.lock().unwrap() = Some(chain_id);
+        Ok(())
+    }
+
+    async fn get_chain_id(&self) -> Result<Option<u64>> {
+        Ok(*self.chain_id.lock().unwrap())
+    }
+}
+
+/// Get the version a processor will start from, the same way the processor does.
+async fn get_processor_starting_version(config: &IndexerProcessorConfig) -> Result<u64> {
+    let starting_version = match &config.db_config {
+        DbConfig::PostgresConfig(postgres_config) => {
+            let db_pool = new_db_pool(&postgres_config.connection_string, Some(1))
+                .await
+                .context("Failed to create connection pool for PostgresConfig")?;
+            run_migrations(
+                postgres_config.connection_string.clone(),
+                db_pool.clone(),
+                MIGRATIONS,
+            )
+            .await;
+            get_starting_version(config, db_pool).await?
+        },
+        DbConfig::ParquetConfig(parquet_config) => {
+            let db_pool = new_db_pool(&parquet_config.connection_string, Some(1))
+                .await
+                .context("Failed to create connection pool for ParquetConfig")?;
+            run_migrations(
+                parquet_config.connection_string.clone(),
+                db_pool.clone(),
+                MIGRATIONS,
+            )
+            .await;
+            get_parquet_starting_version(config, db_pool).await?
+        },
+        DbConfig::ClickhouseConfig(clickhouse_config) => {
+            let client = ClickhouseClient::new(clickhouse_config)?;
+            create_clickhouse_metadata_tables(&client).await?;
+            get_clickhouse_starting_version(config, &client).await?
+        },
+    };
+    Ok(starting_version.unwrap_or(0))
+}
+
+/// Runs `processor_config` and every entry of `additional_processor_configs` from one transaction
+/// stream, until all of them finish or one of them fails.
+///
+/// A processor that finishes its ending version stops holding back the stream for the others. If a
+/// processor fails, or stops while the stream has no end, the remaining processors are aborted.
+pub async fn run_fanout(config: &IndexerProcessorConfig) -> Result<()> {
+    let configs: Vec<_> = std::iter::once(&config.processor_config)
+        .chain(&config.additional_processor_configs)
+        .map(|processor_config| IndexerProcessorConfig {
+            processor_config: processor_config.clone(),
+            additional_processor_configs: vec![],
+            ..config.clone()
+        })
+        .collect();
+    let mut processor_names = HashSet::new();
+    for processor_config in &configs {
+        let processor_name = processor_config.processor_config.name();
+        if !processor_names.insert(processor_name) {
+            anyhow::bail!("{processor_name} is configured more than once");
+        }
+    }
+
+    let mut starting_versions = vec![];
+    for processor_config in &configs {
+        starting_versions.push(get_processor_starting_version(processor_config).await?);
+    }
+    let starting_version = starting_versions.iter().copied().min().unwrap_or(0);
+    info!(
+        processors = ?processor_names,
+        starting_version, "Starting processors from a shared transaction stream"
+    );
+
+    let chain_id_recorder = ChainIdRecorder::default();
+    check_or_update_chain_id(&config.transaction_stream_config, &chain_id_recorder).await?;
+    let chain_id = chain_id_recorder
+        .get_chain_id()
+        .await?
+        .context("Failed to get the chain id of the transaction stream")?;
+
+    let buffer = Arc::new(TransactionBuffer::new(
+        starting_versions,
+        MAX_BUFFERED_BATCHES,
+    ));
+    let transaction_stream = TransactionStreamStep::new(TransactionStreamConfig {
+        starting_version: Some(starting_version),
+        ..config.transaction_stream_config.clone()
+    })
+    .await?;
+    let fanout_step = TransactionFanoutStep {
+        buffer: buffer.clone(),
+    };
+    let (_, buffer_receiver) =
+        ProcessorBuilder::new_with_inputless_first_step(transaction_stream.into_runnable_step())
+            .connect_to(fanout_step.into_runnable_step(), FANOUT_CHANNEL_SIZE)
+            .end_and_return_output_receiver(FANOUT_CHANNEL_SIZE);
+    let stream_buffer = buffer.clone();
+    let stream_task = tokio::spawn(async move {
+        while buffer_receiver.recv().await.is_ok() {}
+        stream_buffer.close();
+    });
+
+    let mut processors = vec![];
+    for (consumer, processor_config) in configs.into_iter().enumerate() {
+        let address = serve_raw_data(FanoutService {
+            buffer: buffer.clone(),
+            consumer,
+            chain_id,
+        })
+        .await?;
+        processors.push(IndexerProcessorConfig {
+            transaction_stream_config: TransactionStreamConfig {
+                indexer_grpc_data_service_address: address,
+                // Leave it to the processor, so its chain id check reads the oldest buffered batch
+                starting_version: None,
+                ..processor_config.transaction_stream_config.clone()
+            },
+            ..processor_config
+        });
+    }
+
+    // Without an ending version the processors only stop on errors
+    let has_ending_version = !matches!(config.processor_mode, ProcessorMode::Default(_))
+        || config
+            .transaction_stream_config
+            .request_ending_version
+            .is_some();
+    let mut running: FuturesUnordered<_> = processors
+        .iter()
+        .enumerate()
+        .map(|(consumer, processor)| async move { (consumer, processor.run().await) })
+        .collect();
+    let mut result = Ok(());
+    while let Some((consumer, processor_result)) = running.next().await {
+        let processor_name = processors[consumer].processor_config.name();
+        buffer.release(consumer);
+        match processor_result {
+            Ok(()) if has_ending_version => info!(processor_name, "Processor finished"),
+            Ok(()) => {
+                result = Err(anyhow::anyhow!(
+                    "{processor_name} stopped before the end of the transaction stream"
+                ));
+                break;
+            },
+            Err(e) => {
+                result = Err(e.context(format!("{processor_name} failed")));
+                break;
+            },
+        }
+    }
+    // Dropping the processors that are still running aborts them
+    drop(running);
+    stream_task.abort();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn transactions(versions: std::ops::RangeInclusive<u64>) -> Vec<Transaction> {
+        versions
+            .map(|version| Transaction {
+                version,
+                ..Transaction::default()
+            })
+            .collect()
+    }
+
+    fn versions(transactions: &[Transaction]) -> Vec<u64> {
+        transactions.iter().map(|txn| txn.version).collect()
+    }
+
+    #[tokio::test]
+    async fn test_buffer_keeps_batches_until_every_consumer_read_them() {
+        let buffer = TransactionBuffer::new(vec![0, 5], 2);
+        buffer.push(0, 4, transactions(0..=4)).await;
+        buffer.push(5, 9, transactions(5..=9)).await;
+
+        // The buffer is full until consumer 0 has read the first batch
+        let push = buffer.push(10, 14, transactions(10..=14));
+        tokio::pin!(push);
+        assert!(tokio::time::timeout(Duration::from_millis(50), &mut push)
+            .await
+            .is_err());
+
+        let (batch, next_version) = buffer.next(2, u64::MAX).await.unwrap().unwrap();
+        assert_eq!(versions(&batch), vec![2, 3, 4]);
+        assert_eq!(next_version, 5);
+        buffer.set_position(0, next_version);
+        push.await;
+
+        assert!(buffer.next(4, u64::MAX).await.is_err());
+        let (batch, _) = buffer.next(5, 7).await.unwrap().unwrap();
+        assert_eq!(versions(&batch), vec![5, 6]);
+        assert!(buffer.next(7, 7).await.unwrap().is_none());
+
+        buffer.close();
+        let (batch, _) = buffer.next(10, u64::MAX).await.unwrap().unwrap();
+        assert_eq!(versions(&batch), vec![10, 11, 12, 13, 14]);
+        assert!(buffer.next(15, u64::MAX).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fanout_service_streams_from_requested_version() {
+        let buffer = Arc::new(TransactionBuffer::new(vec![0, 0], 10));
+        let service = FanoutService {
+            buffer: buffer.clone(),
+            consumer: 1,
+            chain_id: 4,
+        };
+        buffer.push(0, 4, transactions(0..=4)).await;
+        buffer.push(5, 9, transactions(5..=9)).await;
+        buffer.close();
+
+        let response = service
+            .get_transactions(Request::new(GetTransactionsRequest {
+                starting_version: Some(3),
+                transactions_count: Some(4),
+                ..GetTransactionsRequest::default()
+            }))
+            .await
+            .unwrap();
+        let responses: Vec<_> = response
+            .into_inner()
+            .map(|response| response.unwrap())
+            .collect()
+            .await;
+        assert_eq!(responses.len(), 2);
+        assert_eq!(versions(&responses[0].transactions), vec![3, 4]);
+        assert_eq!(versions(&responses[1].transactions), vec![5, 6]);
+        assert!(responses
+            .iter()
+            .all(|response| response.chain_id == Some(4)));
+
+        // Batches are kept from the next version the consumer asked for
+        assert_eq!(buffer.state.lock().unwrap().positions, vec![0, 10]);
+    }
+
+    #[tokio::test]
+    async fn test_finished_consumer_releases_the_buffer() {
+        let buffer = Arc::new(TransactionBuffer::new(vec![0, 0], 2));
+        let service = FanoutService {
+            buffer: buffer.clone(),
+            consumer: 1,
+            chain_id: 4,
+        };
+        buffer.push(0, 4, transactions(0..=4)).await;
+        buffer.close();
+
+        // Consumer 1 reads to the end of the stream
+        let response = service
+            .get_transactions(Request::new(GetTransactionsRequest {
+                starting_version: Some(0),
+                ..GetTransactionsRequest::default()
+            }))
+            .await
+            .unwrap();
+        let responses: Vec<_> = response.into_inner().collect().await;
+        assert_eq!(responses.len(), 1);
+        assert_eq!(buffer.state.lock().unwrap().positions, vec![0, u64::MAX]);
+    }
+
+    #[tokio::test]
+    async fn test_released_consumer_doesnt_hold_back_the_stream() {
+        let buffer = TransactionBuffer::new(vec![0, 0], 2);
+        buffer.push(0, 4, transactions(0..=4)).await;
+        buffer.push(5, 9, transactions(5..=9)).await;
+
+        // Consumer 1 stops early without reading anything, consumer 0 keeps going
+        buffer.release(1);
+        buffer.set_position(0, 5);
+        tokio::time::timeout(
+            Duration::from_millis(500),
+            buffer.push(10, 14, transactions(10..=14)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(buffer.oldest_version(), 5);
+    }
+}